@@ -2,6 +2,18 @@ lang01 = English
 lang02 = French
 start_game = Start game
 current_level = Level { $current_level }
+checkpoint = Checkpoint!
+select_level = Choose a level
+level_finished = (finished)
+level_locked = (locked)
+results_title = Level { $current_level } completed!
+results_time = Time: { $time }
+results_lives_lost = Lives lost: { $count }
+results_extra_lives = Extra lives: { $count }
+results_nuts = Nuts collected: { $count }
+results_keys = Keys collected: { $count }
+results_enigmas = Enigmas solved first time: { $first_try }, after retries: { $retried }
+results_continue = Continue
 hello-world = Hello world { $name }.
 mammals-question = Which of these animals is not a mammal?
 mammals = Lion, Elephant, Dolphin, Bat, Hippopotamus, Gorilla, Kangaroo, Otter, Whale, Platypus
@@ -2,6 +2,18 @@ lang01 = Anglais
 lang02 = Francais
 start_game = Commencer le jeu
 current_level = Niveau { $current_level }
+checkpoint = Point de contrôle !
+select_level = Choisir un niveau
+level_finished = (terminé)
+level_locked = (verrouillé)
+results_title = Niveau { $current_level } terminé !
+results_time = Temps : { $time }
+results_lives_lost = Vies perdues : { $count }
+results_extra_lives = Vies gagnées : { $count }
+results_nuts = Noisettes ramassées : { $count }
+results_keys = Clés ramassées : { $count }
+results_enigmas = Énigmes résolues du premier coup : { $first_try }, après plusieurs essais : { $retried }
+results_continue = Continuer
 hello-world = Salut monde { $name }.
 mammals-question = Par-mis ces a-ni-maux, le-quel n'est pas un mam-mi-fè-re ?
 mammals = Lion, Éléphant, Dauphin, Chauve-souris, Hippopotame, Gorille, Kangourou, Loutre, Baleine, Ornithorynque
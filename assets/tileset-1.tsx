@@ -0,0 +1,4 @@
+<?xml version="1.0" encoding="UTF-8"?>
+<tileset version="1.10" tiledversion="1.11.2" name="tileset-1" tilewidth="16" tileheight="16" tilecount="2030" columns="58">
+ <image source="tileset-1.png" width="928" height="560"/>
+</tileset>
@@ -12,13 +12,9 @@ pub struct RockRunAssets {
     #[asset(path = "locales/fr-FR/main.ftl.ron")]
     pub french_locale: Handle<BundleAsset>,
 
-    // Levels
-    #[asset(path = "level01.tmx")]
-    pub level01: Handle<helpers::tiled::TiledMap>,
-    #[asset(path = "level02.tmx")]
-    pub level02: Handle<helpers::tiled::TiledMap>,
-    #[asset(path = "level03.tmx")]
-    pub level03: Handle<helpers::tiled::TiledMap>,
+    // Levels, their maps and musics are loaded by the manifest
+    #[asset(path = "levels.ron")]
+    pub levels: Handle<helpers::level_manifest::LevelManifest>,
 
     // Fonts
     #[asset(path = "fonts/Cute_Dino.ttf")]
@@ -99,6 +95,8 @@ pub struct RockRunAssets {
     pub jump_sound: Handle<AudioSource>,
     #[asset(path = "sounds/hit.ogg")]
     pub hit_sound: Handle<AudioSource>,
+    #[asset(path = "sounds/splash.wav")]
+    pub splash_sound: Handle<AudioSource>,
     #[asset(path = "sounds/loose.ogg")]
     pub loose_sound: Handle<AudioSource>,
     #[asset(path = "sounds/pterodactyl.ogg")]
@@ -129,12 +127,4 @@ pub struct RockRunAssets {
     pub victory_sound: Handle<AudioSource>,
     #[asset(path = "sounds/eruption.ogg")]
     pub eruption_sound: Handle<AudioSource>,
-
-    // Music
-    #[asset(path = "musics/theme_01.ogg")]
-    pub music_level01: Handle<AudioSource>,
-    #[asset(path = "musics/theme_07.ogg")]
-    pub music_level02: Handle<AudioSource>,
-    #[asset(path = "musics/theme_03.ogg")]
-    pub music_level03: Handle<AudioSource>,
 }
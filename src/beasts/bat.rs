@@ -1,14 +1,17 @@
 use bevy::{audio::PlaybackMode, prelude::*};
 use bevy_rapier2d::{
     control::KinematicCharacterController, dynamics::RigidBody, geometry::Collider,
-    pipeline::QueryFilterFlags,
 };
 
 use crate::{
     assets::RockRunAssets,
+    collision_layers::CollisionLayer,
     collisions::CollisionSet,
-    coregame::state::AppState,
-    events::{Hit, PositionSensorCollisionStart, Restart},
+    coregame::{
+        state::AppState,
+        triggers::{SensorTrigger, TriggerHandlerAppExt, TriggerKind, TriggeredSensor},
+    },
+    events::{Hit, Restart, UnlockScreen},
     helpers::texture::cycle_texture,
     player::Player,
 };
@@ -53,11 +56,12 @@ pub struct BatPlugin;
 
 impl Plugin for BatPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::StartMenu), despawn_bat)
+        app.register_trigger_handler(TriggerKind::Bat, spawn_bat)
+            .add_systems(OnEnter(AppState::StartMenu), despawn_bat)
             .add_systems(OnEnter(AppState::FinishLevel), despawn_bat)
             .add_systems(
                 Update,
-                (move_bat, spawn_bat, despawn_bat_on_restart)
+                (move_bat, despawn_bat_on_restart)
                     .after(CollisionSet)
                     .run_if(in_state(AppState::GameRunning)),
             );
@@ -83,61 +87,65 @@ fn get_collider_shapes(y_mirror: bool) -> Vec<(Vec2, f32, Collider)> {
 }
 
 fn spawn_bat(
+    In(triggered_sensor): In<TriggeredSensor>,
     mut commands: Commands,
     rock_run_assets: Res<RockRunAssets>,
     mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
-    mut bat_sensor_collision: EventReader<PositionSensorCollisionStart>,
-) {
-    for collision_event in bat_sensor_collision.read() {
-        if !collision_event.sensor_name.contains("bat") {
-            return;
-        }
+) -> Result<()> {
+    let SensorTrigger::Bat {
+        spawn_pos,
+        exit_pos,
+    } = triggered_sensor.trigger
+    else {
+        return Ok(());
+    };
+    if !triggered_sensor.entered {
+        return Ok(());
+    }
 
-        let texture = rock_run_assets.bat.clone();
-        let layout = TextureAtlasLayout::from_grid(
-            UVec2::new(BAT_WIDTH as u32, BAT_HEIGHT as u32),
-            7,
-            2,
-            None,
-            None,
-        );
-        let texture_atlas_layout = texture_atlases.add(layout);
-
-        commands.spawn((
-            Sprite::from_atlas_image(
-                texture,
-                TextureAtlas {
-                    layout: texture_atlas_layout,
-                    index: 0,
-                },
-            ),
-            Transform {
-                scale: Vec3::splat(BAT_SCALE_FACTOR),
-                translation: collision_event.spawn_pos.extend(20.0),
-                ..default()
-            },
-            RigidBody::KinematicPositionBased,
-            AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
-            ChaseTimer(Timer::from_seconds(15.0, TimerMode::Once)),
-            Collider::compound(get_collider_shapes(false)),
-            KinematicCharacterController {
-                filter_flags: QueryFilterFlags::ONLY_FIXED,
-                ..default()
-            },
-            Bat {
-                exit_pos: collision_event.exit_pos,
-                current_movement: BatMovement::Fly(BatDirection::default()),
-            },
-        ));
+    let texture = rock_run_assets.bat.clone();
+    let layout = TextureAtlasLayout::from_grid(
+        UVec2::new(BAT_WIDTH as u32, BAT_HEIGHT as u32),
+        7,
+        2,
+        None,
+        None,
+    );
+    let texture_atlas_layout = texture_atlases.add(layout);
 
-        commands.spawn((
-            AudioPlayer::new(rock_run_assets.bat_sound.clone()),
-            PlaybackSettings {
-                mode: PlaybackMode::Despawn,
-                ..default()
+    commands.spawn((
+        Sprite::from_atlas_image(
+            texture,
+            TextureAtlas {
+                layout: texture_atlas_layout,
+                index: 0,
             },
-        ));
-    }
+        ),
+        Transform {
+            scale: Vec3::splat(BAT_SCALE_FACTOR),
+            translation: spawn_pos.extend(20.0),
+            ..default()
+        },
+        RigidBody::KinematicPositionBased,
+        AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
+        ChaseTimer(Timer::from_seconds(15.0, TimerMode::Once)),
+        Collider::compound(get_collider_shapes(false)),
+        CollisionLayer::Beast.controller(),
+        CollisionLayer::Beast.groups(),
+        Bat {
+            exit_pos,
+            current_movement: BatMovement::Fly(BatDirection::default()),
+        },
+    ));
+
+    commands.spawn((
+        AudioPlayer::new(rock_run_assets.bat_sound.clone()),
+        PlaybackSettings {
+            mode: PlaybackMode::Despawn,
+            ..default()
+        },
+    ));
+    Ok(())
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -158,7 +166,9 @@ fn move_bat(
     player_query: Query<&mut Transform, (With<Player>, Without<Bat>)>,
     hit: EventReader<Hit>,
     mut chase_timer: Query<&mut ChaseTimer>,
+    mut unlock_screen: EventWriter<UnlockScreen>,
 ) -> Result<()> {
+    let nb_bats = bat_query.iter().count();
     for (bat_entity, mut bat_collider, mut bat_controller, bat_pos, mut bat) in bat_query.iter_mut()
     {
         let player = player_query.single()?;
@@ -206,7 +216,8 @@ fn move_bat(
         let direction = if chase_timer.finished() {
             debug!("chase_timer finished");
             debug!("bat_pos: {:?}", bat_pos);
-            bat_controller.filter_flags = QueryFilterFlags::ONLY_KINEMATIC;
+            // The bat flies away through the terrain
+            *bat_controller = CollisionLayer::Beast.flying_controller();
             (bat.exit_pos - bat_pos).normalize() * BAT_SPEED * time.delta_secs()
         } else {
             (player_pos - bat_pos).normalize() * BAT_SPEED * time.delta_secs()
@@ -214,6 +225,10 @@ fn move_bat(
 
         if bat_pos.distance(bat.exit_pos) < 2.0 {
             commands.entity(bat_entity).despawn();
+            // The player got rid of the last bat
+            if nb_bats == 1 {
+                unlock_screen.write(UnlockScreen);
+            }
             return Ok(());
         }
 
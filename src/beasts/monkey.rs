@@ -1,14 +1,12 @@
-use bevy::{platform::collections::HashMap, prelude::*};
-use bevy_rapier2d::{
-    control::KinematicCharacterController, dynamics::RigidBody, geometry::Collider,
-    pipeline::QueryFilterFlags,
-};
+use bevy::prelude::*;
+use bevy_rapier2d::{dynamics::RigidBody, geometry::Collider};
 
 use crate::{
     assets::RockRunAssets,
+    collision_layers::CollisionLayer,
     collisions::CollisionSet,
     coregame::{
-        level::{CurrentLevel, Level},
+        entities::{EntitySpawn, EntitySpawnerAppExt, ResetOnRestart},
         state::AppState,
     },
     events::SmallRockAboutToRelease,
@@ -52,8 +50,7 @@ pub struct MonkeyPlugin;
 
 impl Plugin for MonkeyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::GameCreate), setup_monkey)
-            .add_systems(OnEnter(AppState::NextLevel), setup_monkey)
+        app.register_entity_spawner("monkey", spawn_monkey)
             .add_systems(OnEnter(AppState::StartMenu), despawn_monkey)
             .add_systems(OnEnter(AppState::FinishLevel), despawn_monkey)
             .add_systems(
@@ -65,19 +62,15 @@ impl Plugin for MonkeyPlugin {
     }
 }
 
-fn setup_monkey(
+/// Spawns a monkey, looking to the `direction` property ("left" or "right") and drawn with the
+/// `texture` property ("monkey" or "monkey2")
+fn spawn_monkey(
+    In(entity_spawn): In<EntitySpawn>,
     mut commands: Commands,
     rock_run_assets: Res<RockRunAssets>,
     mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
-    levels: Query<&Level, With<Level>>,
-    current_level: Res<CurrentLevel>,
-) {
-    info!("setup_monkey");
-
-    let level = levels
-        .iter()
-        .find(|level| level.id == current_level.id)
-        .unwrap();
+) -> Option<Entity> {
+    info!("spawn_monkey {}", entity_spawn.name);
 
     let layout = TextureAtlasLayout::from_grid(
         UVec2::new(MONKEY_WIDTH as u32, MONKEY_HEIGHT as u32),
@@ -87,64 +80,58 @@ fn setup_monkey(
         None,
     );
     let texture_atlas_layout = texture_atlases.add(layout);
-    let mut level_monkey: HashMap<u8, Vec<Monkey>> = HashMap::new();
-    level_monkey.insert(
-        2,
-        vec![
-            Monkey {
-                start_pos: level.map.tiled_to_bevy_coord(Vec2::new(1415.0, 705.0)),
-                current_movement: MonkeyMovement::Look(MonkeyDirection::Right),
-                initial_movement: MonkeyMovement::Look(MonkeyDirection::Right),
-                texture: rock_run_assets.monkey2.clone(),
-            },
-            Monkey {
-                start_pos: level.map.tiled_to_bevy_coord(Vec2::new(5650.0, 705.0)),
-                current_movement: MonkeyMovement::Look(MonkeyDirection::Left),
-                initial_movement: MonkeyMovement::Look(MonkeyDirection::Left),
-                texture: rock_run_assets.monkey.clone(),
-            },
-        ],
-    );
 
-    let monkeys = match level_monkey.get(&current_level.id) {
-        Some(positions) => positions,
-        None => return,
+    let direction = match entity_spawn.get_string("direction") {
+        Some("left") => MonkeyDirection::Left,
+        Some("right") | None => MonkeyDirection::Right,
+        Some(direction) => {
+            warn!(
+                "Monkey {}: unknown direction {}",
+                entity_spawn.name, direction
+            );
+            MonkeyDirection::default()
+        }
+    };
+    let texture = match entity_spawn.get_string("texture") {
+        Some("monkey2") => rock_run_assets.monkey2.clone(),
+        _ => rock_run_assets.monkey.clone(),
     };
 
-    for monkey in monkeys {
-        commands.spawn((
-            Sprite {
-                image: monkey.texture.clone(),
-                texture_atlas: Some(TextureAtlas {
-                    layout: texture_atlas_layout.clone(),
-                    index: 0,
-                }),
-                ..default()
-            },
-            Transform {
-                scale: Vec3::splat(MONKEY_SCALE_FACTOR),
-                translation: monkey.start_pos.extend(20.0),
-                ..default()
-            },
-            RigidBody::KinematicPositionBased,
-            AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
-            Collider::compound(vec![(
-                Vec2::new(0.0, -30.0),
-                0.0,
-                Collider::cuboid(MONKEY_WIDTH / 2.0, MONKEY_HEIGHT / 3.70),
-            )]),
-            KinematicCharacterController {
-                filter_flags: QueryFilterFlags::EXCLUDE_KINEMATIC,
-                ..default()
-            },
-            Monkey {
-                current_movement: monkey.current_movement,
-                initial_movement: monkey.current_movement,
-                start_pos: monkey.start_pos,
-                texture: monkey.texture.clone(),
-            },
-        ));
-    }
+    Some(
+        commands
+            .spawn((
+                Sprite {
+                    image: texture.clone(),
+                    texture_atlas: Some(TextureAtlas {
+                        layout: texture_atlas_layout,
+                        index: 0,
+                    }),
+                    ..default()
+                },
+                Transform {
+                    scale: Vec3::splat(MONKEY_SCALE_FACTOR),
+                    translation: entity_spawn.position.extend(20.0),
+                    ..default()
+                },
+                RigidBody::KinematicPositionBased,
+                AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
+                Collider::compound(vec![(
+                    Vec2::new(0.0, -30.0),
+                    0.0,
+                    Collider::cuboid(MONKEY_WIDTH / 2.0, MONKEY_HEIGHT / 3.70),
+                )]),
+                CollisionLayer::Beast.controller(),
+                CollisionLayer::Beast.groups(),
+                ResetOnRestart,
+                Monkey {
+                    current_movement: MonkeyMovement::Look(direction),
+                    initial_movement: MonkeyMovement::Look(direction),
+                    start_pos: entity_spawn.position,
+                    texture,
+                },
+            ))
+            .id(),
+    )
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -3,17 +3,20 @@ use bevy_rapier2d::{
     control::KinematicCharacterController,
     dynamics::{Ccd, GravityScale, RigidBody, Velocity},
     geometry::{ActiveCollisionTypes, Collider},
-    prelude::{CollisionGroups, Group, QueryFilterFlags},
 };
 use rand::{rng, seq::IndexedRandom};
 
 use crate::{
     WINDOW_HEIGHT, WINDOW_WIDTH,
     assets::RockRunAssets,
+    collision_layers::CollisionLayer,
     collisions::CollisionSet,
-    coregame::state::AppState,
+    coregame::{
+        state::AppState,
+        triggers::{SensorTrigger, TriggerHandlerAppExt, TriggerKind, TriggeredSensor},
+    },
     elements::rock::Rock,
-    events::{PositionSensorCollisionStart, Restart, StartGame},
+    events::{Restart, StartGame, UnlockScreen},
     helpers::texture::cycle_texture,
     player::Player,
 };
@@ -34,6 +37,10 @@ pub struct Pterodactyl {
     attack: bool,
 }
 
+/// Pterodactyls waiting to be spawned, one after the other
+#[derive(Resource, Default)]
+struct PterodactylsToSpawn(Vec<Pterodactyl>);
+
 #[derive(Component, Deref, DerefMut)]
 struct AnimationTimer(Timer);
 
@@ -66,7 +73,10 @@ pub struct PterodactylPlugin;
 
 impl Plugin for PterodactylPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::StartMenu), despawn_pterodactyl)
+        app.register_trigger_handler(TriggerKind::Pterodactyl, queue_pterodactyls)
+            .register_trigger_handler(TriggerKind::PterodactylAttack, queue_pterodactyls)
+            .register_trigger_handler(TriggerKind::PterodactylWave, queue_pterodactyls)
+            .add_systems(OnEnter(AppState::StartMenu), despawn_pterodactyl)
             .add_systems(OnEnter(AppState::FinishLevel), despawn_pterodactyl)
             .add_systems(
                 Update,
@@ -77,7 +87,8 @@ impl Plugin for PterodactylPlugin {
                 )
                     .after(CollisionSet)
                     .run_if(in_state(AppState::GameRunning)),
-            );
+            )
+            .insert_resource(PterodactylsToSpawn::default());
     }
 }
 
@@ -105,8 +116,7 @@ fn spawn_pterodactyl(
     time: Res<Time>,
     rock_run_assets: Res<RockRunAssets>,
     mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
-    mut pterodactyl_sensor_collision: EventReader<PositionSensorCollisionStart>,
-    mut pterodactyls: Local<Vec<Pterodactyl>>,
+    mut pterodactyls: ResMut<PterodactylsToSpawn>,
     mut spawn_timer: Local<Timer>,
     mut game_event: EventReader<StartGame>,
     mut restart_event: EventReader<Restart>,
@@ -114,20 +124,20 @@ fn spawn_pterodactyl(
     camera_query: Query<&mut Transform, (With<Camera2d>, Without<Player>)>,
 ) -> Result<()> {
     if !game_event.is_empty() {
-        pterodactyls.clear();
+        pterodactyls.0.clear();
         game_event.clear();
         return Ok(());
     }
 
     if !restart_event.is_empty() {
-        pterodactyls.clear();
+        pterodactyls.0.clear();
         restart_event.clear();
         return Ok(());
     }
 
     spawn_timer.tick(time.delta());
 
-    if !pterodactyls.is_empty() && spawn_timer.finished() {
+    if !pterodactyls.0.is_empty() && spawn_timer.finished() {
         let spawn_time_values = [0.3, 0.6, 1.0];
         let spawn_y_values = [-50.0, 0.0, 50.0];
         let mut rng = rng();
@@ -141,7 +151,7 @@ fn spawn_pterodactyl(
         );
         let texture_atlas_layout = texture_atlases.add(layout);
 
-        let mut pterodactyl = pterodactyls.pop().unwrap();
+        let mut pterodactyl = pterodactyls.0.pop().unwrap();
         let player_pos = player_query.single()?;
         let camera_pos = camera_query.single()?;
 
@@ -174,12 +184,8 @@ fn spawn_pterodactyl(
             ChaseTimer(Timer::from_seconds(20.0, TimerMode::Once)),
             ThrowTimer(Timer::from_seconds(0.3, TimerMode::Once)),
             Collider::compound(get_collider_shapes(false)),
-            KinematicCharacterController {
-                filter_flags: QueryFilterFlags::ONLY_KINEMATIC,
-                filter_groups: Some(CollisionGroups::new(Group::GROUP_2, Group::GROUP_2)),
-                ..default()
-            },
-            CollisionGroups::new(Group::GROUP_2, Group::GROUP_2),
+            CollisionLayer::Beast.flying_controller(),
+            CollisionLayer::Beast.groups(),
             pterodactyl,
         ));
 
@@ -196,58 +202,49 @@ fn spawn_pterodactyl(
             TimerMode::Once,
         );
     }
+    Ok(())
+}
 
-    for collision_event in pterodactyl_sensor_collision.read() {
-        if !collision_event.sensor_name.contains("pterodactyl") {
-            return Ok(());
-        }
-
-        *pterodactyls = build_pterodactyls_to_spawn(collision_event);
+fn queue_pterodactyls(
+    In(triggered_sensor): In<TriggeredSensor>,
+    mut pterodactyls: ResMut<PterodactylsToSpawn>,
+) -> Result<()> {
+    if triggered_sensor.entered {
+        pterodactyls.0 = build_pterodactyls_to_spawn(&triggered_sensor.trigger);
     }
     Ok(())
 }
 
-fn build_pterodactyls_to_spawn(collision_event: &PositionSensorCollisionStart) -> Vec<Pterodactyl> {
-    if collision_event.sensor_name.contains("pterodactyl_attack") {
-        return vec![Pterodactyl {
-            spawn_pos: Some(collision_event.spawn_pos),
-            exit_pos: collision_event.exit_pos,
+fn build_pterodactyls_to_spawn(trigger: &SensorTrigger) -> Vec<Pterodactyl> {
+    match *trigger {
+        SensorTrigger::PterodactylAttack {
+            spawn_pos,
+            exit_pos,
+        } => vec![Pterodactyl {
+            spawn_pos: Some(spawn_pos),
+            exit_pos,
             current_movement: PterodactylMovement::Fly(PterodactylDirection::Left),
             attack: true,
-        }];
-    }
-
-    if collision_event.sensor_name.contains("pterodactyl_wave") {
-        let mut pterodactyls = Vec::new();
-        let number_of_pterodactyls: usize = collision_event
-            .sensor_name
-            .split('_')
-            .nth(2)
-            .unwrap()
-            .parse()
-            .unwrap();
-
-        for _ in 0..number_of_pterodactyls {
-            pterodactyls.push(Pterodactyl {
+        }],
+        SensorTrigger::PterodactylWave { count, exit_pos } => (0..count)
+            .map(|_| Pterodactyl {
                 spawn_pos: None,
-                exit_pos: collision_event.exit_pos,
+                exit_pos,
                 current_movement: PterodactylMovement::Fly(PterodactylDirection::Left),
                 attack: true,
-            });
-        }
-        return pterodactyls;
-    }
-
-    if collision_event.sensor_name.contains("pterodactyl") {
-        return vec![Pterodactyl {
-            spawn_pos: Some(collision_event.spawn_pos),
-            exit_pos: collision_event.exit_pos,
+            })
+            .collect(),
+        SensorTrigger::Pterodactyl {
+            spawn_pos,
+            exit_pos,
+        } => vec![Pterodactyl {
+            spawn_pos: Some(spawn_pos),
+            exit_pos,
             current_movement: PterodactylMovement::Fly(PterodactylDirection::Left),
             attack: false,
-        }];
+        }],
+        _ => vec![],
     }
-
-    vec![]
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -271,7 +268,9 @@ fn move_pterodactyl(
     rock_run_assets: Res<RockRunAssets>,
     mut audio_entity: Local<Option<Entity>>,
     query_entity: Query<Entity>,
+    mut unlock_screen: EventWriter<UnlockScreen>,
 ) -> Result<()> {
+    let nb_pterodactyls = pterodactyl_query.iter().count();
     for (
         pterodactyl_entity,
         mut pterodactyl_collider,
@@ -367,6 +366,10 @@ fn move_pterodactyl(
 
         if pterodactyl_pos.distance(pterodactyl.exit_pos) < 2.0 {
             commands.entity(pterodactyl_entity).despawn();
+            // The player got rid of the last pterodactyl
+            if nb_pterodactyls == 1 {
+                unlock_screen.write(UnlockScreen);
+            }
             return Ok(());
         }
 
@@ -420,6 +423,7 @@ fn spawn_little_rock(
         GravityScale(9.0),
         Velocity::zero(),
         Collider::ball(8.0),
+        CollisionLayer::Projectile.groups(),
         ActiveCollisionTypes::DYNAMIC_KINEMATIC | ActiveCollisionTypes::DYNAMIC_DYNAMIC,
         Ccd::enabled(),
         Rock,
@@ -1,22 +1,21 @@
 use bevy::{
     audio::{PlaybackMode, Volume},
-    platform::collections::HashMap,
     prelude::*,
 };
 use bevy_rapier2d::{
     control::KinematicCharacterController,
     dynamics::RigidBody,
     geometry::Collider,
-    pipeline::QueryFilterFlags,
-    prelude::{ActiveCollisionTypes, ActiveEvents, CollisionGroups, Group, Sensor},
+    prelude::{ActiveCollisionTypes, ActiveEvents, Sensor},
 };
 
 use crate::{
     assets::RockRunAssets,
+    collision_layers::CollisionLayer,
     collisions::CollisionSet,
     coregame::{
         colliders::{ColliderName, Ladder, Spike},
-        level::{CurrentLevel, Level},
+        entities::{EntitySpawn, EntitySpawnerAppExt},
         state::AppState,
     },
     events::{EnigmaResult, NextLevel, NutCollision, StartGame},
@@ -41,13 +40,8 @@ pub struct Squirel {
     end_pos: Vec2,
 }
 
-struct Squirels {
-    pos: Vec2,
-    end_pos: Vec2,
-    visibility: Visibility,
-}
-
-#[derive(Clone)]
+/// Where a vine grows, spawned from the Entities layer
+#[derive(Component, Clone)]
 struct VineData {
     pos: Vec2,
     size: usize,
@@ -111,7 +105,10 @@ impl Plugin for SquirelPlugin {
             OnEnter(AppState::FinishLevel),
             (despawn_squirel, despawn_nuts, despawn_vines),
         )
-        .add_systems(OnEnter(AppState::GameCreate), (setup_squirels, setup_nuts))
+        .add_systems(OnEnter(AppState::GameCreate), reset_nuts)
+        .register_entity_spawner("squirel", spawn_squirel)
+        .register_entity_spawner("vine", spawn_vine)
+        .register_entity_spawner("nut", spawn_nut)
         .add_systems(
             Update,
             (move_squirel, check_get_nut, unroll_vine)
@@ -141,21 +138,27 @@ fn get_collider_shapes(y_mirror: bool) -> Vec<(Vec2, f32, Collider)> {
     }
 }
 
-fn setup_squirels(
+/// Spawns a squirrel standing on the position of its object, hidden with the `hidden` property
+///
+/// The squirrel runs to the `end_x`, `end_y` point (Tiled coordinates) once its enigma is solved.
+fn spawn_squirel(
+    In(entity_spawn): In<EntitySpawn>,
     mut commands: Commands,
     rock_run_assets: Res<RockRunAssets>,
     mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
-    levels: Query<&Level, With<Level>>,
-    current_level: Res<CurrentLevel>,
-) {
-    info!("setup_squirels");
+) -> Option<Entity> {
+    info!("spawn_squirel {}", entity_spawn.name);
 
-    let level = levels
-        .iter()
-        .find(|level| level.id == current_level.id)
-        .unwrap();
+    let Some(end_pos) = entity_spawn.get_point("end") else {
+        warn!("Squirel {}: missing end position", entity_spawn.name);
+        return None;
+    };
+    let visibility = if entity_spawn.get_bool("hidden").unwrap_or(false) {
+        Visibility::Hidden
+    } else {
+        Visibility::Visible
+    };
 
-    let texture = rock_run_assets.squirel.clone();
     let layout = TextureAtlasLayout::from_grid(
         UVec2::new(SQUIREL_WIDTH as u32, SQUIREL_HEIGHT as u32),
         14,
@@ -165,129 +168,102 @@ fn setup_squirels(
     );
     let texture_atlas_layout = texture_atlases.add(layout);
 
-    let mut level_squirels: HashMap<u8, Vec<Squirels>> = HashMap::new();
-    level_squirels.insert(
-        1,
-        vec![
-            Squirels {
-                pos: level.map.tiled_to_bevy_coord(Vec2::new(
-                    5189.0,
-                    461.0 - SQUIREL_HEIGHT * SQUIREL_SCALE_FACTOR / 2.0,
-                )),
-                end_pos: level.map.tiled_to_bevy_coord(Vec2::new(7300.0, 600.0)),
-                visibility: Visibility::Visible,
-            },
-            Squirels {
-                pos: level.map.tiled_to_bevy_coord(Vec2::new(
-                    7290.0,
-                    70.0 - SQUIREL_HEIGHT * SQUIREL_SCALE_FACTOR / 2.0,
-                )),
-                end_pos: level.map.tiled_to_bevy_coord(Vec2::new(12714.0, 610.0)),
-                visibility: Visibility::Hidden,
-            },
-        ],
-    );
+    // The object is at the feet of the squirrel
+    let position = entity_spawn.position + Vec2::Y * SQUIREL_HEIGHT * SQUIREL_SCALE_FACTOR / 2.0;
 
-    let squirels = match level_squirels.get(&current_level.id) {
-        Some(squirels) => squirels,
-        None => return,
+    Some(
+        commands
+            .spawn((
+                Sprite {
+                    image: rock_run_assets.squirel.clone(),
+                    flip_x: true,
+                    texture_atlas: Some(TextureAtlas {
+                        layout: texture_atlas_layout,
+                        index: 0,
+                    }),
+                    ..default()
+                },
+                visibility,
+                Transform {
+                    scale: Vec3::splat(SQUIREL_SCALE_FACTOR),
+                    translation: position.extend(10.0),
+                    ..default()
+                },
+                RigidBody::KinematicPositionBased,
+                AnimationTimer(Timer::from_seconds(0.12, TimerMode::Repeating)),
+                Collider::compound(get_collider_shapes(false)),
+                KinematicCharacterController {
+                    max_slope_climb_angle: 85.0f32.to_radians(),
+                    min_slope_slide_angle: 85.0f32.to_radians(),
+                    ..CollisionLayer::Beast.controller()
+                },
+                CollisionLayer::Beast.groups(),
+                Squirel {
+                    current_movement: SquirelMovement::Idle,
+                    end_pos,
+                },
+            ))
+            .id(),
+    )
+}
+
+/// Spawns the root of a vine, unrolled by `size` chunks when the `enigma` property is solved
+fn spawn_vine(In(entity_spawn): In<EntitySpawn>, mut commands: Commands) -> Option<Entity> {
+    info!("spawn_vine {}", entity_spawn.name);
+
+    let (Some(size), Some(enigma)) = (
+        entity_spawn.get_float("size"),
+        entity_spawn.get_string("enigma"),
+    ) else {
+        warn!("Vine {}: missing size or enigma", entity_spawn.name);
+        return None;
     };
 
-    for squirel in squirels {
-        commands.spawn((
-            Sprite {
-                image: texture.clone(),
-                flip_x: true,
-                texture_atlas: Some(TextureAtlas {
-                    layout: texture_atlas_layout.clone(),
-                    index: 0,
-                }),
-                ..default()
-            },
-            squirel.visibility,
-            Transform {
-                scale: Vec3::splat(SQUIREL_SCALE_FACTOR),
-                translation: squirel.pos.extend(10.0),
-                ..default()
-            },
-            RigidBody::KinematicPositionBased,
-            AnimationTimer(Timer::from_seconds(0.12, TimerMode::Repeating)),
-            Collider::compound(get_collider_shapes(false)),
-            KinematicCharacterController {
-                filter_flags: QueryFilterFlags::EXCLUDE_SENSORS,
-                filter_groups: Some(CollisionGroups::new(Group::GROUP_3, Group::GROUP_3)),
-                max_slope_climb_angle: 85.0f32.to_radians(),
-                min_slope_slide_angle: 85.0f32.to_radians(),
-                ..default()
-            },
-            CollisionGroups::new(Group::GROUP_3, Group::GROUP_3),
-            Squirel {
-                current_movement: SquirelMovement::Idle,
-                end_pos: squirel.end_pos,
-            },
-        ));
-    }
+    Some(
+        commands
+            .spawn((
+                Transform::from_translation(entity_spawn.position.extend(10.0)),
+                VineData {
+                    pos: entity_spawn.position,
+                    size: size as usize,
+                    associated_enigma: enigma.to_string(),
+                },
+            ))
+            .id(),
+    )
 }
 
-fn setup_nuts(
+fn spawn_nut(
+    In(entity_spawn): In<EntitySpawn>,
     mut commands: Commands,
     rock_run_assets: Res<RockRunAssets>,
-    levels: Query<&Level, With<Level>>,
-    current_level: Res<CurrentLevel>,
-    mut collected_nuts: ResMut<Nuts>,
-) {
-    info!("setup_nuts");
-
-    let level = levels
-        .iter()
-        .find(|level| level.id == current_level.id)
-        .unwrap();
-
-    let texture = &rock_run_assets.nut;
-    let mut nuts_pos: HashMap<u8, Vec<Vec2>> = HashMap::new();
-    nuts_pos.insert(
-        1,
-        vec![
-            level.map.tiled_to_bevy_coord(Vec2::new(5600.0, 446.0)),
-            level.map.tiled_to_bevy_coord(Vec2::new(5528.0, 302.0)),
-            level.map.tiled_to_bevy_coord(Vec2::new(5258.0, 248.0)),
-            level.map.tiled_to_bevy_coord(Vec2::new(5312.0, 452.0)),
-            level.map.tiled_to_bevy_coord(Vec2::new(5760.0, 612.0)),
-            level.map.tiled_to_bevy_coord(Vec2::new(6100.0, 612.0)),
-            level.map.tiled_to_bevy_coord(Vec2::new(6300.0, 612.0)),
-            level.map.tiled_to_bevy_coord(Vec2::new(6496.0, 612.0)),
-            level.map.tiled_to_bevy_coord(Vec2::new(6625.0, 612.0)),
-            level.map.tiled_to_bevy_coord(Vec2::new(5323.0, 300.0)),
-            level.map.tiled_to_bevy_coord(Vec2::new(5427.0, 288.0)),
-        ],
-    );
-
-    let start_positions = match nuts_pos.get(&current_level.id) {
-        Some(positions) => positions,
-        None => return,
-    };
-
-    for start_pos in start_positions {
-        commands.spawn((
-            Sprite {
-                image: texture.clone(),
-                ..default()
-            },
-            Transform {
-                scale: Vec3::splat(NUT_SCALE_FACTOR),
-                translation: start_pos.extend(10.0),
-                ..default()
-            },
-            Collider::cuboid(NUT_WIDTH / 2.0, NUT_HEIGHT / 2.0),
-            Sensor,
-            ActiveEvents::COLLISION_EVENTS,
-            ActiveCollisionTypes::KINEMATIC_STATIC,
-            Nut,
-            ColliderName("nut01".to_string()),
-        ));
+) -> Option<Entity> {
+    Some(
+        commands
+            .spawn((
+                Sprite {
+                    image: rock_run_assets.nut.clone(),
+                    ..default()
+                },
+                Transform {
+                    scale: Vec3::splat(NUT_SCALE_FACTOR),
+                    translation: entity_spawn.position.extend(10.0),
+                    ..default()
+                },
+                Collider::cuboid(NUT_WIDTH / 2.0, NUT_HEIGHT / 2.0),
+                CollisionLayer::Collectible.groups(),
+                Sensor,
+                ActiveEvents::COLLISION_EVENTS,
+                ActiveCollisionTypes::KINEMATIC_STATIC,
+                Nut,
+                ColliderName(entity_spawn.name),
+            ))
+            .id(),
+    )
+}
 
-        collected_nuts.entities.clear();
-    }
+fn reset_nuts(mut collected_nuts: ResMut<Nuts>) {
+    collected_nuts.entities.clear();
 }
 
 fn check_get_nut(
@@ -414,8 +390,7 @@ fn move_squirel(
 fn unroll_vine(
     mut commands: Commands,
     rock_run_assets: Res<RockRunAssets>,
-    levels: Query<&Level, With<Level>>,
-    current_level: Res<CurrentLevel>,
+    vine_roots: Query<&VineData>,
     time: Res<Time>,
     mut animation_timer: Local<Timer>,
     mut vines: Local<Vec<VineData>>,
@@ -434,33 +409,10 @@ fn unroll_vine(
 
     for ev in enigna_result.read() {
         if let EnigmaResult::Correct(enigma) = ev {
-            let level = levels
+            match vine_roots
                 .iter()
-                .find(|level| level.id == current_level.id)
-                .unwrap();
-
-            let mut enigma_vine: HashMap<String, VineData> = HashMap::new();
-            enigma_vine.insert(
-                "story100-03".to_string(),
-                VineData {
-                    pos: level.map.tiled_to_bevy_coord(Vec2::new(
-                        7272.0,
-                        66.0 - SQUIREL_HEIGHT * SQUIREL_SCALE_FACTOR / 2.0,
-                    )),
-                    size: 35,
-                    associated_enigma: "story100-03".to_string(),
-                },
-            );
-
-            enigma_vine.insert(
-                "story101-03".to_string(),
-                VineData {
-                    pos: level.map.tiled_to_bevy_coord(Vec2::new(12568.0, 1538.0)),
-                    size: 31,
-                    associated_enigma: "story101-03".to_string(),
-                },
-            );
-            match enigma_vine.get(enigma) {
+                .find(|vine_data| vine_data.associated_enigma == *enigma)
+            {
                 Some(vine_data) => {
                     *animation_timer = Timer::from_seconds(0.1, TimerMode::Repeating);
                     vines.push(vine_data.clone());
@@ -512,6 +464,7 @@ fn display_vine(
                     Collider::cuboid(1.0, VINE_HEIGHT / 2.0),
                 )]),
                 ColliderName(vine_data.associated_enigma.to_string()),
+                CollisionLayer::Sensor.groups(),
                 Sensor,
                 ActiveEvents::COLLISION_EVENTS,
                 ActiveCollisionTypes::KINEMATIC_STATIC,
@@ -542,6 +495,7 @@ fn display_vine(
                     Collider::cuboid(1.0, VINE_HEIGHT / 2.0),
                 )]),
                 ColliderName(vine_data.associated_enigma.to_string()),
+                CollisionLayer::Sensor.groups(),
                 Sensor,
                 ActiveEvents::COLLISION_EVENTS,
                 ActiveCollisionTypes::KINEMATIC_STATIC,
@@ -620,6 +574,9 @@ fn display_vine(
         commands
             .entity(parent)
             .insert(ColliderName(vine_data.associated_enigma.to_string()));
+        commands
+            .entity(parent)
+            .insert(CollisionLayer::Sensor.groups());
         commands.entity(parent).insert(Sensor);
         commands
             .entity(parent)
@@ -656,7 +613,7 @@ fn despawn_nuts(mut commands: Commands, nuts: Query<Entity, With<Nut>>) {
     }
 }
 
-fn despawn_vines(mut commands: Commands, vines: Query<Entity, With<Vine>>) {
+fn despawn_vines(mut commands: Commands, vines: Query<Entity, Or<(With<Vine>, With<VineData>)>>) {
     for vine in vines.iter() {
         commands.entity(vine).despawn();
     }
@@ -1,17 +1,17 @@
-use bevy::{audio::PlaybackMode, platform::collections::HashMap, prelude::*};
+use bevy::{audio::PlaybackMode, prelude::*};
 use bevy_rapier2d::{
     control::{KinematicCharacterController, KinematicCharacterControllerOutput},
     dynamics::RigidBody,
     geometry::Collider,
-    pipeline::QueryFilterFlags,
 };
 
 use crate::{
     WINDOW_WIDTH,
     assets::RockRunAssets,
+    collision_layers::CollisionLayer,
     collisions::CollisionSet,
     coregame::{
-        level::{CurrentLevel, Level},
+        entities::{EntitySpawn, EntitySpawnerAppExt, ResetOnRestart},
         state::AppState,
     },
     helpers::texture::cycle_texture,
@@ -59,8 +59,7 @@ pub struct TrexPlugin;
 
 impl Plugin for TrexPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::GameCreate), setup_trex)
-            .add_systems(OnEnter(AppState::NextLevel), setup_trex)
+        app.register_entity_spawner("trex", spawn_trex)
             .add_systems(OnEnter(AppState::StartMenu), despawn_trex)
             .add_systems(OnEnter(AppState::FinishLevel), despawn_trex)
             .add_systems(
@@ -126,19 +125,13 @@ fn get_collider_shapes(collider_type: ColliderType, y_mirror: bool) -> Vec<(Vec2
     }
 }
 
-fn setup_trex(
+fn spawn_trex(
+    In(entity_spawn): In<EntitySpawn>,
     mut commands: Commands,
     rock_run_assets: Res<RockRunAssets>,
     mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
-    levels: Query<&Level, With<Level>>,
-    current_level: Res<CurrentLevel>,
-) {
-    info!("setup_trex");
-
-    let level = levels
-        .iter()
-        .find(|level| level.id == current_level.id)
-        .unwrap();
+) -> Option<Entity> {
+    info!("spawn_trex {}", entity_spawn.name);
 
     let texture = rock_run_assets.trex.clone();
     let layout = TextureAtlasLayout::from_grid(
@@ -149,48 +142,41 @@ fn setup_trex(
         None,
     );
     let texture_atlas_layout = texture_atlases.add(layout);
-    let mut level_trex_pos: HashMap<u8, Vec<Vec2>> = HashMap::new();
-    level_trex_pos.insert(
-        2,
-        vec![level.map.tiled_to_bevy_coord(Vec2::new(9200.0, 2030.0))],
-    );
-
-    let start_positions = match level_trex_pos.get(&current_level.id) {
-        Some(positions) => positions,
-        None => return,
-    };
 
-    for start_pos in start_positions {
-        commands.spawn((
-            Sprite {
-                image: texture.clone(),
-                texture_atlas: Some(TextureAtlas {
-                    layout: texture_atlas_layout.clone(),
-                    index: 0,
-                }),
-                ..default()
-            },
-            Transform {
-                scale: Vec3::splat(TREX_SCALE_FACTOR),
-                translation: start_pos.extend(20.0),
-                ..default()
-            },
-            RigidBody::KinematicPositionBased,
-            AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
-            Collider::compound(get_collider_shapes(ColliderType::Normal, false)),
-            KinematicCharacterController {
-                filter_flags: QueryFilterFlags::ONLY_FIXED,
-                max_slope_climb_angle: 30.0f32.to_radians(),
-                // Automatically slide down on slopes smaller than 30 degrees.
-                min_slope_slide_angle: 30.0f32.to_radians(),
-                normal_nudge_factor: 1.0,
-                ..default()
-            },
-            Trex {
-                current_movement: TrexMovement::default(),
-            },
-        ));
-    }
+    Some(
+        commands
+            .spawn((
+                Sprite {
+                    image: texture,
+                    texture_atlas: Some(TextureAtlas {
+                        layout: texture_atlas_layout,
+                        index: 0,
+                    }),
+                    ..default()
+                },
+                Transform {
+                    scale: Vec3::splat(TREX_SCALE_FACTOR),
+                    translation: entity_spawn.position.extend(20.0),
+                    ..default()
+                },
+                RigidBody::KinematicPositionBased,
+                AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
+                Collider::compound(get_collider_shapes(ColliderType::Normal, false)),
+                KinematicCharacterController {
+                    max_slope_climb_angle: 30.0f32.to_radians(),
+                    // Automatically slide down on slopes smaller than 30 degrees.
+                    min_slope_slide_angle: 30.0f32.to_radians(),
+                    normal_nudge_factor: 1.0,
+                    ..CollisionLayer::Beast.controller()
+                },
+                CollisionLayer::Beast.groups(),
+                ResetOnRestart,
+                Trex {
+                    current_movement: TrexMovement::default(),
+                },
+            ))
+            .id(),
+    )
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1,14 +1,14 @@
-use bevy::{platform::collections::HashMap, prelude::*};
+use bevy::prelude::*;
 use bevy_rapier2d::{
     control::KinematicCharacterController, dynamics::RigidBody, geometry::Collider,
-    pipeline::QueryFilterFlags,
 };
 
 use crate::{
     assets::RockRunAssets,
+    collision_layers::CollisionLayer,
     collisions::CollisionSet,
     coregame::{
-        level::{CurrentLevel, Level},
+        entities::{EntitySpawn, EntitySpawnerAppExt, ResetOnRestart},
         state::AppState,
     },
     events::TriceratopsCollision,
@@ -50,8 +50,7 @@ pub struct TriceratopsPlugin;
 
 impl Plugin for TriceratopsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::GameCreate), setup_triceratops)
-            .add_systems(OnEnter(AppState::NextLevel), setup_triceratops)
+        app.register_entity_spawner("triceratops", spawn_triceratops)
             .add_systems(OnEnter(AppState::StartMenu), despawn_triceratops)
             .add_systems(OnEnter(AppState::FinishLevel), despawn_triceratops)
             .add_systems(
@@ -95,19 +94,13 @@ fn get_collider_shapes(y_mirror: bool) -> Vec<(Vec2, f32, Collider)> {
     }
 }
 
-fn setup_triceratops(
+fn spawn_triceratops(
+    In(entity_spawn): In<EntitySpawn>,
     mut commands: Commands,
     rock_run_assets: Res<RockRunAssets>,
     mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
-    levels: Query<&Level, With<Level>>,
-    current_level: Res<CurrentLevel>,
-) {
-    info!("setup_triceratops");
-
-    let level = levels
-        .iter()
-        .find(|level| level.id == current_level.id)
-        .unwrap();
+) -> Option<Entity> {
+    info!("spawn_triceratops {}", entity_spawn.name);
 
     let texture = rock_run_assets.triceratops.clone();
     let layout = TextureAtlasLayout::from_grid(
@@ -118,52 +111,35 @@ fn setup_triceratops(
         None,
     );
     let texture_atlas_layout = texture_atlases.add(layout);
-    let mut level_triceratops_pos: HashMap<u8, Vec<Vec2>> = HashMap::new();
-    // level_triceratops_pos.insert(
-    //     1,
-    //     vec![level.map.tiled_to_bevy_coord(Vec2::new(4720.0, 576.0))],
-    // );
-    level_triceratops_pos.insert(
-        3,
-        vec![
-            level.map.tiled_to_bevy_coord(Vec2::new(2400.0, 480.0)),
-            level.map.tiled_to_bevy_coord(Vec2::new(6020.0, 1050.0)),
-        ],
-    );
 
-    let start_positions = match level_triceratops_pos.get(&current_level.id) {
-        Some(positions) => positions,
-        None => return,
-    };
-
-    for start_pos in start_positions {
-        commands.spawn((
-            Sprite {
-                image: texture.clone(),
-                texture_atlas: Some(TextureAtlas {
-                    layout: texture_atlas_layout.clone(),
-                    index: 0,
-                }),
-                ..default()
-            },
-            Transform {
-                scale: Vec3::splat(TRICERATOPS_SCALE_FACTOR),
-                translation: start_pos.extend(20.0),
-                ..default()
-            },
-            RigidBody::KinematicPositionBased,
-            AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
-            Collider::compound(get_collider_shapes(false)),
-            KinematicCharacterController {
-                filter_flags: QueryFilterFlags::EXCLUDE_KINEMATIC
-                    | QueryFilterFlags::EXCLUDE_SENSORS,
-                ..default()
-            },
-            Triceratops {
-                current_movement: TriceratopsMovement::Run(TriceratopsDirection::default()),
-            },
-        ));
-    }
+    Some(
+        commands
+            .spawn((
+                Sprite {
+                    image: texture,
+                    texture_atlas: Some(TextureAtlas {
+                        layout: texture_atlas_layout,
+                        index: 0,
+                    }),
+                    ..default()
+                },
+                Transform {
+                    scale: Vec3::splat(TRICERATOPS_SCALE_FACTOR),
+                    translation: entity_spawn.position.extend(20.0),
+                    ..default()
+                },
+                RigidBody::KinematicPositionBased,
+                AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
+                Collider::compound(get_collider_shapes(false)),
+                CollisionLayer::Beast.controller(),
+                CollisionLayer::Beast.groups(),
+                ResetOnRestart,
+                Triceratops {
+                    current_movement: TriceratopsMovement::Run(TriceratopsDirection::default()),
+                },
+            ))
+            .id(),
+    )
 }
 
 fn move_triceratops(
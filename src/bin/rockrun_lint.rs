@@ -0,0 +1,254 @@
+// Checks the levels of the game without starting it.
+//
+// Every map of the `levels.ron` manifest is loaded with the tiled crate and checked against what
+// the game expects: the collider layers, the supported shapes, an exit sensor, the triggers of
+// the position sensors and the translations of the stories. Problems are printed and the process
+// exits with a non-zero code.
+//
+// The manifest and the objects are read with the types and parsers of the game (`level_manifest`
+// and `level_objects` of the `screen_map` library), so both can't diverge.
+//
+// Usage: rockrun-lint [ASSETS_DIRECTORY]  (default: assets)
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use screen_map::{
+    level_manifest::LevelManifestDescription,
+    level_objects::{PlatformKind, SensorTrigger, SensorValues, breath_limit},
+};
+use tiled::{LayerType, Loader, Map, ObjectShape};
+
+const GROUND_LAYER: &str = "Ground";
+const PLATFORMS_LAYER: &str = "Platforms";
+const STORIES_LAYER: &str = "Stories";
+const POSITION_SENSORS_LAYER: &str = "PositionSensors";
+/// Object layers read by `setup_colliders`
+const COLLIDER_LAYERS: [&str; 6] = [
+    GROUND_LAYER,
+    PLATFORMS_LAYER,
+    "Spikes",
+    STORIES_LAYER,
+    POSITION_SENSORS_LAYER,
+    "Ladders",
+];
+/// Optional layer of the water zones
+const WATER_LAYER: &str = "Water";
+const LOCALES_DIRECTORY: &str = "locales";
+const LOCALE_FILE: &str = "rock_run.ftl";
+
+/// Message ids of a locale
+struct Locale {
+    name: String,
+    ids: HashSet<String>,
+}
+
+fn main() -> ExitCode {
+    let assets = PathBuf::from(std::env::args().nth(1).unwrap_or("assets".to_string()));
+
+    let problems = match lint(&assets) {
+        Ok(problems) => problems,
+        Err(error) => {
+            eprintln!("error: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for problem in problems.iter() {
+        eprintln!("{problem}");
+    }
+
+    if problems.is_empty() {
+        println!("No problem found");
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("{} problem(s) found", problems.len());
+        ExitCode::FAILURE
+    }
+}
+
+/// Returns the problems found in the levels, or an error if they cannot be read
+fn lint(assets: &Path) -> Result<Vec<String>, String> {
+    let manifest_path = assets.join("levels.ron");
+    let manifest = fs::read_to_string(&manifest_path)
+        .map_err(|error| format!("{}: {error}", manifest_path.display()))?;
+    let manifest: LevelManifestDescription = ron::from_str(&manifest)
+        .map_err(|error| format!("{}: {error}", manifest_path.display()))?;
+
+    let locales = read_locales(&assets.join(LOCALES_DIRECTORY))?;
+
+    let mut loader = Loader::new();
+    let mut problems = Vec::new();
+    for level in manifest.levels.iter() {
+        match loader.load_tmx_map(assets.join(&level.map)) {
+            Ok(map) => problems.extend(
+                lint_map(&map, &locales)
+                    .into_iter()
+                    .map(|problem| format!("{}: {problem}", level.map)),
+            ),
+            Err(error) => problems.push(format!("{}: cannot load map: {error}", level.map)),
+        }
+    }
+    Ok(problems)
+}
+
+fn read_locales(directory: &Path) -> Result<Vec<Locale>, String> {
+    let entries =
+        fs::read_dir(directory).map_err(|error| format!("{}: {error}", directory.display()))?;
+
+    let mut locales = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|error| format!("{}: {error}", directory.display()))?
+            .path();
+        let file = path.join(LOCALE_FILE);
+        if !file.is_file() {
+            continue;
+        }
+
+        let content =
+            fs::read_to_string(&file).map_err(|error| format!("{}: {error}", file.display()))?;
+        locales.push(Locale {
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            ids: parse_message_ids(&content),
+        });
+    }
+
+    if locales.is_empty() {
+        return Err(format!("{}: no {LOCALE_FILE} found", directory.display()));
+    }
+    Ok(locales)
+}
+
+/// Returns the ids of the messages defined at the start of the lines (`id = value`)
+fn parse_message_ids(content: &str) -> HashSet<String> {
+    content
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(id, _)| id.trim_end())
+        .filter(|id| {
+            !id.is_empty()
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+        .map(|id| id.to_string())
+        .collect()
+}
+
+fn lint_map(map: &Map, locales: &[Locale]) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    for layer_name in COLLIDER_LAYERS {
+        let objects = match map.layers().find(|layer| layer.name == layer_name) {
+            Some(layer) => match layer.layer_type() {
+                LayerType::Objects(objects) => objects,
+                _ => {
+                    problems.push(format!("{layer_name} is not an object layer"));
+                    continue;
+                }
+            },
+            None => {
+                problems.push(format!("missing {layer_name} layer"));
+                continue;
+            }
+        };
+
+        if layer_name == GROUND_LAYER && objects.objects().len() != 1 {
+            problems.push(format!(
+                "{layer_name} must contain exactly 1 object, found {}",
+                objects.objects().len()
+            ));
+        }
+
+        if layer_name == POSITION_SENSORS_LAYER
+            && !objects.objects().any(|object| {
+                SensorTrigger::from_sensor(&object.name, &SensorValues::default())
+                    == Ok(SensorTrigger::Exit)
+            })
+        {
+            problems.push(format!("{layer_name} has no exit sensor"));
+        }
+
+        for object in objects.objects() {
+            if let ObjectShape::Text { .. } = object.shape {
+                problems.push(format!(
+                    "{layer_name}/{}: text shape is not supported",
+                    object.name
+                ));
+            }
+
+            if layer_name == PLATFORMS_LAYER {
+                match PlatformKind::from_object(&object.user_type, &object.properties) {
+                    Ok(Some(PlatformKind::Crumbling))
+                        if !matches!(object.shape, ObjectShape::Rect { .. }) =>
+                    {
+                        problems.push(format!(
+                            "{layer_name}/{}: crumbling platforms must be rectangles",
+                            object.name
+                        ));
+                    }
+                    Ok(_) => {}
+                    Err(error) => problems.push(format!("{layer_name}/{}: {error}", object.name)),
+                }
+            }
+
+            if layer_name == POSITION_SENSORS_LAYER {
+                // Positions don't change the kind of trigger.
+                if let Err(error) =
+                    SensorTrigger::from_sensor(&object.name, &SensorValues::default())
+                {
+                    problems.push(format!("{layer_name}/{}: {error}", object.name));
+                }
+            }
+
+            if layer_name == STORIES_LAYER {
+                let prefix = format!("{}-", object.name);
+                for locale in locales {
+                    if !locale.ids.iter().any(|id| id.starts_with(&prefix)) {
+                        problems.push(format!(
+                            "{layer_name}/{}: no {}NN message in {}/{LOCALE_FILE}",
+                            object.name, prefix, locale.name
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    problems.extend(lint_water(map));
+
+    problems
+}
+
+/// Checks the water zones, the layer is optional
+fn lint_water(map: &Map) -> Vec<String> {
+    let Some(layer) = map.layers().find(|layer| layer.name == WATER_LAYER) else {
+        return Vec::new();
+    };
+    let LayerType::Objects(objects) = layer.layer_type() else {
+        return vec![format!("{WATER_LAYER} is not an object layer")];
+    };
+
+    let mut problems = Vec::new();
+    for object in objects.objects() {
+        if let ObjectShape::Text { .. } = object.shape {
+            problems.push(format!(
+                "{WATER_LAYER}/{}: text shape is not supported",
+                object.name
+            ));
+        }
+
+        if let Err(error) = breath_limit(&object.properties) {
+            problems.push(format!("{WATER_LAYER}/{}: {error}", object.name));
+        }
+    }
+    problems
+}
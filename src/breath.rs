@@ -0,0 +1,138 @@
+// Breath of the player under the water. Water objects with a `breath` property limit the time
+// the player can swim, the remaining breath is shown under the lives.
+
+use bevy::prelude::*;
+
+use crate::{
+    coregame::{menu::Godmode, state::AppState},
+    events::Hit,
+    player::PlayerState,
+};
+
+const BREATH_BAR_WIDTH: f32 = 160.0;
+const BREATH_BAR_HEIGHT: f32 = 14.0;
+
+#[derive(Resource, Default, Debug)]
+pub struct Breath(Option<Timer>);
+
+impl Breath {
+    /// Starts holding the breath, `None` is an unlimited breath
+    pub fn start(&mut self, seconds: Option<f32>) {
+        self.0 = seconds.map(|seconds| Timer::from_seconds(seconds, TimerMode::Once));
+    }
+
+    pub fn stop(&mut self) {
+        self.0 = None;
+    }
+}
+
+#[derive(Component)]
+struct BreathUI;
+
+#[derive(Component)]
+struct BreathBar;
+
+pub struct BreathPlugin;
+
+impl Plugin for BreathPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::GameCreate), setup_breath)
+            .add_systems(OnEnter(AppState::StartMenu), despawn_breath)
+            .add_systems(OnEnter(AppState::FinishLevel), reset_breath)
+            .add_systems(
+                Update,
+                (update_breath, show_breath)
+                    .chain()
+                    .run_if(in_state(AppState::GameRunning)),
+            )
+            .insert_resource(Breath::default());
+    }
+}
+
+fn setup_breath(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(20.0),
+                top: Val::Px(50.0),
+                width: Val::Px(BREATH_BAR_WIDTH),
+                height: Val::Px(BREATH_BAR_HEIGHT),
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BorderColor(Color::srgb_u8(0x54, 0x2E, 0x0A)),
+            BackgroundColor(Color::srgba_u8(0x00, 0x00, 0x00, 0x80)),
+            Visibility::Hidden,
+            BreathUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb_u8(0x7F, 0xC8, 0xF8)),
+                BreathBar,
+            ));
+        });
+}
+
+fn update_breath(
+    time: Res<Time>,
+    state: Res<State<PlayerState>>,
+    mut breath: ResMut<Breath>,
+    mut hit: EventWriter<Hit>,
+    god_mode: Res<Godmode>,
+) {
+    if state.get() != &PlayerState::Swimming {
+        return;
+    }
+
+    let Some(timer) = breath.0.as_mut() else {
+        return;
+    };
+
+    timer.tick(time.delta());
+    if timer.just_finished() {
+        debug!("out of breath");
+        if !god_mode.0 {
+            hit.write(Hit);
+        }
+    }
+}
+
+fn show_breath(
+    breath: Res<Breath>,
+    mut breath_ui: Query<&mut Visibility, With<BreathUI>>,
+    mut breath_bar: Query<&mut Node, With<BreathBar>>,
+) {
+    for mut visibility in breath_ui.iter_mut() {
+        *visibility = match breath.0 {
+            Some(_) => Visibility::Inherited,
+            None => Visibility::Hidden,
+        };
+    }
+
+    if let Some(timer) = &breath.0 {
+        for mut node in breath_bar.iter_mut() {
+            node.width = Val::Percent(timer.fraction_remaining() * 100.0);
+        }
+    }
+}
+
+fn reset_breath(mut breath: ResMut<Breath>) {
+    breath.stop();
+}
+
+fn despawn_breath(
+    mut commands: Commands,
+    mut breath: ResMut<Breath>,
+    breath_ui: Query<Entity, With<BreathUI>>,
+) {
+    breath.stop();
+    for entity in breath_ui.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,224 @@
+// Collision layers of the colliders.
+//
+// Every collider belongs to one layer and only interacts (contacts and sensor events) with the
+// layers paired with it in `INTERACTIONS`. Colliders get their layer with
+// `CollisionLayer::groups`, the character controllers of the player and the beasts get the
+// colliders stopping them with `CollisionLayer::controller`. A collider spawned without a layer
+// collides with everything.
+//
+// In debug mode, F11 shows the layers of every collider.
+
+use bevy::{platform::collections::HashSet, prelude::*};
+use bevy_rapier2d::{
+    control::KinematicCharacterController,
+    geometry::Collider,
+    pipeline::QueryFilterFlags,
+    prelude::{CollisionGroups, Group},
+};
+
+const LAYER_LABEL_Z: f32 = 50.0;
+const LAYER_LABEL_FONT_SIZE: f32 = 14.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CollisionLayer {
+    Player,
+    /// Beasts moved by a character controller
+    Beast,
+    /// Ground, platforms, spikes and gates
+    Terrain,
+    /// Sensors only reached by the player: stories, position sensors, ladders and water
+    Sensor,
+    /// Wind, conveyor and ice sensors, affecting every moving body
+    Zone,
+    /// Rocks and fireballs
+    Projectile,
+    /// Extra lives, keys and nuts
+    Collectible,
+}
+
+/// Pairs of layers interacting with each other
+///
+/// Beasts don't interact with the projectiles: a pterodactyl drops its rocks from inside its
+/// body, and rolling rocks must not be stopped by the beasts on their way.
+const INTERACTIONS: [(CollisionLayer, CollisionLayer); 13] = [
+    (CollisionLayer::Player, CollisionLayer::Beast),
+    (CollisionLayer::Player, CollisionLayer::Terrain),
+    (CollisionLayer::Player, CollisionLayer::Sensor),
+    (CollisionLayer::Player, CollisionLayer::Zone),
+    (CollisionLayer::Player, CollisionLayer::Projectile),
+    (CollisionLayer::Player, CollisionLayer::Collectible),
+    (CollisionLayer::Beast, CollisionLayer::Beast),
+    (CollisionLayer::Beast, CollisionLayer::Terrain),
+    (CollisionLayer::Beast, CollisionLayer::Zone),
+    (CollisionLayer::Terrain, CollisionLayer::Terrain),
+    (CollisionLayer::Terrain, CollisionLayer::Projectile),
+    (CollisionLayer::Zone, CollisionLayer::Projectile),
+    (CollisionLayer::Projectile, CollisionLayer::Projectile),
+];
+
+impl CollisionLayer {
+    pub const ALL: [Self; 7] = [
+        Self::Player,
+        Self::Beast,
+        Self::Terrain,
+        Self::Sensor,
+        Self::Zone,
+        Self::Projectile,
+        Self::Collectible,
+    ];
+
+    fn membership(self) -> Group {
+        match self {
+            Self::Player => Group::GROUP_1,
+            Self::Beast => Group::GROUP_2,
+            Self::Terrain => Group::GROUP_3,
+            Self::Sensor => Group::GROUP_4,
+            Self::Zone => Group::GROUP_5,
+            Self::Projectile => Group::GROUP_6,
+            Self::Collectible => Group::GROUP_7,
+        }
+    }
+
+    /// Groups of the layers interacting with this one
+    fn filter(self) -> Group {
+        INTERACTIONS
+            .iter()
+            .filter_map(|&(a, b)| match (a == self, b == self) {
+                (true, _) => Some(b),
+                (_, true) => Some(a),
+                _ => None,
+            })
+            .fold(Group::NONE, |filter, layer| filter | layer.membership())
+    }
+
+    /// Collision groups of the colliders of this layer
+    pub fn groups(self) -> CollisionGroups {
+        CollisionGroups::new(self.membership(), self.filter())
+    }
+
+    /// Collision groups of the colliders of this layer, ignoring the colliders of `other` (e.g. a
+    /// one-way platform the player is below)
+    pub fn groups_ignoring(self, other: Self) -> CollisionGroups {
+        CollisionGroups::new(
+            self.membership(),
+            self.filter().difference(other.membership()),
+        )
+    }
+
+    /// Character controller of the bodies of this layer, with the colliders stopping them
+    ///
+    /// The beasts are only stopped by the terrain, the player by every layer it interacts with.
+    /// Sensors never stop a controller.
+    pub fn controller(self) -> KinematicCharacterController {
+        let obstacles = match self {
+            Self::Player => self.filter(),
+            _ => Self::Terrain.membership(),
+        };
+        self.controller_stopped_by(obstacles)
+    }
+
+    /// Character controller of the flying bodies of this layer, going through the terrain
+    pub fn flying_controller(self) -> KinematicCharacterController {
+        self.controller_stopped_by(self.filter().difference(Self::Terrain.membership()))
+    }
+
+    fn controller_stopped_by(self, obstacles: Group) -> KinematicCharacterController {
+        KinematicCharacterController {
+            filter_flags: QueryFilterFlags::EXCLUDE_SENSORS,
+            filter_groups: Some(CollisionGroups::new(self.membership(), obstacles)),
+            ..default()
+        }
+    }
+}
+
+pub struct CollisionLayersPlugin;
+
+impl Plugin for CollisionLayersPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShowCollisionLayers>()
+            .add_systems(Update, update_layer_labels);
+
+        #[cfg(debug_assertions)]
+        app.add_systems(Update, toggle_layer_labels.before(update_layer_labels));
+    }
+}
+
+#[derive(Resource, Default)]
+struct ShowCollisionLayers(bool);
+
+/// Text showing the layers of a collider
+#[derive(Component)]
+struct LayerLabel(Entity);
+
+#[cfg(debug_assertions)]
+fn toggle_layer_labels(
+    mut commands: Commands,
+    kbd: Res<ButtonInput<KeyCode>>,
+    mut show_layers: ResMut<ShowCollisionLayers>,
+    labels: Query<Entity, With<LayerLabel>>,
+) {
+    if kbd.just_pressed(KeyCode::F11) {
+        show_layers.0 = !show_layers.0;
+        if !show_layers.0 {
+            for label in labels.iter() {
+                commands.entity(label).despawn();
+            }
+        }
+    }
+}
+
+/// Names the layers of the collision groups, colliders without a layer are shown in red
+fn layer_label(groups: Option<&CollisionGroups>) -> (String, Color) {
+    let memberships = groups.map_or(Group::ALL, |groups| groups.memberships);
+    if memberships == Group::ALL {
+        return ("no layer".to_string(), Color::srgb(1.0, 0.2, 0.2));
+    }
+
+    let names: Vec<String> = CollisionLayer::ALL
+        .iter()
+        .filter(|layer| memberships.contains(layer.membership()))
+        .map(|layer| format!("{layer:?}"))
+        .collect();
+    (names.join(", "), Color::WHITE)
+}
+
+/// Labels the colliders with their layers, the labels follow the colliders
+fn update_layer_labels(
+    mut commands: Commands,
+    show_layers: Res<ShowCollisionLayers>,
+    colliders: Query<(Entity, &GlobalTransform, Option<&CollisionGroups>), With<Collider>>,
+    mut labels: Query<(Entity, &LayerLabel, &mut Transform)>,
+) {
+    if !show_layers.0 {
+        return;
+    }
+
+    let mut labelled = HashSet::new();
+    for (label_entity, label, mut transform) in labels.iter_mut() {
+        match colliders.get(label.0) {
+            Ok((_, global_transform, _)) => {
+                transform.translation = global_transform.translation().xy().extend(LAYER_LABEL_Z);
+                labelled.insert(label.0);
+            }
+            Err(_) => commands.entity(label_entity).despawn(),
+        }
+    }
+
+    for (entity, global_transform, groups) in colliders.iter() {
+        if labelled.contains(&entity) {
+            continue;
+        }
+
+        let (text, color) = layer_label(groups);
+        commands.spawn((
+            Text2d::new(text),
+            TextFont {
+                font_size: LAYER_LABEL_FONT_SIZE,
+                ..default()
+            },
+            TextColor(color),
+            Transform::from_translation(global_transform.translation().xy().extend(LAYER_LABEL_Z)),
+            LayerLabel(entity),
+        ));
+    }
+}
@@ -1,7 +1,9 @@
 use bevy::{platform::collections::HashMap, prelude::*};
 use bevy_rapier2d::{
-    control::KinematicCharacterControllerOutput, dynamics::Velocity,
-    geometry::ActiveCollisionTypes, pipeline::CollisionEvent,
+    control::KinematicCharacterControllerOutput,
+    dynamics::Velocity,
+    geometry::{ActiveCollisionTypes, CollisionGroups},
+    pipeline::CollisionEvent,
 };
 use rand::seq::SliceRandom;
 use rand::{rng, seq::IndexedRandom};
@@ -12,13 +14,19 @@ use crate::{
         bat::Bat, monkey::Monkey, pterodactyl::Pterodactyl, squirel::Nut, trex::Trex,
         triceratops::Triceratops,
     },
+    collision_layers::CollisionLayer,
     coregame::{
-        colliders::{ColliderName, Ground, Ladder, Platform, PositionSensor, Spike, Story},
-        level::{CurrentLevel, Level},
+        camera::no_screen_transition,
+        colliders::{
+            BouncePad, BreathLimit, ColliderName, CrumblingPlatform, Ground, Ladder,
+            OneWayPlatform, Platform, PositionSensor, SensorValues, Spike, Story, Water,
+        },
         menu::Godmode,
         state::AppState,
+        triggers::{SensorTrigger, TriggerHandlers, TriggeredSensor, run_trigger_handlers},
     },
     elements::{
+        checkpoint::Checkpoint,
         enigma::{EnigmaKind, Enigmas, RockGate},
         moving_platform::MovingPlatform,
         rock::Rock,
@@ -26,20 +34,18 @@ use crate::{
         volcano::Fireball,
     },
     events::{
-        ExtraLifeCollision, Hit, KeyCollision, LadderCollisionStart, LadderCollisionStop,
-        LifeEvent, MovingPlatformCollision, NutCollision, PositionSensorCollisionStart,
-        PositionSensorCollisionStop, Restart, StoryMessages, TriceratopsCollision,
+        BouncePadCollision, CrumblingPlatformCollision, ExtraLifeCollision, Hit, KeyCollision,
+        LadderCollisionStart, LadderCollisionStop, LifeEvent, MovingPlatformCollision,
+        NutCollision, Restart, StoryMessages, TriceratopsCollision, WaterCollisionStart,
+        WaterCollisionStop,
     },
     key::Key,
     life::ExtraLife,
-    player::{self, PLAYER_HEIGHT, Player, PlayerState},
+    player::{self, PLAYER_FEET_OFFSET, PLAYER_HEIGHT, Player, PlayerState},
 };
 
-struct SensorValues {
-    start_pos: Vec2,
-    end_pos: Vec2,
-    disable_next_collision: bool,
-}
+/// Distance below the top of a one-way platform where the player still lands on it
+const ONE_WAY_PLATFORM_TOLERANCE: f32 = 1.0;
 
 #[derive(Debug, Component)]
 pub struct StoryQM(String);
@@ -55,28 +61,34 @@ impl Plugin for CollisionsPlugin {
             Update,
             (
                 player_collisions_with_elements,
+                player_collisions_with_platform_kinds,
+                one_way_platform_collisions,
                 player_collisions_with_beasts,
                 triceratops_collisions,
                 story_collisions,
                 display_story,
                 position_sensor_collisions,
                 ladder_collisions,
+                water_collisions,
                 extra_life_collisions,
                 nut_collisions,
                 key_collisions,
                 fireball_collisions,
             )
                 .in_set(CollisionSet)
-                .run_if(in_state(AppState::GameRunning)),
+                .run_if(in_state(AppState::GameRunning))
+                .run_if(no_screen_transition),
         )
         .add_systems(OnEnter(AppState::StartMenu), despawn_qm)
         .add_event::<Hit>()
         .add_event::<TriceratopsCollision>()
-        .add_event::<PositionSensorCollisionStart>()
-        .add_event::<PositionSensorCollisionStop>()
         .add_event::<LadderCollisionStart>()
         .add_event::<LadderCollisionStop>()
+        .add_event::<WaterCollisionStart>()
+        .add_event::<WaterCollisionStop>()
         .add_event::<MovingPlatformCollision>()
+        .add_event::<CrumblingPlatformCollision>()
+        .add_event::<BouncePadCollision>()
         .add_event::<ExtraLifeCollision>()
         .add_event::<NutCollision>()
         .add_event::<KeyCollision>();
@@ -137,13 +149,16 @@ fn player_collisions_with_elements(
             || platforms.contains(character_collision.entity))
             && output.grounded
             && state.get() != &PlayerState::Jumping
+            && state.get() != &PlayerState::Swimming
         {
             next_state.set(PlayerState::Idling);
         }
 
         // Player collides with moving platforms
         for moving_platform in moving_platforms.iter() {
-            if character_collision.entity == moving_platform && state.get() != &PlayerState::Jumping
+            if character_collision.entity == moving_platform
+                && state.get() != &PlayerState::Jumping
+                && state.get() != &PlayerState::Swimming
             {
                 next_state.set(PlayerState::Idling);
                 moving_platform_collision.write(MovingPlatformCollision {
@@ -168,7 +183,10 @@ fn player_collisions_with_elements(
                     hit.write(Hit);
                 }
 
-                if output.grounded && state.get() != &PlayerState::Jumping {
+                if output.grounded
+                    && state.get() != &PlayerState::Jumping
+                    && state.get() != &PlayerState::Swimming
+                {
                     next_state.set(PlayerState::Idling);
                 }
             }
@@ -184,6 +202,95 @@ fn player_collisions_with_elements(
     }
 }
 
+fn player_collisions_with_platform_kinds(
+    player_controller: Query<&KinematicCharacterControllerOutput, With<Player>>,
+    state: Res<State<PlayerState>>,
+    crumbling_platforms: Query<Entity, With<CrumblingPlatform>>,
+    bounce_pads: Query<&BouncePad>,
+    mut crumbling_platform_collision: EventWriter<CrumblingPlatformCollision>,
+    mut bounce_pad_collision: EventWriter<BouncePadCollision>,
+) {
+    if state.get() == &PlayerState::Hit || state.get() == &PlayerState::Jumping {
+        return;
+    }
+
+    let output = match player_controller.single() {
+        Ok(output) => output,
+        Err(_) => return,
+    };
+
+    if !output.grounded {
+        return;
+    }
+
+    for character_collision in output.collisions.iter() {
+        // Player lands on crumbling platforms
+        if crumbling_platforms.contains(character_collision.entity) {
+            crumbling_platform_collision.write(CrumblingPlatformCollision {
+                entity: character_collision.entity,
+            });
+        }
+
+        // Player lands on bounce pads
+        if let Ok(bounce_pad) = bounce_pads.get(character_collision.entity) {
+            bounce_pad_collision.write(BouncePadCollision {
+                strength: bounce_pad.strength,
+            });
+        }
+    }
+}
+
+/// One-way platforms are solid for the player only when they are above them, crouching on one of
+/// them drops the player through it.
+///
+/// The platforms ignore the player layer while passable, rocks and beasts still rest on them.
+fn one_way_platform_collisions(
+    player: Query<
+        (
+            &Transform,
+            &KinematicCharacterControllerOutput,
+            &leafwing_input_manager::action_state::ActionState<player::PlayerMovement>,
+        ),
+        With<Player>,
+    >,
+    mut one_way_platforms: Query<(Entity, &mut OneWayPlatform, &mut CollisionGroups)>,
+) {
+    let (transform, output, input_state) = match player.single() {
+        Ok(player) => player,
+        Err(_) => return,
+    };
+
+    let feet = transform.translation.y - PLAYER_FEET_OFFSET;
+
+    for (entity, mut platform, mut groups) in one_way_platforms.iter_mut() {
+        if input_state.just_pressed(&player::PlayerMovement::Crouch)
+            && output.grounded
+            && output
+                .collisions
+                .iter()
+                .any(|character_collision| character_collision.entity == entity)
+        {
+            debug!("drop through one-way platform {:?}", entity);
+            platform.dropping = true;
+        }
+
+        let above = feet >= platform.top - ONE_WAY_PLATFORM_TOLERANCE;
+        if !above {
+            platform.dropping = false;
+        }
+
+        let solid = above && !platform.dropping;
+        let platform_groups = if solid {
+            CollisionLayer::Terrain.groups()
+        } else {
+            CollisionLayer::Terrain.groups_ignoring(CollisionLayer::Player)
+        };
+        if *groups != platform_groups {
+            *groups = platform_groups;
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn player_collisions_with_beasts(
     player_controller: Query<(Entity, &KinematicCharacterControllerOutput), With<Player>>,
@@ -603,6 +710,63 @@ fn ladder_collisions(
     }
 }
 
+fn water_collisions(
+    water: Query<(Entity, &ColliderName, Option<&BreathLimit>), With<Water>>,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut water_collision_start: EventWriter<WaterCollisionStart>,
+    mut water_collision_stop: EventWriter<WaterCollisionStop>,
+    player: Query<Entity, With<Player>>,
+) {
+    let player_entity = match player.single() {
+        Ok(entity) => entity,
+        Err(_) => return,
+    };
+
+    for collision_event in collision_events.read() {
+        match collision_event {
+            CollisionEvent::Started(e1, e2, _cf) => {
+                // Warning, e1 and e2 can be swapped.
+                if let Some((water_entity, collider_name, breath_limit)) =
+                    water
+                        .iter()
+                        .find(|(entity, _collider_name, _breath_limit)| {
+                            (entity == e1 && player_entity == *e2)
+                                || (entity == e2 && player_entity == *e1)
+                        })
+                {
+                    debug!(
+                        "Received collision event: {:?}, collider name: {:?}",
+                        collision_event, collider_name
+                    );
+                    water_collision_start.write(WaterCollisionStart {
+                        water: water_entity,
+                        breath: breath_limit.map(|breath_limit| breath_limit.0),
+                    });
+                };
+            }
+            CollisionEvent::Stopped(e1, e2, _cf) => {
+                // Warning, e1 and e2 can be swapped.
+                if let Some((water_entity, collider_name, _breath_limit)) =
+                    water
+                        .iter()
+                        .find(|(entity, _collider_name, _breath_limit)| {
+                            (entity == e1 && player_entity == *e2)
+                                || (entity == e2 && player_entity == *e1)
+                        })
+                {
+                    debug!(
+                        "Received collision event: {:?}, collider name: {:?}",
+                        collision_event, collider_name
+                    );
+                    water_collision_stop.write(WaterCollisionStop {
+                        water: water_entity,
+                    });
+                };
+            }
+        }
+    }
+}
+
 fn fireball_collisions(
     fireballs: Query<(Entity, &ColliderName), With<Fireball>>,
     mut collision_events: EventReader<CollisionEvent>,
@@ -655,25 +819,38 @@ fn fireball_collisions(
     }
 }
 
-#[allow(clippy::too_many_arguments)]
 fn position_sensor_collisions(
+    mut commands: Commands,
     mut position_sensors: Query<
-        (Entity, &ColliderName, &mut ActiveCollisionTypes),
+        (
+            Entity,
+            &ColliderName,
+            &SensorValues,
+            Option<&SensorTrigger>,
+            &mut ActiveCollisionTypes,
+        ),
         With<PositionSensor>,
     >,
     mut collision_events: EventReader<CollisionEvent>,
-    mut event_start: EventWriter<PositionSensorCollisionStart>,
-    mut event_stop: EventWriter<PositionSensorCollisionStop>,
-    levels: Query<&Level, With<Level>>,
-    current_level: Res<CurrentLevel>,
+    trigger_handlers: Res<TriggerHandlers>,
     mut restart_event: EventReader<Restart>,
     player: Query<Entity, With<Player>>,
+    checkpoint: Res<Checkpoint>,
 ) {
     if !restart_event.is_empty() {
-        for (_position_sensor, _collider_name, mut active_collision_type) in
-            position_sensors.iter_mut()
+        for (
+            _position_sensor,
+            collider_name,
+            _sensor_values,
+            _trigger,
+            mut active_collision_type,
+        ) in position_sensors.iter_mut()
         {
-            *active_collision_type = ActiveCollisionTypes::KINEMATIC_STATIC;
+            // Sensors passed before the checkpoint stay disabled, the beasts they triggered
+            // are behind the player.
+            if !checkpoint.passed_sensors.contains(&collider_name.0) {
+                *active_collision_type = ActiveCollisionTypes::KINEMATIC_STATIC;
+            }
         }
         restart_event.clear();
     }
@@ -684,190 +861,45 @@ fn position_sensor_collisions(
     };
 
     for collision_event in collision_events.read() {
-        let level = levels
-            .iter()
-            .find(|level| level.id == current_level.id)
-            .unwrap();
-
-        let mut level_sensor_pos: HashMap<u8, HashMap<String, SensorValues>> = HashMap::new();
-        level_sensor_pos.insert(
-            1,
-            HashMap::from([
-                (
-                    "exit01".to_string(),
-                    SensorValues {
-                        start_pos: Vec2::ZERO,
-                        end_pos: Vec2::ZERO,
-                        disable_next_collision: false,
-                    },
-                ),
-                (
-                    "pterodactyl_attack01".to_string(),
-                    SensorValues {
-                        start_pos: level.map.tiled_to_bevy_coord(Vec2::new(1596.0, 455.0)),
-                        end_pos: level.map.tiled_to_bevy_coord(Vec2::new(-100.0, 455.0)),
-                        disable_next_collision: true,
-                    },
-                ),
-                (
-                    "pterodactyl_wave_30_01".to_string(),
-                    SensorValues {
-                        start_pos: Vec2::ZERO,
-                        end_pos: Vec2::ZERO,
-                        disable_next_collision: false,
-                    },
-                ),
-                (
-                    "volcano01_01".to_string(),
-                    SensorValues {
-                        start_pos: level.map.tiled_to_bevy_coord(Vec2::new(10000.0, 150.0)),
-                        end_pos: level.map.tiled_to_bevy_coord(Vec2::new(10000.0, 740.0)),
-                        disable_next_collision: true,
-                    },
-                ),
-                (
-                    "volcano01_02".to_string(),
-                    SensorValues {
-                        start_pos: level.map.tiled_to_bevy_coord(Vec2::new(10000.0, 150.0)),
-                        end_pos: level.map.tiled_to_bevy_coord(Vec2::new(10000.0, 740.0)),
-                        disable_next_collision: true,
-                    },
-                ),
-            ]),
-        );
+        // Warning, e1 and e2 can be swapped.
+        let (e1, e2, entered) = match collision_event {
+            CollisionEvent::Started(e1, e2, _cf) => (*e1, *e2, true),
+            CollisionEvent::Stopped(e1, e2, _cf) => (*e1, *e2, false),
+        };
+        let sensor_entity = if player_entity == e2 {
+            e1
+        } else if player_entity == e1 {
+            e2
+        } else {
+            continue;
+        };
 
-        level_sensor_pos.insert(
-            2,
-            HashMap::from([
-                (
-                    "exit01".to_string(),
-                    SensorValues {
-                        start_pos: Vec2::ZERO,
-                        end_pos: Vec2::ZERO,
-                        disable_next_collision: false,
-                    },
-                ),
-                (
-                    "pterodactyl_attack02".to_string(),
-                    SensorValues {
-                        start_pos: level.map.tiled_to_bevy_coord(Vec2::new(3520.0, 820.0)),
-                        end_pos: level.map.tiled_to_bevy_coord(Vec2::new(1500.0, 820.0)),
-                        disable_next_collision: true,
-                    },
-                ),
-                (
-                    "pterodactyl_attack03".to_string(),
-                    SensorValues {
-                        start_pos: level.map.tiled_to_bevy_coord(Vec2::new(4800.0, 820.0)),
-                        end_pos: level.map.tiled_to_bevy_coord(Vec2::new(1500.0, 820.0)),
-                        disable_next_collision: true,
-                    },
-                ),
-            ]),
-        );
+        let Ok((_entity, collider_name, sensor_values, trigger, mut active_collision_type)) =
+            position_sensors.get_mut(sensor_entity)
+        else {
+            continue;
+        };
 
-        level_sensor_pos.insert(
-            3,
-            HashMap::from([
-                (
-                    "bat01".to_string(),
-                    SensorValues {
-                        start_pos: level.map.tiled_to_bevy_coord(Vec2::new(3940.0, 850.0)),
-                        end_pos: level.map.tiled_to_bevy_coord(Vec2::new(3060.0, 1460.0)),
-                        disable_next_collision: false,
-                    },
-                ),
-                (
-                    "pterodactyl01".to_string(),
-                    SensorValues {
-                        start_pos: level.map.tiled_to_bevy_coord(Vec2::new(1400.0, 320.0)),
-                        end_pos: level.map.tiled_to_bevy_coord(Vec2::new(-30.0, 320.0)),
-                        disable_next_collision: true,
-                    },
-                ),
-                (
-                    "rock01".to_string(),
-                    SensorValues {
-                        start_pos: level.map.tiled_to_bevy_coord(Vec2::new(5300.0, 800.0)),
-                        end_pos: level.map.tiled_to_bevy_coord(Vec2::new(5300.0, 600.0)),
-                        disable_next_collision: false,
-                    },
-                ),
-                (
-                    "rock02".to_string(),
-                    SensorValues {
-                        start_pos: level.map.tiled_to_bevy_coord(Vec2::new(5300.0, 800.0)),
-                        end_pos: level.map.tiled_to_bevy_coord(Vec2::new(5300.0, 600.0)),
-                        disable_next_collision: false,
-                    },
-                ),
-                (
-                    "rock03".to_string(),
-                    SensorValues {
-                        start_pos: level.map.tiled_to_bevy_coord(Vec2::new(5300.0, 800.0)),
-                        end_pos: level.map.tiled_to_bevy_coord(Vec2::new(5300.0, 600.0)),
-                        disable_next_collision: false,
-                    },
-                ),
-                (
-                    "exit01".to_string(),
-                    SensorValues {
-                        start_pos: Vec2::ZERO,
-                        end_pos: Vec2::ZERO,
-                        disable_next_collision: false,
-                    },
-                ),
-            ]),
+        debug!(
+            "Received collision event: {:?}, collider name: {:?}",
+            collision_event, collider_name
         );
 
-        match collision_event {
-            CollisionEvent::Started(e1, e2, _cf) => {
-                // Warning, e1 and e2 can be swapped.
-                if let Some((_entity, collider_name, mut active_collision_type)) = position_sensors
-                    .iter_mut()
-                    .find(|(entity, _collider_name, _active_collision_type)| {
-                        (entity == e1 && player_entity == *e2)
-                            || (entity == e2 && player_entity == *e1)
-                    })
-                {
-                    debug!(
-                        "Received collision event: {:?}, collider name: {:?}",
-                        collision_event, collider_name
-                    );
-
-                    if let Some(collider) = level_sensor_pos.get(&current_level.id) {
-                        if let Some(sensor_values) = collider.get(&collider_name.0) {
-                            if sensor_values.disable_next_collision {
-                                *active_collision_type = ActiveCollisionTypes::STATIC_STATIC;
-                            }
-                            event_start.write(PositionSensorCollisionStart {
-                                sensor_name: collider_name.0.clone(),
-                                spawn_pos: sensor_values.start_pos,
-                                exit_pos: sensor_values.end_pos,
-                            });
-                        }
-                    }
-                };
-            }
-            CollisionEvent::Stopped(e1, e2, _cf) => {
-                // Warning, e1 and e2 can be swapped.
-                if let Some((_entity, collider_name, _active_collision_type)) = position_sensors
-                    .iter()
-                    .find(|(entity, _collider_name, _active_collision_type)| {
-                        (entity == e1 && player_entity == *e2)
-                            || (entity == e2 && player_entity == *e1)
-                    })
-                {
-                    debug!(
-                        "Received collision event: {:?}, collider name: {:?}",
-                        collision_event, collider_name
-                    );
+        if entered && sensor_values.disable_next_collision {
+            *active_collision_type = ActiveCollisionTypes::STATIC_STATIC;
+        }
 
-                    event_stop.write(PositionSensorCollisionStop {
-                        sensor_name: collider_name.0.clone(),
-                    });
-                };
-            }
+        // Sensors with an unknown trigger have been reported when the level was loaded.
+        if let Some(trigger) = trigger {
+            run_trigger_handlers(
+                &mut commands,
+                &trigger_handlers,
+                TriggeredSensor {
+                    sensor_name: collider_name.0.clone(),
+                    trigger: trigger.clone(),
+                    entered,
+                },
+            );
         }
     }
 }
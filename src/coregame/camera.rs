@@ -1,16 +1,18 @@
 use std::f32::consts::PI;
 
 use crate::{
+    WINDOW_HEIGHT, WINDOW_WIDTH,
     coregame::state::AppState,
-    events::{Restart, ShakeCamera, StartGame},
+    events::{NextLevel, Restart, ShakeCamera, StartGame},
     player::PlayerSet,
-    screen_map::Transition,
 };
 use bevy::prelude::*;
+use bevy_rapier2d::plugin::RapierConfiguration;
+use screen_map::{CameraTarget, Transition};
 
 use crate::{
     coregame::level::{CurrentLevel, Level},
-    player::{PLAYER_SPEED, Player, PlayerState},
+    player::{PLAYER_HEIGHT, PLAYER_SPEED, PLAYER_WIDTH, Player, PlayerState},
 };
 pub struct CameraPlugin;
 
@@ -20,17 +22,75 @@ pub struct CameraSet;
 #[derive(Debug, Resource, Eq, PartialEq, Clone, Copy, Default)]
 pub struct Shake(bool);
 
+/// Animated transition being played between two screens
+///
+/// The physics are paused, player inputs and collisions are frozen while a transition is played.
+#[derive(Debug, Resource, Default)]
+pub struct ScreenTransition(Option<PlayingTransition>);
+
+#[derive(Debug)]
+struct PlayingTransition {
+    transition: Transition,
+    timer: Timer,
+    from: Vec2,
+    to: Vec2,
+}
+
+#[derive(Component)]
+struct TransitionOverlay;
+
+/// What the camera remembers about the player between two frames
+///
+/// Cleared when a game starts, on a new level and on restart: the player is moved to its start
+/// position, which is not a movement the camera should follow.
+#[derive(Debug, Resource, Default)]
+struct CameraFollow {
+    /// Position of the player on the previous frame, used for the look-ahead velocity
+    last_player_pos: Option<Vec2>,
+    /// Indices of the screen of the player on the previous frame, used to detect transitions
+    last_player_screen: Option<(usize, usize)>,
+}
+
+/// Run condition returning `true` when no screen transition is being played
+pub fn no_screen_transition(screen_transition: Res<ScreenTransition>) -> bool {
+    screen_transition.0.is_none()
+}
+
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(AppState::Loading), setup_camera);
         app.add_systems(
             OnEnter(AppState::GameCreate),
-            move_camera_to_level_start_screen,
+            (
+                move_camera_to_level_start_screen,
+                reset_screen_transition,
+                reset_camera_follow,
+            ),
+        )
+        .add_systems(
+            OnEnter(AppState::NextLevel),
+            (reset_screen_transition, reset_camera_follow),
         )
         .add_systems(OnEnter(AppState::StartMenu), move_camera_to_center)
         .add_systems(
             Update,
-            (camera_follows_player, shake_camera)
+            reset_camera_follow
+                .run_if(
+                    on_event::<StartGame>
+                        .or(on_event::<Restart>)
+                        .or(on_event::<NextLevel>),
+                )
+                .before(CameraSet),
+        )
+        .add_systems(
+            Update,
+            (
+                play_screen_transition,
+                camera_follows_player.run_if(no_screen_transition),
+                pause_physics_during_transition,
+                keep_player_in_view,
+                shake_camera,
+            )
                 .chain()
                 .in_set(CameraSet)
                 .after(PlayerSet)
@@ -38,6 +98,8 @@ impl Plugin for CameraPlugin {
         );
         app.add_event::<ShakeCamera>();
         app.insert_resource(Shake::default());
+        app.insert_resource(ScreenTransition::default());
+        app.init_resource::<CameraFollow>();
     }
 }
 
@@ -76,11 +138,19 @@ fn camera_follows_player(
     current_level: Res<CurrentLevel>,
     levels: Query<&Level, With<Level>>,
     mut offset: Local<Vec2>,
+    mut camera_follow: ResMut<CameraFollow>,
+    mut current_transition: ResMut<ScreenTransition>,
     shake: Res<Shake>,
 ) -> Result<()> {
     let mut camera = camera_query.single_mut()?;
     let player = player_query.single()?;
 
+    let player_velocity = match (camera_follow.last_player_pos, time.delta_secs()) {
+        (Some(last_pos), delta) if delta > 0.0 => (player.translation.xy() - last_pos) / delta,
+        _ => Vec2::ZERO,
+    };
+    camera_follow.last_player_pos = Some(player.translation.xy());
+
     levels
         .iter()
         .filter(|level| level.id == current_level.id)
@@ -103,6 +173,34 @@ fn camera_follows_player(
                     .get_center()
                     .y;
             }
+            // Animated transitions are played when entering or leaving their screen
+            if let Some(screen) = level.map.get_screen(player.translation.xy(), 0.0, 0.0) {
+                let indices = screen.get_indices();
+                let previous_screen = camera_follow
+                    .last_player_screen
+                    .replace(indices)
+                    .filter(|previous| *previous != indices)
+                    .and_then(|(x, y)| level.map.get_screen_from_index(x, y));
+                if let Some(previous_screen) = previous_screen {
+                    let transition = [screen.get_transition(), previous_screen.get_transition()]
+                        .into_iter()
+                        .find(Transition::is_animated);
+                    if let Some(transition) = transition {
+                        debug!("play {:?} to screen {:?}", transition, indices);
+                        current_transition.0 = Some(PlayingTransition {
+                            transition,
+                            timer: Timer::new(
+                                transition.get_duration().unwrap_or_default(),
+                                TimerMode::Once,
+                            ),
+                            from: camera.translation.xy(),
+                            to: screen.get_center(),
+                        });
+                        return;
+                    }
+                }
+            }
+
             let (screen_center, screen_is_fixed, screen_transition) =
                 match level.map.get_screen(player.translation.xy(), 0.0, 0.0) {
                     Some(screen) => (
@@ -130,14 +228,6 @@ fn camera_follows_player(
                 above_screen_is_fixed,
                 above_screen_transition,
             ) {
-                (true, Transition::Hard, _, _) => {
-                    // Hard camera transition going down
-                    Vec2::new(player.translation.x, player.translation.y + dist.y)
-                }
-                (false, _, true, Transition::Hard) => {
-                    // Hard camera transition going up
-                    Vec2::new(player.translation.x, player.translation.y + dist.y)
-                }
                 (true, Transition::Smooth, _, _) => {
                     // Smooth camera transition going down
                     if dist.y > 0.0
@@ -180,20 +270,204 @@ fn camera_follows_player(
                     trace!("offset: {:?}", offset);
                     Vec2::new(player.translation.x, player.translation.y + offset.y)
                 }
+                (true, _, _, _) => {
+                    // Hard (or animated) camera transition going down
+                    Vec2::new(player.translation.x, player.translation.y + dist.y)
+                }
+                (false, _, true, _) => {
+                    // Hard (or animated) camera transition going up
+                    Vec2::new(player.translation.x, player.translation.y + dist.y)
+                }
                 _ => {
                     // The camera follows the player
                     Vec2::new(player.translation.x, player.translation.y)
                 }
             };
 
+            let target = CameraTarget {
+                position: new_camera_pos,
+                velocity: player_velocity,
+                grounded: !(*player_state == PlayerState::Falling
+                    || *player_state == PlayerState::Jumping),
+            };
             camera.translation = level
                 .map
-                .move_camera(&time, camera.translation.xy(), new_camera_pos)
+                .move_camera_to_target(&time, camera.translation.xy(), &target)
                 .extend(0.0);
         });
     Ok(())
 }
 
+fn reset_screen_transition(
+    mut commands: Commands,
+    mut screen_transition: ResMut<ScreenTransition>,
+    overlays: Query<Entity, With<TransitionOverlay>>,
+) {
+    screen_transition.0 = None;
+    for overlay in overlays.iter() {
+        commands.entity(overlay).despawn();
+    }
+}
+
+/// Pauses the physics while a transition is played, the collisions happening during the
+/// transition would be lost as the systems reading them are not run.
+///
+/// The physics are resumed once, when the transition ends, to not undo a pause of the game.
+fn pause_physics_during_transition(
+    screen_transition: Res<ScreenTransition>,
+    mut rapier_config: Query<&mut RapierConfiguration>,
+    mut was_playing: Local<bool>,
+) -> Result<()> {
+    let playing = screen_transition.0.is_some();
+    if playing || *was_playing {
+        rapier_config.single_mut()?.physics_pipeline_active = !playing;
+    }
+    *was_playing = playing;
+    Ok(())
+}
+
+fn reset_camera_follow(mut camera_follow: ResMut<CameraFollow>) {
+    *camera_follow = CameraFollow::default();
+}
+
+fn play_screen_transition(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut screen_transition: ResMut<ScreenTransition>,
+    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+    mut overlays: Query<(Entity, &mut Node, &mut BackgroundColor), With<TransitionOverlay>>,
+) -> Result<()> {
+    let Some(playing) = screen_transition.0.as_mut() else {
+        return Ok(());
+    };
+    let mut camera = camera_query.single_mut()?;
+
+    // The iris circle must cover the whole screen when fully open
+    let iris_max_diameter = WINDOW_WIDTH.hypot(WINDOW_HEIGHT);
+
+    if overlays.is_empty() {
+        match playing.transition {
+            Transition::Fade(_) => {
+                commands.spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::NONE),
+                    GlobalZIndex(i32::MAX),
+                    TransitionOverlay,
+                ));
+            }
+            Transition::Iris(_) => {
+                commands.spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px((WINDOW_WIDTH - iris_max_diameter) / 2.0),
+                        top: Val::Px((WINDOW_HEIGHT - iris_max_diameter) / 2.0),
+                        width: Val::Px(iris_max_diameter),
+                        height: Val::Px(iris_max_diameter),
+                        ..default()
+                    },
+                    BackgroundColor(Color::NONE),
+                    BorderRadius::MAX,
+                    Outline::new(Val::Px(iris_max_diameter), Val::ZERO, Color::BLACK),
+                    GlobalZIndex(i32::MAX),
+                    TransitionOverlay,
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    playing.timer.tick(time.delta());
+    let progress = playing.timer.fraction();
+    // 1.0 at the beginning and the end of the transition, 0.0 in the middle
+    let openness = (2.0 * progress - 1.0).abs();
+
+    match playing.transition {
+        Transition::Slide(_) => {
+            camera.translation = playing.from.lerp(playing.to, progress).extend(0.0);
+        }
+        // Switch screens while they can't be seen
+        _ if progress >= 0.5 => camera.translation = playing.to.extend(0.0),
+        _ => {}
+    }
+
+    for (_, mut node, mut background) in overlays.iter_mut() {
+        match playing.transition {
+            Transition::Fade(_) => background.0 = Color::BLACK.with_alpha(1.0 - openness),
+            Transition::Iris(_) => {
+                let diameter = iris_max_diameter * openness;
+                node.left = Val::Px((WINDOW_WIDTH - diameter) / 2.0);
+                node.top = Val::Px((WINDOW_HEIGHT - diameter) / 2.0);
+                node.width = Val::Px(diameter);
+                node.height = Val::Px(diameter);
+            }
+            _ => {}
+        }
+    }
+
+    if playing.timer.finished() {
+        camera.translation = playing.to.extend(0.0);
+        screen_transition.0 = None;
+        for (overlay, _, _) in overlays.iter() {
+            commands.entity(overlay).despawn();
+        }
+    }
+    Ok(())
+}
+
+/// Keeps the player inside locked screens and inside the camera view on auto-scroll screens
+fn keep_player_in_view(
+    current_level: Res<CurrentLevel>,
+    levels: Query<&Level, With<Level>>,
+    camera_query: Query<&Transform, (With<Camera2d>, Without<Player>)>,
+    mut player_query: Query<&mut Transform, With<Player>>,
+) -> Result<()> {
+    let camera = camera_query.single()?;
+    let mut player = player_query.single_mut()?;
+
+    for level in levels.iter().filter(|level| level.id == current_level.id) {
+        if let Some(screen) = level
+            .map
+            .get_screen(player.translation.xy(), 0.0, 0.0)
+            .filter(|screen| {
+                let (index_x, index_y) = screen.get_indices();
+                level.map.is_screen_locked(index_x, index_y)
+            })
+        {
+            let (x_range, _) = screen.get_ranges();
+            player.translation.x = player.translation.x.clamp(
+                x_range.start + PLAYER_WIDTH / 2.0,
+                x_range.end - PLAYER_WIDTH / 2.0,
+            );
+        }
+
+        if let Some(velocity) = level
+            .map
+            .get_screen(camera.translation.xy(), 0.0, 0.0)
+            .and_then(|screen| screen.get_auto_scroll())
+        {
+            // Only push the player along the scrolling axis
+            if velocity.x != 0.0 {
+                player.translation.x = player.translation.x.clamp(
+                    camera.translation.x - (WINDOW_WIDTH - PLAYER_WIDTH) / 2.0,
+                    camera.translation.x + (WINDOW_WIDTH - PLAYER_WIDTH) / 2.0,
+                );
+            }
+            if velocity.y != 0.0 {
+                player.translation.y = player.translation.y.clamp(
+                    camera.translation.y - (WINDOW_HEIGHT - PLAYER_HEIGHT) / 2.0,
+                    camera.translation.y + (WINDOW_HEIGHT - PLAYER_HEIGHT) / 2.0,
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
 fn shake_camera(
     mut shake_events: EventReader<ShakeCamera>,
     mut camera_query: Query<(&mut Transform, &mut Projection), With<Camera2d>>,
@@ -3,10 +3,23 @@ use bevy_rapier2d::geometry::{ActiveCollisionTypes, ActiveEvents, Collider, Sens
 use tiled::ObjectShape;
 
 use crate::{
+    collision_layers::CollisionLayer,
     coregame::level::{CurrentLevel, Level},
     coregame::state::AppState,
-    helpers::tiled::TiledMap,
+    coregame::triggers::SensorTrigger,
+    events::LevelReloaded,
+    helpers::tiled::{TiledMap, get_bool_property, get_float_property, get_string_property},
 };
+use screen_map::{
+    Map,
+    level_objects::{PlatformKind, breath_limit},
+};
+
+pub use screen_map::level_objects::SensorValues;
+
+/// Number of segments used to approximate a non-circular ellipse
+const ELLIPSE_SEGMENTS: usize = 16;
+const DEFAULT_BOUNCE_STRENGTH: f32 = 2.0;
 
 pub struct CollidersPlugin;
 
@@ -15,7 +28,13 @@ impl Plugin for CollidersPlugin {
         app.add_systems(OnEnter(AppState::GameCreate), setup_colliders)
             .add_systems(OnEnter(AppState::NextLevel), setup_colliders)
             .add_systems(OnEnter(AppState::StartMenu), despawn_colliders)
-            .add_systems(OnEnter(AppState::FinishLevel), despawn_colliders);
+            .add_systems(OnEnter(AppState::FinishLevel), despawn_colliders)
+            .add_systems(
+                Update,
+                (despawn_colliders, setup_colliders)
+                    .chain()
+                    .run_if(on_event::<LevelReloaded>),
+            );
     }
 }
 
@@ -37,9 +56,207 @@ pub struct PositionSensor;
 #[derive(Component, Clone, Debug)]
 pub struct Ladder;
 
+#[derive(Component, Clone, Debug)]
+pub struct Water;
+
+/// Seconds the player can stay under the water before losing a life
+#[derive(Component, Clone, Debug)]
+pub struct BreathLimit(pub f32);
+
+#[derive(Component, Clone, Debug)]
+pub struct Wind;
+
+#[derive(Component, Clone, Debug)]
+pub struct Conveyor;
+
+#[derive(Component, Clone, Debug)]
+pub struct Ice;
+
+/// Continuous effect of a wind, conveyor or ice zone, read from the custom properties of its
+/// Tiled object:
+///
+/// - `direction`: "left", "right", "up" or "down", "right" by default
+/// - `strength`: speed given by winds and conveyors in pixels per second, between 0.0 (no effect)
+///   and 1.0 (no friction) for ice
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct ZoneEffect {
+    pub direction: Vec2,
+    pub strength: f32,
+}
+
+impl ZoneEffect {
+    fn from_tiled_object(object: &tiled::Object) -> Self {
+        let direction = match get_string_property(&object.properties, "direction") {
+            None | Some("right") => Vec2::X,
+            Some("left") => Vec2::NEG_X,
+            Some("up") => Vec2::Y,
+            Some("down") => Vec2::NEG_Y,
+            Some(direction) => {
+                warn!("Zone {}: invalid direction {}", object.name, direction);
+                Vec2::ZERO
+            }
+        };
+
+        Self {
+            direction,
+            strength: get_float_property(&object.properties, "strength").unwrap_or(0.0),
+        }
+    }
+}
+
+/// Platform the player jumps through from below and drops through with Crouch
+#[derive(Component, Clone, Debug)]
+pub struct OneWayPlatform {
+    /// Top of the platform in Bevy coordinates
+    pub top: f32,
+    /// The player is dropping through the platform
+    pub dropping: bool,
+}
+
+/// Platform shaking then falling a moment after the player lands on it
+#[derive(Component, Clone, Debug)]
+pub struct CrumblingPlatform {
+    pub origin: Vec3,
+    pub size: Vec2,
+}
+
+/// Platform launching the player upward
+#[derive(Component, Clone, Debug)]
+pub struct BouncePad {
+    /// Factor applied to the duration of a jump
+    pub strength: f32,
+}
+
 #[derive(Component, Clone, Debug, Hash, Eq, PartialEq)]
 pub struct ColliderName(pub String);
 
+impl SensorValues {
+    fn from_tiled_object(object: &tiled::Object, level: &Level) -> Self {
+        let position = |x_name: &str, y_name: &str| match (
+            get_float_property(&object.properties, x_name),
+            get_float_property(&object.properties, y_name),
+        ) {
+            (Some(x), Some(y)) => level.map.tiled_to_bevy_coord(Vec2::new(x, y)),
+            (None, None) => Vec2::ZERO,
+            _ => {
+                warn!(
+                    "Sensor {}: {} and {} must be both defined",
+                    object.name, x_name, y_name
+                );
+                Vec2::ZERO
+            }
+        };
+
+        Self {
+            start_pos: position("start_x", "start_y"),
+            end_pos: position("end_x", "end_y"),
+            disable_next_collision: get_bool_property(&object.properties, "disable_next_collision")
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// Converts a point given relative to a Tiled object origin to Bevy coordinates.
+///
+/// Tiled rotates objects clockwise around their origin, the rotation is given in degrees.
+fn object_point_to_bevy(map: &Map, origin: Vec2, rotation: f32, point: Vec2) -> Vec2 {
+    // Tiled y axis points down, so a positive angle is a clockwise rotation.
+    map.tiled_to_bevy_coord(origin + Vec2::from_angle(rotation.to_radians()).rotate(point))
+}
+
+/// Highest point of a Tiled object in Bevy coordinates, points are relative to the object origin
+fn object_top(map: &Map, origin: Vec2, rotation: f32, points: &[Vec2]) -> f32 {
+    points
+        .iter()
+        .map(|point| object_point_to_bevy(map, origin, rotation, *point).y)
+        .fold(f32::NEG_INFINITY, f32::max)
+}
+
+/// Points of a polygon approximating an ellipse centered on the origin
+fn ellipse_points(half_width: f32, half_height: f32, segments: usize) -> Vec<Vec2> {
+    (0..segments)
+        .map(|i| {
+            let angle = i as f32 * std::f32::consts::TAU / segments as f32;
+            Vec2::new(half_width * angle.cos(), half_height * angle.sin())
+        })
+        .collect()
+}
+
+/// Twice the signed area of a polygon, positive when its points are counter-clockwise
+fn signed_area(points: &[Vec2]) -> f32 {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a.perp_dot(*b))
+        .sum()
+}
+
+fn is_convex(points: &[Vec2]) -> bool {
+    let n = points.len();
+    let turns: Vec<f32> = (0..n)
+        .map(|i| {
+            let (a, b, c) = (points[i], points[(i + 1) % n], points[(i + 2) % n]);
+            (b - a).perp_dot(c - b)
+        })
+        .filter(|turn| turn.abs() > f32::EPSILON)
+        .collect();
+
+    turns.iter().all(|turn| *turn > 0.0) || turns.iter().all(|turn| *turn < 0.0)
+}
+
+fn triangle_contains(a: Vec2, b: Vec2, c: Vec2, point: Vec2) -> bool {
+    (b - a).perp_dot(point - a) >= 0.0
+        && (c - b).perp_dot(point - b) >= 0.0
+        && (a - c).perp_dot(point - c) >= 0.0
+}
+
+/// Splits a simple polygon in triangles using ear clipping.
+///
+/// Returns `None` when the polygon has less than 3 points or crosses itself.
+fn triangulate(points: &[Vec2]) -> Option<Vec<[Vec2; 3]>> {
+    if points.len() < 3 {
+        return None;
+    }
+
+    // Ears are searched in counter-clockwise order.
+    let mut indices: Vec<usize> = (0..points.len()).collect();
+    if signed_area(points) < 0.0 {
+        indices.reverse();
+    }
+
+    let mut triangles = Vec::new();
+    while indices.len() > 2 {
+        let n = indices.len();
+        let corner = |i: usize| {
+            (
+                points[indices[(i + n - 1) % n]],
+                points[indices[i]],
+                points[indices[(i + 1) % n]],
+            )
+        };
+
+        let ear = (0..n).find(|&i| {
+            let (a, b, c) = corner(i);
+            let turn = (b - a).perp_dot(c - b);
+            // Aligned points are dropped without creating a triangle.
+            turn.abs() <= f32::EPSILON
+                || (turn > 0.0
+                    && indices.iter().all(|&j| {
+                        let point = points[j];
+                        point == a || point == b || point == c || !triangle_contains(a, b, c, point)
+                    }))
+        })?;
+
+        let (a, b, c) = corner(ear);
+        if (b - a).perp_dot(c - b).abs() > f32::EPSILON {
+            triangles.push([a, b, c]);
+        }
+        indices.remove(ear);
+    }
+
+    Some(triangles)
+}
+
 fn tiled_object_to_collider<T: Component + Clone>(
     commands: &mut Commands,
     tiled_map: &TiledMap,
@@ -62,63 +279,58 @@ fn tiled_object_to_collider<T: Component + Clone>(
             debug!("Found object {:?}", object.name);
             debug!("Shape {:?}", object.shape);
 
-            match &object.shape {
+            // Points are always used as sensors.
+            let origin = Vec2::new(object.x, object.y);
+            let rotation = Quat::from_rotation_z(-object.rotation.to_radians());
+            let (collider, transform, sensor) = match &object.shape {
                 ObjectShape::Rect { width, height } => {
-                    let Vec2 { x, y } = level.map.tiled_to_bevy_coord(Vec2::new(
-                        object.x + *width / 2.0,
-                        object.y + *height / 2.0,
-                    ));
-
-                    if bridge.sensor {
-                        commands
-                            .spawn((
-                                Collider::cuboid(*width / 2.0, *height / 2.0),
-                                bridge.component.clone(),
-                                Transform::from_xyz(x, y, 0.0),
-                            ))
-                            .insert(Sensor)
-                            .insert(ActiveEvents::COLLISION_EVENTS)
-                            .insert(ActiveCollisionTypes::KINEMATIC_STATIC)
-                            .insert(ColliderName(object.name.clone()));
-                    } else {
-                        commands
-                            .spawn((
-                                Collider::cuboid(*width / 2.0, *height / 2.0),
-                                bridge.component.clone(),
-                                Transform::from_xyz(x, y, 0.0),
-                            ))
-                            .insert(ColliderName(object.name.clone()));
-                    }
+                    let center = object_point_to_bevy(
+                        &level.map,
+                        origin,
+                        object.rotation,
+                        Vec2::new(*width / 2.0, *height / 2.0),
+                    );
+
+                    (
+                        Collider::cuboid(*width / 2.0, *height / 2.0),
+                        Transform::from_translation(center.extend(0.0)).with_rotation(rotation),
+                        bridge.sensor,
+                    )
                 }
                 ObjectShape::Polygon { points } => {
                     let points: Vec<Vec2> = points
                         .iter()
                         .map(|(x, y)| {
-                            level.map.tiled_to_bevy_coord(
-                                Vec2::new(*x, *y) + Vec2::new(object.x, object.y),
+                            object_point_to_bevy(
+                                &level.map,
+                                origin,
+                                object.rotation,
+                                Vec2::new(*x, *y),
                             )
                         })
                         .collect();
 
                     debug!("Polygon points: {:?}", points);
 
-                    match Collider::convex_hull(&points) {
-                        Some(collider) => {
-                            if bridge.sensor {
-                                commands
-                                    .spawn((collider, bridge.component.clone()))
-                                    .insert(Sensor)
-                                    .insert(ActiveEvents::COLLISION_EVENTS)
-                                    .insert(ActiveCollisionTypes::KINEMATIC_STATIC)
-                                    .insert(ColliderName(object.name.clone()));
-                            } else {
-                                commands
-                                    .spawn((collider, bridge.component.clone()))
-                                    .insert(ColliderName(object.name.clone()));
-                            }
-                        }
+                    let collider = if is_convex(&points) {
+                        Collider::convex_hull(&points)
+                    } else {
+                        // Concave polygons are split in triangles, a convex hull would fill them
+                        triangulate(&points).map(|triangles| {
+                            Collider::compound(
+                                triangles
+                                    .into_iter()
+                                    .map(|[a, b, c]| (Vec2::ZERO, 0.0, Collider::triangle(a, b, c)))
+                                    .collect(),
+                            )
+                        })
+                    };
+
+                    match collider {
+                        Some(collider) => (collider, Transform::default(), bridge.sensor),
                         None => {
-                            error!("Failed to create convex hull");
+                            error!("Failed to create polygon collider {}", object.name);
+                            return;
                         }
                     }
                 }
@@ -126,84 +338,190 @@ fn tiled_object_to_collider<T: Component + Clone>(
                     let points: Vec<Vec2> = points
                         .iter()
                         .map(|(x, y)| {
-                            level.map.tiled_to_bevy_coord(
-                                Vec2::new(*x, *y) + Vec2::new(object.x, object.y),
+                            object_point_to_bevy(
+                                &level.map,
+                                origin,
+                                object.rotation,
+                                Vec2::new(*x, *y),
                             )
                         })
                         .collect();
 
                     debug!("Polyline points: {:?}", points);
 
-                    if bridge.sensor {
-                        commands
-                            .spawn((Collider::polyline(points, None), bridge.component.clone()))
-                            .insert(Sensor)
-                            .insert(ActiveEvents::COLLISION_EVENTS)
-                            .insert(ActiveCollisionTypes::KINEMATIC_STATIC)
-                            .insert(ColliderName(object.name.clone()));
-                    } else {
-                        commands
-                            .spawn((Collider::polyline(points, None), bridge.component.clone()))
-                            .insert(ColliderName(object.name.clone()));
-                    }
+                    (
+                        Collider::polyline(points, None),
+                        Transform::default(),
+                        bridge.sensor,
+                    )
                 }
                 ObjectShape::Text { .. } => {
                     warn!("Text shape not supported");
+                    return;
                 }
                 ObjectShape::Ellipse { width, height } => {
-                    let Vec2 { x, y } = level.map.tiled_to_bevy_coord(Vec2::new(
-                        object.x + *width / 2.0,
-                        object.y + *height / 2.0,
-                    ));
-                    if *width != *height {
-                        warn!("Ellipse shape not supported: {:?}x{:?}", width, height);
-                    }
-                    if bridge.sensor {
-                        commands
-                            .spawn((
-                                Collider::ball(*width / 2.0),
-                                bridge.component.clone(),
-                                // Platform,
-                                Transform::from_xyz(x, y, 0.0),
-                            ))
-                            .insert(Sensor)
-                            .insert(ActiveEvents::COLLISION_EVENTS)
-                            .insert(ActiveCollisionTypes::KINEMATIC_STATIC)
-                            .insert(ColliderName(object.name.clone()));
+                    let center = object_point_to_bevy(
+                        &level.map,
+                        origin,
+                        object.rotation,
+                        Vec2::new(*width / 2.0, *height / 2.0),
+                    );
+
+                    let collider = if *width == *height {
+                        Some(Collider::ball(*width / 2.0))
                     } else {
-                        commands
-                            .spawn((
-                                Collider::ball(*width / 2.0),
-                                bridge.component.clone(),
-                                // Platform,
-                                Transform::from_xyz(x, y, 0.0),
-                            ))
-                            .insert(ColliderName(object.name.clone()));
+                        Collider::convex_hull(&ellipse_points(
+                            *width / 2.0,
+                            *height / 2.0,
+                            ELLIPSE_SEGMENTS,
+                        ))
+                    };
+
+                    match collider {
+                        Some(collider) => (
+                            collider,
+                            Transform::from_translation(center.extend(0.0)).with_rotation(rotation),
+                            bridge.sensor,
+                        ),
+                        None => {
+                            error!("Failed to create ellipse collider {}", object.name);
+                            return;
+                        }
                     }
                 }
                 ObjectShape::Point(x, y) => {
-                    // Used as a sensor
                     let Vec2 { x, y } = level.map.tiled_to_bevy_coord(Vec2::new(*x, *y));
-                    commands
-                        .spawn((
-                            Collider::cuboid(1.0, 1.0),
-                            bridge.component.clone(),
-                            Transform::from_xyz(x, y, 0.0),
-                        ))
-                        .insert(Sensor)
-                        .insert(ActiveEvents::COLLISION_EVENTS)
-                        .insert(ActiveCollisionTypes::KINEMATIC_STATIC)
-                        .insert(ColliderName(object.name.clone()));
+
+                    (
+                        Collider::cuboid(1.0, 1.0),
+                        Transform::from_xyz(x, y, 0.0),
+                        true,
+                    )
                 }
             };
+
+            let mut entity = commands.spawn((
+                collider,
+                bridge.component.clone(),
+                transform,
+                ColliderName(object.name.clone()),
+                bridge.collision_layer.groups(),
+            ));
+            if sensor {
+                entity.insert((
+                    Sensor,
+                    ActiveEvents::COLLISION_EVENTS,
+                    ActiveCollisionTypes::KINEMATIC_STATIC,
+                ));
+            }
+            if bridge.sensor_values {
+                let sensor_values = SensorValues::from_tiled_object(&object, level);
+                if bridge.triggers {
+                    match SensorTrigger::from_sensor(&object.name, &sensor_values) {
+                        Ok(trigger) => {
+                            entity.insert(trigger);
+                        }
+                        Err(error) => error!("Sensor {}: {}", object.name, error),
+                    }
+                }
+                entity.insert(sensor_values);
+            }
+            if bridge.platform_kinds {
+                insert_platform_kind(&mut entity, &object, level, transform);
+            }
+            if bridge.zone_effect {
+                // Zones also affect the rocks and other dynamic bodies.
+                entity.insert((
+                    ZoneEffect::from_tiled_object(&object),
+                    ActiveCollisionTypes::KINEMATIC_STATIC | ActiveCollisionTypes::DYNAMIC_STATIC,
+                ));
+            }
+            if bridge.breath_limit {
+                match breath_limit(&object.properties) {
+                    Ok(Some(breath)) => {
+                        entity.insert(BreathLimit(breath));
+                    }
+                    Ok(None) => {}
+                    Err(error) => warn!("Water {}: {}", object.name, error),
+                }
+            }
         })
     })
 }
 
+/// Adds the marker of the platform variant selected by the class of the object, or its
+/// `platform` property when it has no class:
+///
+/// - `one_way`: the player jumps through it from below
+/// - `crumbling`: falls a moment after the player lands on it, must be a rectangle
+/// - `bounce`: launches the player, the `strength` property scales the jump (2.0 by default)
+fn insert_platform_kind(
+    entity: &mut EntityCommands,
+    object: &tiled::Object,
+    level: &Level,
+    transform: Transform,
+) {
+    let kind = match PlatformKind::from_object(&object.user_type, &object.properties) {
+        Ok(Some(kind)) => kind,
+        Ok(None) => return,
+        Err(error) => {
+            warn!("Platform {}: {}", object.name, error);
+            return;
+        }
+    };
+
+    match (kind, &object.shape) {
+        (PlatformKind::OneWay, shape) => {
+            let points = match shape {
+                ObjectShape::Rect { width, height } | ObjectShape::Ellipse { width, height } => {
+                    vec![
+                        Vec2::ZERO,
+                        Vec2::new(*width, 0.0),
+                        Vec2::new(*width, *height),
+                        Vec2::new(0.0, *height),
+                    ]
+                }
+                ObjectShape::Polygon { points } | ObjectShape::Polyline { points } => {
+                    points.iter().map(|(x, y)| Vec2::new(*x, *y)).collect()
+                }
+                _ => vec![Vec2::ZERO],
+            };
+            let origin = Vec2::new(object.x, object.y);
+
+            entity.insert(OneWayPlatform {
+                top: object_top(&level.map, origin, object.rotation, &points),
+                dropping: false,
+            });
+        }
+        (PlatformKind::Crumbling, ObjectShape::Rect { width, height }) => {
+            entity.insert(CrumblingPlatform {
+                origin: transform.translation,
+                size: Vec2::new(*width, *height),
+            });
+        }
+        (PlatformKind::Bounce, _) => {
+            entity.insert(BouncePad {
+                strength: get_float_property(&object.properties, "strength")
+                    .unwrap_or(DEFAULT_BOUNCE_STRENGTH),
+            });
+        }
+        (kind, shape) => warn!(
+            "Platform {}: {:?} platform not supported for {:?}",
+            object.name, kind, shape
+        ),
+    }
+}
+
 struct LayerComponentBridge<'a, T: Component + Clone> {
     layer: &'a str,
     component: T,
     sensor: bool,
+    collision_layer: CollisionLayer,
+    sensor_values: bool,
+    triggers: bool,
+    platform_kinds: bool,
+    breath_limit: bool,
+    zone_effect: bool,
 }
 
 impl<'a, T: Component + Clone> LayerComponentBridge<'a, T> {
@@ -212,8 +530,49 @@ impl<'a, T: Component + Clone> LayerComponentBridge<'a, T> {
             layer,
             component,
             sensor,
+            collision_layer: if sensor {
+                CollisionLayer::Sensor
+            } else {
+                CollisionLayer::Terrain
+            },
+            sensor_values: false,
+            triggers: false,
+            platform_kinds: false,
+            breath_limit: false,
+            zone_effect: false,
         }
     }
+
+    /// Attaches the [`SensorValues`] read from the object properties
+    fn with_sensor_values(mut self) -> Self {
+        self.sensor_values = true;
+        self
+    }
+
+    /// Attaches the [`SensorTrigger`] parsed from the object name, requires the sensor values
+    fn with_triggers(mut self) -> Self {
+        self.triggers = true;
+        self
+    }
+
+    /// Attaches the platform variant markers selected by the object classes
+    fn with_platform_kinds(mut self) -> Self {
+        self.platform_kinds = true;
+        self
+    }
+
+    /// Attaches the [`BreathLimit`] given by the `breath` property of the objects
+    fn with_breath_limit(mut self) -> Self {
+        self.breath_limit = true;
+        self
+    }
+
+    /// Attaches the [`ZoneEffect`] read from the object properties, in the zone collision layer
+    fn with_zone_effect(mut self) -> Self {
+        self.zone_effect = true;
+        self.collision_layer = CollisionLayer::Zone;
+        self
+    }
 }
 
 fn setup_colliders(
@@ -239,7 +598,8 @@ fn setup_colliders(
             let ground = LayerComponentBridge::new("Ground", Ground, false);
             tiled_object_to_collider(&mut commands, tiled_map, level, ground);
 
-            let platforms = LayerComponentBridge::new("Platforms", Platform, false);
+            let platforms =
+                LayerComponentBridge::new("Platforms", Platform, false).with_platform_kinds();
             tiled_object_to_collider(&mut commands, tiled_map, level, platforms);
 
             let spikes = LayerComponentBridge::new("Spikes", Spike, false);
@@ -249,15 +609,30 @@ fn setup_colliders(
             tiled_object_to_collider(&mut commands, tiled_map, level, stories);
 
             let position_sensors =
-                LayerComponentBridge::new("PositionSensors", PositionSensor, true);
+                LayerComponentBridge::new("PositionSensors", PositionSensor, true)
+                    .with_sensor_values()
+                    .with_triggers();
             tiled_object_to_collider(&mut commands, tiled_map, level, position_sensors);
 
             let ladders = LayerComponentBridge::new("Ladders", Ladder, true);
             tiled_object_to_collider(&mut commands, tiled_map, level, ladders);
+
+            let water = LayerComponentBridge::new("Water", Water, true).with_breath_limit();
+            tiled_object_to_collider(&mut commands, tiled_map, level, water);
+
+            let winds = LayerComponentBridge::new("Wind", Wind, true).with_zone_effect();
+            tiled_object_to_collider(&mut commands, tiled_map, level, winds);
+
+            let conveyors =
+                LayerComponentBridge::new("Conveyors", Conveyor, true).with_zone_effect();
+            tiled_object_to_collider(&mut commands, tiled_map, level, conveyors);
+
+            let ice = LayerComponentBridge::new("Ice", Ice, true).with_zone_effect();
+            tiled_object_to_collider(&mut commands, tiled_map, level, ice);
         });
 }
 
-fn despawn_colliders(
+pub fn despawn_colliders(
     mut commands: Commands,
     ground_query: Query<(Entity, &Collider), With<Ground>>,
     platforms_query: Query<(Entity, &Collider), With<Platform>>,
@@ -265,6 +640,8 @@ fn despawn_colliders(
     stories_query: Query<(Entity, &Collider), With<Story>>,
     position_sensors_query: Query<(Entity, &Collider), With<PositionSensor>>,
     ladders_query: Query<(Entity, &Collider), With<Ladder>>,
+    water_query: Query<(Entity, &Collider), With<Water>>,
+    zones_query: Query<(Entity, &Collider), With<ZoneEffect>>,
 ) {
     for (entity, _) in ground_query.iter() {
         commands.entity(entity).despawn();
@@ -289,4 +666,158 @@ fn despawn_colliders(
     for (entity, _) in ladders_query.iter() {
         commands.entity(entity).despawn();
     }
+
+    for (entity, _) in water_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    for (entity, _) in zones_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn map() -> Map {
+        Map::new("XOX\nSOO\nXXX", 1280, 720)
+    }
+
+    fn assert_near(left: Vec2, right: Vec2) {
+        assert!(left.abs_diff_eq(right, 1e-3), "{left:?} != {right:?}");
+    }
+
+    #[test]
+    fn test_object_point_without_rotation() {
+        let map = map();
+        let origin = Vec2::new(100.0, 200.0);
+
+        assert_near(
+            object_point_to_bevy(&map, origin, 0.0, Vec2::new(20.0, 10.0)),
+            map.tiled_to_bevy_coord(Vec2::new(120.0, 210.0)),
+        );
+    }
+
+    #[test]
+    fn test_object_point_with_rotation() {
+        let map = map();
+        let origin = Vec2::new(100.0, 200.0);
+
+        // A clockwise quarter turn in Tiled moves a point on the right of the origin below it.
+        assert_near(
+            object_point_to_bevy(&map, origin, 90.0, Vec2::new(20.0, 10.0)),
+            map.tiled_to_bevy_coord(Vec2::new(90.0, 220.0)),
+        );
+        assert_near(
+            object_point_to_bevy(&map, origin, 90.0, Vec2::new(20.0, 0.0)),
+            map.tiled_to_bevy_coord(origin) - Vec2::new(0.0, 20.0),
+        );
+        assert_near(
+            object_point_to_bevy(&map, origin, 180.0, Vec2::new(20.0, 10.0)),
+            map.tiled_to_bevy_coord(Vec2::new(80.0, 190.0)),
+        );
+    }
+
+    #[test]
+    fn test_collider_rotation_matches_points() {
+        let map = map();
+        let origin = Vec2::new(100.0, 200.0);
+        let rotation = 30.0;
+        let center = object_point_to_bevy(&map, origin, rotation, Vec2::new(20.0, 10.0));
+        let corner = object_point_to_bevy(&map, origin, rotation, Vec2::new(40.0, 20.0));
+
+        // The bottom right corner of the rectangle, rotated the way the collider is.
+        let collider_corner = center
+            + (Quat::from_rotation_z(-f32::to_radians(rotation)) * Vec3::new(20.0, -10.0, 0.0))
+                .truncate();
+
+        assert_near(collider_corner, corner);
+    }
+
+    #[test]
+    fn test_object_top() {
+        let map = map();
+        let origin = Vec2::new(100.0, 200.0);
+        let points = [Vec2::ZERO, Vec2::new(40.0, 20.0)];
+
+        assert_eq!(
+            object_top(&map, origin, 0.0, &points),
+            map.tiled_to_bevy_coord(origin).y
+        );
+        // Upside down, the object is above its origin.
+        assert!(
+            (object_top(&map, origin, 180.0, &points)
+                - map.tiled_to_bevy_coord(Vec2::new(60.0, 180.0)).y)
+                .abs()
+                < 1e-3
+        );
+    }
+
+    #[test]
+    fn test_ellipse_points() {
+        let points = ellipse_points(40.0, 10.0, 16);
+
+        assert_eq!(points.len(), 16);
+        assert_near(points[0], Vec2::new(40.0, 0.0));
+        assert_near(points[4], Vec2::new(0.0, 10.0));
+        assert!(is_convex(&points));
+    }
+
+    #[test]
+    fn test_is_convex() {
+        let square = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ];
+        let l_shape = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(20.0, 0.0),
+            Vec2::new(20.0, 10.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(10.0, 20.0),
+            Vec2::new(0.0, 20.0),
+        ];
+
+        assert!(is_convex(&square));
+        assert!(!is_convex(&l_shape));
+    }
+
+    #[test]
+    fn test_triangulate_concave_polygon() {
+        let map = map();
+        let origin = Vec2::new(100.0, 200.0);
+        // Clockwise in Bevy coordinates once the y axis is flipped.
+        let points: Vec<Vec2> = [
+            (0.0, 0.0),
+            (20.0, 0.0),
+            (20.0, 10.0),
+            (10.0, 10.0),
+            (10.0, 20.0),
+            (0.0, 20.0),
+        ]
+        .iter()
+        .map(|(x, y)| object_point_to_bevy(&map, origin, 0.0, Vec2::new(*x, *y)))
+        .collect();
+
+        let triangles = triangulate(&points).unwrap();
+        let area: f32 = triangles
+            .iter()
+            .map(|[a, b, c]| (b - a).perp_dot(c - a).abs() / 2.0)
+            .sum();
+
+        assert_eq!(triangles.len(), 4);
+        assert!((area - 300.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_triangulate_degenerate_polygon() {
+        assert_eq!(
+            triangulate(&[Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0)]),
+            None
+        );
+    }
 }
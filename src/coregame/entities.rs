@@ -0,0 +1,368 @@
+// Spawns the beasts and elements described in the "Entities" object layer of the level maps.
+//
+// The type (class) of each object selects the spawner registered for it with
+// `register_entity_spawner`. Spawners are one-shot systems receiving the object as an
+// `EntitySpawn`: its name, position and custom properties.
+//
+// Objects with the `deferred` boolean property are not spawned with the level, they are spawned
+// when a `SpawnLevelEntity` event names them (e.g. a key given as an enigma reward).
+//
+// Spawned entities are tagged with `LevelEntity`, they are spawned again when the map of the
+// current level is reloaded. Objects whose entity is gone since they were spawned (e.g. collected
+// nuts and lives) are not spawned again, deferred objects already spawned are.
+//
+// Entities tagged with `ResetOnRestart` (the beasts) are spawned again at their object when the
+// player restarts, except the ones already gone when the last checkpoint was reached.
+
+use bevy::{
+    ecs::system::SystemId,
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+use tiled::ObjectShape;
+
+use crate::{
+    coregame::{
+        level::{CurrentLevel, Level},
+        state::AppState,
+    },
+    elements::checkpoint::Checkpoint,
+    events::{LevelReloaded, Restart, SpawnLevelEntity},
+    helpers::tiled::{TiledMap, get_bool_property, get_float_property, get_string_property},
+};
+
+pub const ENTITIES_LAYER: &str = "Entities";
+const DEFERRED_PROPERTY: &str = "deferred";
+
+pub struct EntitiesPlugin;
+
+impl Plugin for EntitiesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EntitySpawners>()
+            .init_resource::<SpawnedObjects>()
+            .add_systems(
+                OnEnter(AppState::GameCreate),
+                (reset_spawned_objects, spawn_level_entities).chain(),
+            )
+            .add_systems(
+                OnEnter(AppState::NextLevel),
+                (reset_spawned_objects, spawn_level_entities).chain(),
+            )
+            .add_systems(
+                Update,
+                spawn_deferred_entities.run_if(in_state(AppState::GameRunning)),
+            )
+            .add_systems(
+                Update,
+                (despawn_level_entities, spawn_level_entities)
+                    .chain()
+                    .run_if(on_event::<LevelReloaded>),
+            )
+            .add_systems(
+                Update,
+                reset_level_entities
+                    .run_if(in_state(AppState::GameRunning).and(on_event::<Restart>)),
+            )
+            .add_event::<SpawnLevelEntity>();
+    }
+}
+
+/// An entity spawned from the Entities layer, with the name of its object
+#[derive(Component)]
+pub struct LevelEntity(String);
+
+impl LevelEntity {
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A level entity spawned again at its object when the player restarts
+#[derive(Component)]
+pub struct ResetOnRestart;
+
+/// Objects of the current level spawned so far
+#[derive(Resource, Default)]
+struct SpawnedObjects {
+    spawned: HashSet<String>,
+    /// Spawned objects tagged with `ResetOnRestart`
+    reset_on_restart: HashSet<String>,
+    /// Spawned objects whose entity is gone, not spawned again when the level is reloaded
+    gone: HashSet<String>,
+}
+
+/// An object of the Entities layer, given to its spawner
+#[derive(Debug, Clone)]
+pub struct EntitySpawn {
+    pub name: String,
+    /// Position of the object (center for shapes) in Bevy coordinates
+    pub position: Vec2,
+    /// Position of the object (center for shapes) in Tiled coordinates
+    tiled_position: Vec2,
+    /// Size of rectangle and ellipse objects, `None` for other objects
+    pub size: Option<Vec2>,
+    pub properties: tiled::Properties,
+}
+
+impl EntitySpawn {
+    pub fn get_float(&self, name: &str) -> Option<f32> {
+        get_float_property(&self.properties, name)
+    }
+
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        get_bool_property(&self.properties, name)
+    }
+
+    pub fn get_string(&self, name: &str) -> Option<&str> {
+        get_string_property(&self.properties, name)
+    }
+
+    /// Converts a point from Tiled coordinates to Bevy coordinates
+    pub fn tiled_to_bevy_coord(&self, tiled_coord: Vec2) -> Vec2 {
+        // Both coordinate systems only differ by their origin and the direction of the y axis.
+        let offset = tiled_coord - self.tiled_position;
+        self.position + Vec2::new(offset.x, -offset.y)
+    }
+
+    /// Returns the point defined by the `<name>_x` and `<name>_y` properties (in Tiled
+    /// coordinates), converted to Bevy coordinates
+    pub fn get_point(&self, name: &str) -> Option<Vec2> {
+        let x = self.get_float(&format!("{name}_x"))?;
+        let y = self.get_float(&format!("{name}_y"))?;
+        Some(self.tiled_to_bevy_coord(Vec2::new(x, y)))
+    }
+}
+
+/// Spawners registered by object type
+#[derive(Resource, Default)]
+pub struct EntitySpawners(HashMap<String, SystemId<In<EntitySpawn>, Option<Entity>>>);
+
+pub trait EntitySpawnerAppExt {
+    /// Registers the system spawning the objects of the given type from the Entities layer
+    ///
+    /// The spawner returns the spawned entity, or `None` if the object is invalid.
+    fn register_entity_spawner<M>(
+        &mut self,
+        kind: &str,
+        spawner: impl IntoSystem<In<EntitySpawn>, Option<Entity>, M> + 'static,
+    ) -> &mut Self;
+}
+
+impl EntitySpawnerAppExt for App {
+    fn register_entity_spawner<M>(
+        &mut self,
+        kind: &str,
+        spawner: impl IntoSystem<In<EntitySpawn>, Option<Entity>, M> + 'static,
+    ) -> &mut Self {
+        let world = self.world_mut();
+        let id = world.register_system(spawner);
+        if world
+            .get_resource_or_init::<EntitySpawners>()
+            .0
+            .insert(kind.to_string(), id)
+            .is_some()
+        {
+            warn!("Entity spawner for {} registered twice", kind);
+        }
+        self
+    }
+}
+
+/// Returns the objects of the Entities layer with their type
+fn get_entity_spawns(tiled_map: &TiledMap, level: &Level) -> Vec<(String, EntitySpawn)> {
+    tiled_map
+        .map
+        .layers()
+        .filter(|layer| layer.name == ENTITIES_LAYER)
+        .filter_map(|layer| match layer.layer_type() {
+            tiled::LayerType::Objects(object_data) => Some(object_data),
+            _ => None,
+        })
+        .flat_map(|object_data| {
+            object_data
+                .objects()
+                .map(|object| {
+                    let size = match object.shape {
+                        ObjectShape::Rect { width, height }
+                        | ObjectShape::Ellipse { width, height } => Some(Vec2::new(width, height)),
+                        _ => None,
+                    };
+                    let tiled_position =
+                        Vec2::new(object.x, object.y) + size.unwrap_or_default() / 2.0;
+
+                    (
+                        object.user_type.clone(),
+                        EntitySpawn {
+                            name: object.name.clone(),
+                            position: level.map.tiled_to_bevy_coord(tiled_position),
+                            tiled_position,
+                            size,
+                            properties: object.properties.clone(),
+                        },
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn run_spawner(
+    commands: &mut Commands,
+    spawners: &EntitySpawners,
+    kind: &str,
+    entity_spawn: EntitySpawn,
+) {
+    match spawners.0.get(kind).copied() {
+        Some(spawner) => {
+            debug!("Spawn {} {}", kind, entity_spawn.name);
+            commands.queue(move |world: &mut World| {
+                let name = entity_spawn.name.clone();
+                match world.run_system_with(spawner, entity_spawn) {
+                    Ok(Some(entity)) => {
+                        let mut entity = world.entity_mut(entity);
+                        entity.insert(LevelEntity(name.clone()));
+                        let reset_on_restart = entity.contains::<ResetOnRestart>();
+
+                        let mut spawned_objects = world.resource_mut::<SpawnedObjects>();
+                        if reset_on_restart {
+                            spawned_objects.reset_on_restart.insert(name.clone());
+                        }
+                        spawned_objects.spawned.insert(name);
+                    }
+                    // The spawner reports why the object is invalid.
+                    Ok(None) => {}
+                    Err(error) => error!("Entity {} not spawned: {}", name, error),
+                }
+            });
+        }
+        None => warn!(
+            "No spawner for entity {} of type \"{}\"",
+            entity_spawn.name, kind
+        ),
+    }
+}
+
+fn reset_spawned_objects(mut spawned_objects: ResMut<SpawnedObjects>) {
+    *spawned_objects = SpawnedObjects::default();
+}
+
+fn spawn_level_entities(
+    mut commands: Commands,
+    spawners: Res<EntitySpawners>,
+    spawned_objects: Res<SpawnedObjects>,
+    tiled_maps: Res<Assets<TiledMap>>,
+    levels: Query<&Level, With<Level>>,
+    current_level: Res<CurrentLevel>,
+) {
+    info!("spawn_level_entities");
+
+    let level = levels
+        .iter()
+        .find(|level| level.id == current_level.id)
+        .unwrap();
+
+    let Some(tiled_map) = tiled_maps.get(&level.handle) else {
+        return;
+    };
+
+    for (kind, entity_spawn) in get_entity_spawns(tiled_map, level) {
+        if spawned_objects.gone.contains(&entity_spawn.name) {
+            continue;
+        }
+        if entity_spawn.get_bool(DEFERRED_PROPERTY).unwrap_or(false)
+            && !spawned_objects.spawned.contains(&entity_spawn.name)
+        {
+            continue;
+        }
+        run_spawner(&mut commands, &spawners, &kind, entity_spawn);
+    }
+}
+
+/// Despawns the entities of the level before it is reloaded, remembering the objects already gone
+pub fn despawn_level_entities(
+    mut commands: Commands,
+    mut spawned_objects: ResMut<SpawnedObjects>,
+    entities: Query<(Entity, &LevelEntity)>,
+) {
+    let alive: HashSet<&String> = entities.iter().map(|(_, object)| &object.0).collect();
+    let gone: Vec<String> = spawned_objects
+        .spawned
+        .iter()
+        .filter(|name| !alive.contains(name))
+        .cloned()
+        .collect();
+    spawned_objects.gone.extend(gone);
+
+    for (entity, _) in entities.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Spawns again the entities to reset on restart, the ones gone before the checkpoint stay gone
+#[allow(clippy::too_many_arguments)]
+fn reset_level_entities(
+    mut commands: Commands,
+    spawners: Res<EntitySpawners>,
+    spawned_objects: Res<SpawnedObjects>,
+    checkpoint: Res<Checkpoint>,
+    tiled_maps: Res<Assets<TiledMap>>,
+    levels: Query<&Level, With<Level>>,
+    current_level: Res<CurrentLevel>,
+    entities: Query<Entity, (With<LevelEntity>, With<ResetOnRestart>)>,
+) {
+    let level = levels
+        .iter()
+        .find(|level| level.id == current_level.id)
+        .unwrap();
+
+    let Some(tiled_map) = tiled_maps.get(&level.handle) else {
+        return;
+    };
+
+    for entity in entities.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    for (kind, entity_spawn) in get_entity_spawns(tiled_map, level) {
+        if !spawned_objects
+            .reset_on_restart
+            .contains(&entity_spawn.name)
+        {
+            continue;
+        }
+        if checkpoint.name.is_some() && !checkpoint.alive_objects.contains(&entity_spawn.name) {
+            continue;
+        }
+        run_spawner(&mut commands, &spawners, &kind, entity_spawn);
+    }
+}
+
+fn spawn_deferred_entities(
+    mut commands: Commands,
+    spawners: Res<EntitySpawners>,
+    tiled_maps: Res<Assets<TiledMap>>,
+    levels: Query<&Level, With<Level>>,
+    current_level: Res<CurrentLevel>,
+    mut spawn_events: EventReader<SpawnLevelEntity>,
+) {
+    for ev in spawn_events.read() {
+        let level = levels
+            .iter()
+            .find(|level| level.id == current_level.id)
+            .unwrap();
+
+        let Some(tiled_map) = tiled_maps.get(&level.handle) else {
+            return;
+        };
+
+        match get_entity_spawns(tiled_map, level)
+            .into_iter()
+            .find(|(_kind, entity_spawn)| entity_spawn.name == ev.name)
+        {
+            Some((kind, entity_spawn)) => {
+                run_spawner(&mut commands, &spawners, &kind, entity_spawn);
+            }
+            None => warn!("No entity {} in level {}", ev.name, current_level.id),
+        }
+    }
+}
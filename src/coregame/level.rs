@@ -1,5 +1,4 @@
 use bevy::{
-    color,
     platform::collections::HashMap,
     prelude::*,
     render::render_resource::{AsBindGroup, ShaderRef},
@@ -7,34 +6,97 @@ use bevy::{
 };
 use bevy_ecs_tilemap::tiles::{TileStorage, TileVisible};
 use bevy_fluent::{BundleAsset, Locale};
+use screen_map::{CameraPolicy, Map, ScreenMapError, ScreenMetadata};
+use serde::Deserialize;
+use thiserror::Error;
 
 use crate::{
     WINDOW_HEIGHT, WINDOW_WIDTH,
     assets::RockRunAssets,
     coregame::{
+        colliders::despawn_colliders,
+        entities::despawn_level_entities,
         localization::{convert_to_fluent_args, get_translation},
         state::AppState,
+        triggers::{TriggerHandlerAppExt, TriggerKind, TriggeredSensor},
     },
-    events::{NextLevel, PositionSensorCollisionStart, PositionSensorCollisionStop, Restart},
+    elements::checkpoint::Checkpoint,
+    events::{EnigmaResult, LevelReloaded, NextLevel, Restart, UnlockScreen},
     helpers::{
         self,
-        tiled::{TiledMap, TiledMapHandle, TilesetLayerToStorageEntity},
+        level_manifest::{LevelManifest, LevelShader, LevelShaderKind},
+        tiled::{ChunkLoadingArea, TiledMap, TiledMapHandle, TilesetLayerToStorageEntity},
     },
     player,
 };
 
-use crate::screen_map::Map;
+const SCREEN_LAYOUT_PROPERTY: &str = "screen_layout";
+const CAMERA_POLICY_PROPERTY: &str = "camera_policy";
+const SCREEN_METADATA_PROPERTY: &str = "screen_metadata";
+
+#[derive(Debug, Error)]
+pub enum LevelError {
+    #[error("Level manifest is not loaded")]
+    ManifestNotLoaded,
+    #[error("Level {0} map is not loaded")]
+    NotLoaded(u8),
+    #[error("Level {id} shader color \"{color}\" is not a valid hexadecimal color")]
+    InvalidShaderColor { id: u8, color: String },
+    #[error("Level {0} map has no \"screen_layout\" string property")]
+    MissingScreenLayout(u8),
+    #[error(
+        "Level {0} screen layout of the manifest differs from the map \"screen_layout\" property"
+    )]
+    ConflictingScreenLayout(u8),
+    #[error("Level {id} screen layout is invalid: {source}")]
+    InvalidScreenLayout { id: u8, source: ScreenMapError },
+    #[error("Level {id} camera policy is invalid: {source}")]
+    InvalidCameraPolicy { id: u8, source: ScreenMapError },
+    #[error("Level {id} screen metadata is invalid: {source}")]
+    InvalidScreenMetadata { id: u8, source: serde_json::Error },
+    #[error("Level {id} has metadata for screen ({x}, {y}) which is not in its screen layout")]
+    UnknownScreen { id: u8, x: usize, y: usize },
+    #[error(
+        "Level {id} screen layout is {layout_width}x{layout_height} px but its map is {map_width}x{map_height} px"
+    )]
+    SizeMismatch {
+        id: u8,
+        layout_width: usize,
+        layout_height: usize,
+        map_width: usize,
+        map_height: usize,
+    },
+}
+
+/// Metadata of one screen in the `screen_metadata` property
+#[derive(Deserialize)]
+struct ScreenMetadataEntry {
+    x: usize,
+    y: usize,
+    /// Camera policy of the screen, overriding the `camera_policy` of the map
+    camera_policy: Option<String>,
+    #[serde(flatten)]
+    metadata: ScreenMetadata,
+}
 
 #[derive(Resource, PartialEq)]
 pub struct CurrentLevel {
     pub id: u8,
 }
 
+/// The player is in the exit sensor of the level
+#[derive(Resource, Default)]
+struct AtExit(bool);
+
 #[derive(Component, PartialEq)]
 pub struct Level {
     pub id: u8,
     pub handle: Handle<TiledMap>,
     pub map: Map,
+    pub music: Handle<AudioSource>,
+    pub shaders: Vec<LevelShader>,
+    /// Fluent message id of the level name
+    pub display_name: Option<String>,
 }
 
 #[derive(Component)]
@@ -60,6 +122,7 @@ impl Plugin for LevelPlugin {
                     show_level_background,
                     show_current_level,
                     show_level_shaders,
+                    lock_level_screens,
                 ),
             )
             .add_systems(
@@ -84,63 +147,169 @@ impl Plugin for LevelPlugin {
             )
             .add_systems(
                 Update,
-                (check_exit, fade_display_level).run_if(in_state(AppState::GameRunning)),
+                (check_exit, fade_display_level, unlock_screens)
+                    .run_if(in_state(AppState::GameRunning)),
             )
+            .add_systems(
+                Update,
+                update_chunk_loading_area.before(helpers::tiled::update_chunks),
+            )
+            .add_systems(
+                Update,
+                (
+                    reload_level,
+                    show_level_background.run_if(on_event::<LevelReloaded>),
+                )
+                    .chain()
+                    // The tiles of the modified map are rebuilt by process_loaded_maps.
+                    .after(helpers::tiled::process_loaded_maps)
+                    // Colliders and entities are rebuilt in the same frame.
+                    .before(despawn_colliders)
+                    .before(despawn_level_entities)
+                    .run_if(in_state(AppState::GameRunning)),
+            )
+            .register_trigger_handler(TriggerKind::Exit, reach_exit)
             .insert_resource(CurrentLevel { id: 1 })
+            .insert_resource(AtExit::default())
             .add_event::<Restart>()
-            .add_event::<NextLevel>();
+            .add_event::<NextLevel>()
+            .add_event::<UnlockScreen>()
+            .add_event::<LevelReloaded>();
 
         app.add_plugins(Material2dPlugin::<MysteriousFogMaterial>::default());
     }
 }
 
-fn setup_background(mut commands: Commands, rock_run_assets: Res<RockRunAssets>) {
-    let map_handle: Handle<helpers::tiled::TiledMap> = rock_run_assets.level01.clone();
-    commands.spawn((
-        helpers::tiled::TiledMapBundle {
-            tiled_map: TiledMapHandle(map_handle.clone()),
-            ..Default::default()
-        },
-        Level {
-            id: 1,
-            handle: map_handle.clone(),
-            map: Map::new("SOOOOOOOOO", WINDOW_WIDTH as usize, WINDOW_HEIGHT as usize),
-        },
-    ));
-
-    let map_handle: Handle<helpers::tiled::TiledMap> = rock_run_assets.level02.clone();
-    commands.spawn((
-        helpers::tiled::TiledMapBundle {
-            tiled_map: TiledMapHandle(map_handle.clone()),
-            ..Default::default()
-        },
-        Level {
-            id: 2,
-            handle: map_handle.clone(),
-            map: Map::new(
-                "OOOOOOOOOO\nOOOOOOOOOO\nSOOOOOOOOO",
-                WINDOW_WIDTH as usize,
-                WINDOW_HEIGHT as usize,
-            ),
-        },
-    ));
-
-    let map_handle: Handle<helpers::tiled::TiledMap> = rock_run_assets.level03.clone();
-    commands.spawn((
-        helpers::tiled::TiledMapBundle {
-            tiled_map: TiledMapHandle(map_handle.clone()),
-            ..Default::default()
-        },
-        Level {
-            id: 3,
-            handle: map_handle.clone(),
-            map: Map::new(
-                "SHFXF\nXOFOO",
-                WINDOW_WIDTH as usize,
-                WINDOW_HEIGHT as usize,
-            ),
-        },
-    ));
+fn setup_background(
+    mut commands: Commands,
+    rock_run_assets: Res<RockRunAssets>,
+    manifests: Res<Assets<LevelManifest>>,
+    tiled_maps: Res<Assets<TiledMap>>,
+) -> Result<()> {
+    let manifest = manifests
+        .get(&rock_run_assets.levels)
+        .ok_or(LevelError::ManifestNotLoaded)?;
+
+    for (id, level) in manifest.iter() {
+        let tiled_map = tiled_maps
+            .get(&level.map)
+            .ok_or(LevelError::NotLoaded(id))?;
+        let map = screen_map_from_tiled(id, tiled_map, level.screen_layout.as_deref())?;
+
+        commands.spawn((
+            helpers::tiled::TiledMapBundle {
+                tiled_map: TiledMapHandle(level.map.clone()),
+                ..Default::default()
+            },
+            Level {
+                id,
+                handle: level.map.clone(),
+                map,
+                music: level.music.clone(),
+                shaders: level.shaders.clone(),
+                display_name: level.display_name.clone(),
+            },
+        ));
+    }
+    Ok(())
+}
+
+/// Builds the screen map of a level from the `screen_layout` property of its TMX file.
+///
+/// The `screen_layout` of the level manifest can replace the property of maps without it, a level
+/// with both is rejected when they differ.
+///
+/// An optional `camera_policy` property (`smooth_follow`, `dead_zone`, `look_ahead` or
+/// `platform_snap`) selects how the camera follows the player in this level.
+///
+/// An optional `screen_metadata` property attaches metadata to screens, as a JSON list such as
+/// `[{"x": 1, "y": 0, "display_name": "Cave", "checkpoint": "true"}]`. A `camera_policy` entry
+/// overrides the camera policy of the level on that screen, a `checkpoint` entry makes the screen
+/// a checkpoint.
+///
+/// The layout uses one line per row of screens, see [`Map::try_new()`] for the rules it must follow.
+fn screen_map_from_tiled(
+    id: u8,
+    tiled_map: &TiledMap,
+    screen_layout: Option<&str>,
+) -> Result<Map, LevelError> {
+    // Tiled may save multiline properties with CRLF line endings.
+    let normalize = |layout: &str| {
+        layout
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let layout = match (
+        screen_layout.map(normalize),
+        tiled_map
+            .get_string_property(SCREEN_LAYOUT_PROPERTY)
+            .map(normalize),
+    ) {
+        (Some(manifest_layout), Some(map_layout)) if manifest_layout != map_layout => {
+            return Err(LevelError::ConflictingScreenLayout(id));
+        }
+        (manifest_layout, map_layout) => manifest_layout
+            .or(map_layout)
+            .ok_or(LevelError::MissingScreenLayout(id))?,
+    };
+
+    let mut map = Map::try_new(&layout, WINDOW_WIDTH as usize, WINDOW_HEIGHT as usize)
+        .map_err(|source| LevelError::InvalidScreenLayout { id, source })?;
+
+    if let Some(policy) = tiled_map.get_string_property(CAMERA_POLICY_PROPERTY) {
+        let policy = policy
+            .trim()
+            .parse::<CameraPolicy>()
+            .map_err(|source| LevelError::InvalidCameraPolicy { id, source })?;
+        map.set_camera_policy(policy);
+    }
+
+    if let Some(metadata) = tiled_map.get_string_property(SCREEN_METADATA_PROPERTY) {
+        let entries: Vec<ScreenMetadataEntry> = serde_json::from_str(metadata)
+            .map_err(|source| LevelError::InvalidScreenMetadata { id, source })?;
+        for ScreenMetadataEntry {
+            x,
+            y,
+            camera_policy,
+            metadata,
+        } in entries
+        {
+            if !map.set_screen_metadata(x, y, Some(metadata)) {
+                return Err(LevelError::UnknownScreen { id, x, y });
+            }
+            if let Some(policy) = camera_policy {
+                let policy = policy
+                    .trim()
+                    .parse::<CameraPolicy>()
+                    .map_err(|source| LevelError::InvalidCameraPolicy { id, source })?;
+                map.set_screen_camera_policy(x, y, Some(policy));
+            }
+        }
+    }
+
+    let (layout_width, layout_height) = map.get_size();
+    let (map_width, map_height) = tiled_map.get_pixel_size();
+    if (layout_width, layout_height) != (map_width, map_height) {
+        return Err(LevelError::SizeMismatch {
+            id,
+            layout_width,
+            layout_height,
+            map_width,
+            map_height,
+        });
+    }
+
+    for screen in map.get_unreachable_screens() {
+        warn!(
+            "Level {id} screen {:?} can't be reached from the start screen",
+            screen.get_indices()
+        );
+    }
+
+    Ok(map)
 }
 
 fn show_level_shaders(
@@ -149,7 +318,7 @@ fn show_level_shaders(
     levels: Query<&Level, With<Level>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut mysterious_fog: ResMut<Assets<MysteriousFogMaterial>>,
-) {
+) -> Result<()> {
     info!("show level shaders for level {:?}", current_level.id);
 
     let level = levels
@@ -157,30 +326,37 @@ fn show_level_shaders(
         .find(|level| level.id == current_level.id)
         .unwrap();
 
-    #[allow(clippy::single_match)]
-    match current_level.id {
-        3 => {
-            commands.spawn((
-                Mesh2d(meshes.add(Rectangle::default())),
-                MeshMaterial2d(mysterious_fog.add(MysteriousFogMaterial {
-                    color: LinearRgba::from(color::palettes::css::GOLD),
-                })),
-                Transform {
-                    translation: level
-                        .map
-                        .tiled_to_bevy_coord(Vec2::new(
-                            3024.0 + (3376.0 / 2.0),
-                            720.0 + (720.0 / 2.0),
-                        ))
-                        .extend(0.0),
-                    scale: Vec3::new(3376.0, 720.0, 0.0),
-                    ..default()
-                },
-                ShaderLevel,
-            ));
+    for shader in level.shaders.iter() {
+        let color = Srgba::hex(&shader.color).map_err(|_| LevelError::InvalidShaderColor {
+            id: level.id,
+            color: shader.color.clone(),
+        })?;
+        let area = shader.area;
+
+        match shader.kind {
+            LevelShaderKind::MysteriousFog => {
+                commands.spawn((
+                    Mesh2d(meshes.add(Rectangle::default())),
+                    MeshMaterial2d(mysterious_fog.add(MysteriousFogMaterial {
+                        color: LinearRgba::from(color),
+                    })),
+                    Transform {
+                        translation: level
+                            .map
+                            .tiled_to_bevy_coord(Vec2::new(
+                                area.x + (area.width / 2.0),
+                                area.y + (area.height / 2.0),
+                            ))
+                            .extend(0.0),
+                        scale: Vec3::new(area.width, area.height, 0.0),
+                        ..default()
+                    },
+                    ShaderLevel,
+                ));
+            }
         }
-        _ => {}
     }
+    Ok(())
 }
 
 // This is the struct that will be passed to your shader
@@ -238,7 +414,7 @@ fn show_current_level(
                     &locale,
                     &assets,
                     &rock_run_assets,
-                    "current_level",
+                    level.display_name.as_deref().unwrap_or("current_level"),
                     convert_to_fluent_args(Some(HashMap::from([(
                         "current_level".to_string(),
                         current_level.id.to_string(),
@@ -295,6 +471,49 @@ fn despawn_display_level(
     }
 }
 
+/// Loads the chunks of infinite maps around the screen of the camera, only in the displayed level
+fn update_chunk_loading_area(
+    state: Res<State<AppState>>,
+    current_level: Res<CurrentLevel>,
+    camera_query: Query<&Transform, With<Camera2d>>,
+    mut levels: Query<(&Level, &mut ChunkLoadingArea)>,
+) {
+    let displayed = matches!(
+        state.get(),
+        AppState::GameCreate
+            | AppState::GameMessage
+            | AppState::GameRunning
+            | AppState::GamePaused
+            | AppState::GameOver
+            | AppState::NextLevel
+    );
+    let Ok(camera) = camera_query.single() else {
+        return;
+    };
+
+    for (level, mut loading_area) in levels.iter_mut() {
+        if !displayed || level.id != current_level.id {
+            if loading_area.0.is_some() {
+                loading_area.0 = None;
+            }
+            continue;
+        }
+
+        // The neighbour screens are loaded as well, the camera moves between screens
+        let Some(screen) = level.map.get_screen(camera.translation.xy(), 0.0, 0.0) else {
+            continue;
+        };
+        let (x_range, y_range) = screen.get_ranges();
+        let screen_size = Vec2::new(x_range.end - x_range.start, y_range.end - y_range.start);
+        loading_area.0 = Some(Rect::new(
+            x_range.start - screen_size.x,
+            y_range.start - screen_size.y,
+            x_range.end + screen_size.x,
+            y_range.end + screen_size.y,
+        ));
+    }
+}
+
 fn show_level_background(
     current_level: Res<CurrentLevel>,
     mut tile_query: Query<&mut TileVisible>,
@@ -342,38 +561,130 @@ fn get_tiles(
         .collect::<Vec<_>>()
 }
 
+fn reach_exit(
+    In(triggered_sensor): In<TriggeredSensor>,
+    mut at_exit: ResMut<AtExit>,
+) -> Result<()> {
+    at_exit.0 = triggered_sensor.entered;
+    Ok(())
+}
+
 fn check_exit(
     mut next_state: ResMut<NextState<AppState>>,
-    mut sensor_collision_start: EventReader<PositionSensorCollisionStart>,
-    mut sensor_collision_stop: EventReader<PositionSensorCollisionStop>,
     input: Query<
         &leafwing_input_manager::action_state::ActionState<player::PlayerMovement>,
         With<player::Player>,
     >,
-    mut exit_collision: Local<bool>,
+    mut at_exit: ResMut<AtExit>,
 ) {
     let input_state = match input.single() {
         Ok(state) => state,
         Err(_) => return,
     };
 
-    for collision_event in sensor_collision_start.read() {
-        if !collision_event.sensor_name.contains("exit") {
-            return;
+    if at_exit.0 && input_state.just_pressed(&player::PlayerMovement::Climb) {
+        debug!("next level");
+        at_exit.0 = false;
+        next_state.set(AppState::FinishLevel);
+    }
+}
+
+/// Rebuilds the screen map of the current level when its TMX file is modified on disk
+///
+/// Requires the `hot_reload` feature to watch the assets.
+fn reload_level(
+    rock_run_assets: Res<RockRunAssets>,
+    manifests: Res<Assets<LevelManifest>>,
+    tiled_maps: Res<Assets<TiledMap>>,
+    current_level: Res<CurrentLevel>,
+    mut levels: Query<&mut Level>,
+    mut map_events: EventReader<AssetEvent<TiledMap>>,
+    mut level_reloaded: EventWriter<LevelReloaded>,
+) -> Result<()> {
+    for event in map_events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+
+        let Some(mut level) = levels
+            .iter_mut()
+            .find(|level| level.id == current_level.id && level.handle.id() == *id)
+        else {
+            continue;
+        };
+
+        let tiled_map = tiled_maps.get(*id).ok_or(LevelError::NotLoaded(level.id))?;
+        let screen_layout = manifests
+            .get(&rock_run_assets.levels)
+            .ok_or(LevelError::ManifestNotLoaded)?
+            .iter()
+            .find(|(id, _)| *id == level.id)
+            .and_then(|(_, level_info)| level_info.screen_layout.as_deref());
+
+        match screen_map_from_tiled(level.id, tiled_map, screen_layout) {
+            Ok(mut map) => {
+                info!("Level {} reloaded", level.id);
+                map.lock_screens();
+                level.map = map;
+                level_reloaded.write(LevelReloaded);
+            }
+            // Keep playing with the previous map until the TMX file is fixed.
+            Err(error) => error!("Level {} not reloaded: {}", level.id, error),
         }
-        *exit_collision = true;
     }
+    Ok(())
+}
+
+fn lock_level_screens(mut levels: Query<&mut Level>) {
+    for mut level in levels.iter_mut() {
+        level.map.lock_screens();
+    }
+}
 
-    for collision_event in sensor_collision_stop.read() {
-        if !collision_event.sensor_name.contains("exit") {
-            return;
+fn unlock_screens(
+    current_level: Res<CurrentLevel>,
+    mut levels: Query<&mut Level>,
+    player_query: Query<&Transform, With<player::Player>>,
+    mut unlock_events: EventReader<UnlockScreen>,
+    mut enigma_events: EventReader<EnigmaResult>,
+    mut restart_events: EventReader<Restart>,
+    checkpoint: Res<Checkpoint>,
+) -> Result<()> {
+    let mut level = levels
+        .iter_mut()
+        .find(|level| level.id == current_level.id)
+        .ok_or(LevelError::NotLoaded(current_level.id))?;
+
+    if !restart_events.is_empty() {
+        restart_events.clear();
+        // Screens unlocked before the checkpoint must stay open, the player restarts beyond them.
+        level.map.lock_screens();
+        for (index_x, index_y) in checkpoint.unlocked_screens.iter().copied() {
+            level.map.unlock_screen(index_x, index_y);
         }
-        *exit_collision = false;
     }
 
-    if *exit_collision && input_state.just_pressed(&player::PlayerMovement::Climb) {
-        debug!("next level");
-        *exit_collision = false;
-        next_state.set(AppState::FinishLevel);
+    // Solving an enigma unlocks the screen as well
+    let solved_enigmas = enigma_events
+        .read()
+        .filter(|ev| matches!(ev, EnigmaResult::Correct(_)))
+        .count();
+    let unlock_requests = unlock_events.read().count();
+    if solved_enigmas + unlock_requests == 0 {
+        return Ok(());
     }
+
+    let player = player_query.single()?;
+    let Some((index_x, index_y)) = level
+        .map
+        .get_screen(player.translation.xy(), 0.0, 0.0)
+        .map(|screen| screen.get_indices())
+        .filter(|&(index_x, index_y)| level.map.is_screen_locked(index_x, index_y))
+    else {
+        return Ok(());
+    };
+
+    debug!("unlock screen {:?}", (index_x, index_y));
+    level.map.unlock_screen(index_x, index_y);
+    Ok(())
 }
@@ -0,0 +1,264 @@
+// Level select screen, reachable from the start menu.
+//
+// The highest finished level is stored in the `PkvStore`. Finished levels and the level following
+// them can be played, the other levels are locked.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use bevy_fluent::{BundleAsset, Locale};
+use bevy_pkv::PkvStore;
+use leafwing_input_manager::action_state::ActionState;
+
+use crate::{
+    WINDOW_WIDTH,
+    assets::RockRunAssets,
+    coregame::{
+        level::{CurrentLevel, Level},
+        localization::{convert_to_fluent_args, get_translation},
+        menu::MenuAction,
+        state::{AppState, ForState},
+    },
+};
+
+const FINISHED_LEVEL_KEY: &str = "finished_level";
+const TITLE_TOP_MARGIN: f32 = 185.0;
+const LEVELS_TOP_MARGIN: f32 = 290.0;
+const LEVELS_SPACING: f32 = 70.0;
+
+pub struct LevelSelectPlugin;
+
+impl Plugin for LevelSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::LevelSelect), level_select_menu)
+            .add_systems(OnEnter(AppState::FinishLevel), save_progress)
+            .add_systems(
+                Update,
+                update_level_select.run_if(in_state(AppState::LevelSelect)),
+            )
+            .insert_resource(SelectedLevel(1));
+    }
+}
+
+#[derive(Debug, Resource)]
+struct SelectedLevel(u8);
+
+#[derive(Component)]
+struct LevelItem(u8);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LevelStatus {
+    Finished,
+    Available,
+    Locked,
+}
+
+impl LevelStatus {
+    fn new(level_id: u8, finished_level: u8) -> Self {
+        if level_id <= finished_level {
+            LevelStatus::Finished
+        } else if level_id == finished_level + 1 {
+            LevelStatus::Available
+        } else {
+            LevelStatus::Locked
+        }
+    }
+
+    fn color(&self, selected: bool) -> Color {
+        match (self, selected) {
+            (_, true) => Color::srgb_u8(0xD3, 0xCD, 0x39),
+            (LevelStatus::Finished, false) => Color::srgb_u8(0xF4, 0x78, 0x04),
+            (LevelStatus::Available, false) => Color::srgb_u8(0x54, 0x2E, 0x0A),
+            (LevelStatus::Locked, false) => Color::srgb_u8(0xA8, 0x9A, 0x8C),
+        }
+    }
+
+    /// Translation key of the label displayed after the level name
+    fn message(&self) -> Option<&'static str> {
+        match self {
+            LevelStatus::Finished => Some("level_finished"),
+            LevelStatus::Available => None,
+            LevelStatus::Locked => Some("level_locked"),
+        }
+    }
+}
+
+/// Returns the highest level finished by the player, 0 if none
+pub fn get_finished_level(pkv: &PkvStore) -> u8 {
+    pkv.get::<u8>(FINISHED_LEVEL_KEY).unwrap_or(0)
+}
+
+fn save_progress(current_level: Res<CurrentLevel>, mut pkv: ResMut<PkvStore>) {
+    if current_level.id > get_finished_level(&pkv) {
+        info!("level {} finished", current_level.id);
+        if let Err(error) = pkv.set(FINISHED_LEVEL_KEY, &current_level.id) {
+            warn!("Failed to store the finished level: {}", error);
+        }
+    }
+}
+
+fn level_label(
+    locale: &Locale,
+    assets: &Res<Assets<BundleAsset>>,
+    rock_run_assets: &Res<RockRunAssets>,
+    level: &Level,
+    status: LevelStatus,
+) -> String {
+    let name = get_translation(
+        locale,
+        assets,
+        rock_run_assets,
+        level.display_name.as_deref().unwrap_or("current_level"),
+        convert_to_fluent_args(Some(HashMap::from([(
+            "current_level".to_string(),
+            level.id.to_string(),
+        )])))
+        .as_ref(),
+    );
+
+    match status.message() {
+        Some(message) => format!(
+            "{} {}",
+            name,
+            get_translation(locale, assets, rock_run_assets, message, None)
+        ),
+        None => name,
+    }
+}
+
+fn level_select_menu(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    assets: Res<Assets<BundleAsset>>,
+    rock_run_assets: Res<RockRunAssets>,
+    pkv: Res<PkvStore>,
+    levels: Query<&Level>,
+    mut selected_level: ResMut<SelectedLevel>,
+) {
+    info!("level_select_menu");
+
+    let finished_level = get_finished_level(&pkv);
+    let mut levels = levels.iter().collect::<Vec<_>>();
+    levels.sort_by_key(|level| level.id);
+    let last_level = levels.last().map(|level| level.id).unwrap_or(1);
+
+    // Continue where the player stopped
+    selected_level.0 = (finished_level + 1).min(last_level);
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Row,
+                ..default()
+            },
+            ForState {
+                states: vec![AppState::LevelSelect],
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Node {
+                    width: Val::Px(720.0),
+                    ..default()
+                },
+                BackgroundColor(Color::WHITE),
+                ImageNode::new(rock_run_assets.menu.clone()),
+            ));
+        })
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        align_items: AlignItems::Center,
+                        flex_direction: FlexDirection::Column,
+                        width: Val::Px(WINDOW_WIDTH - 720.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::WHITE),
+                    ImageNode::new(rock_run_assets.menu2.clone()),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            top: Val::Px(TITLE_TOP_MARGIN),
+                            ..default()
+                        },
+                        Text::new(get_translation(
+                            &locale,
+                            &assets,
+                            &rock_run_assets,
+                            "select_level",
+                            None,
+                        )),
+                        TextFont {
+                            font: rock_run_assets.cute_dino_font.clone(),
+                            font_size: 45.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb_u8(0x54, 0x2E, 0x0A)),
+                    ));
+
+                    for (index, level) in levels.iter().enumerate() {
+                        let status = LevelStatus::new(level.id, finished_level);
+                        parent.spawn((
+                            Node {
+                                position_type: PositionType::Absolute,
+                                top: Val::Px(LEVELS_TOP_MARGIN + index as f32 * LEVELS_SPACING),
+                                ..default()
+                            },
+                            Text::new(level_label(
+                                &locale,
+                                &assets,
+                                &rock_run_assets,
+                                level,
+                                status,
+                            )),
+                            TextFont {
+                                font: rock_run_assets.cute_dino_font.clone(),
+                                font_size: 35.0,
+                                ..default()
+                            },
+                            TextColor(status.color(level.id == selected_level.0)),
+                            LevelItem(level.id),
+                        ));
+                    }
+                });
+        });
+}
+
+fn update_level_select(
+    mut next_state: ResMut<NextState<AppState>>,
+    menu_action_state: Res<ActionState<MenuAction>>,
+    mut selected_level: ResMut<SelectedLevel>,
+    mut current_level: ResMut<CurrentLevel>,
+    pkv: Res<PkvStore>,
+    mut items: Query<(&LevelItem, &mut TextColor)>,
+) {
+    let finished_level = get_finished_level(&pkv);
+    let last_level = items.iter().map(|(item, _)| item.0).max().unwrap_or(1);
+
+    if menu_action_state.just_pressed(&MenuAction::Up) && selected_level.0 > 1 {
+        selected_level.0 -= 1;
+    }
+
+    if menu_action_state.just_pressed(&MenuAction::Down) && selected_level.0 < last_level {
+        selected_level.0 += 1;
+    }
+
+    if menu_action_state.just_pressed(&MenuAction::Accept) {
+        match LevelStatus::new(selected_level.0, finished_level) {
+            LevelStatus::Locked => info!("level {} is locked", selected_level.0),
+            _ => {
+                info!("start level {}", selected_level.0);
+                current_level.id = selected_level.0;
+                next_state.set(AppState::GameCreate);
+            }
+        }
+    }
+
+    for (item, mut color) in items.iter_mut() {
+        let status = LevelStatus::new(item.0, finished_level);
+        *color = TextColor(status.color(item.0 == selected_level.0));
+    }
+}
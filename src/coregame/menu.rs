@@ -18,11 +18,12 @@ use unic_langid::langid;
 
 use crate::WINDOW_HEIGHT;
 use crate::events::{NextLevel, StartGame};
+use crate::helpers::level_manifest::LevelManifest;
 use crate::{
     WINDOW_WIDTH,
     assets::RockRunAssets,
     coregame::{
-        level::CurrentLevel,
+        level::{CurrentLevel, Level},
         localization::get_translation,
         state::{AppState, ForState},
     },
@@ -30,8 +31,6 @@ use crate::{
     events::{LadderCollisionStop, NoMoreStoryMessages, SelectionChanged, StoryMessages},
 };
 
-const LAST_LEVEL: u8 = 3;
-
 #[derive(Component)]
 pub struct DrawBlinkTimer(pub Timer);
 
@@ -44,6 +43,9 @@ struct Sel1;
 #[derive(Component)]
 struct Sel2;
 
+#[derive(Component)]
+struct Sel3;
+
 // List of user actions associated to menu/ui interaction
 #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
 pub enum MenuAction {
@@ -87,6 +89,7 @@ impl Plugin for MenuPlugin {
             )
             .add_systems(Update, update_menu.run_if(in_state(AppState::StartMenu)))
             .add_systems(OnEnter(AppState::Loading), setup)
+            .add_systems(OnExit(AppState::Loading), check_start_level)
             .add_event::<StartGame>()
             .insert_resource(Godmode(false))
             .insert_resource(StartLevel(1))
@@ -117,7 +120,8 @@ fn setup(
         Ok(level) => {
             let level = level.parse::<u8>().expect("ROCKRUN_LEVEL is not a number");
             match level {
-                1..=3 => start_level.0 = level,
+                // Checked against the level manifest once it is loaded
+                1.. => start_level.0 = level,
                 _ => start_level.0 = 1,
             }
         }
@@ -216,7 +220,7 @@ fn start_menu(
         }
     }
 
-    const TOP_MARGINS: [f32; 4] = [185.0, 290.0, 395.0, 500.0];
+    const TOP_MARGINS: [f32; 5] = [185.0, 290.0, 395.0, 500.0, 605.0];
 
     if let Ok(langid) = pkv.get::<String>("langid") {
         match langid.as_str() {
@@ -410,6 +414,30 @@ fn start_menu(
                         TextColor(Color::srgb_u8(0x54, 0x2E, 0x0A)),
                         Sel0,
                     ));
+                })
+                // level select
+                .with_children(|parent| {
+                    parent.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            top: Val::Px(TOP_MARGINS[4]),
+                            ..default()
+                        },
+                        Text::new(get_translation(
+                            &locale,
+                            &assets,
+                            &rock_run_assets,
+                            "select_level",
+                            None,
+                        )),
+                        TextFont {
+                            font: rock_run_assets.cute_dino_font.clone(),
+                            font_size: 30.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb_u8(0x54, 0x2E, 0x0A)),
+                        Sel3,
+                    ));
                 });
         });
 }
@@ -417,6 +445,10 @@ fn start_menu(
 type Select0 = (With<Sel0>, Without<Sel1>, Without<Sel2>);
 type Select1 = (With<Sel1>, Without<Sel0>, Without<Sel2>);
 type Select2 = (With<Sel2>, Without<Sel0>, Without<Sel1>);
+type Select3 = (With<Sel3>, Without<Sel0>, Without<Sel1>, Without<Sel2>);
+
+// Items are ordered from the bottom of the menu: level select, start, French, English
+const MENU_ITEMS: i8 = 4;
 
 #[allow(clippy::too_many_arguments)]
 fn update_menu(
@@ -427,6 +459,7 @@ fn update_menu(
     mut query0: Query<(&mut Text, &mut TextColor), Select0>,
     mut query1: Query<(&mut Text, &mut TextColor), Select1>,
     mut query2: Query<(&mut Text, &mut TextColor), Select2>,
+    mut query3: Query<(&mut Text, &mut TextColor), Select3>,
     assets: Res<Assets<BundleAsset>>,
     rock_run_assets: Res<RockRunAssets>,
     mut pkv: ResMut<PkvStore>,
@@ -450,17 +483,15 @@ fn update_menu(
     let (mut sel0_text, mut sel0_color) = query0.single_mut()?;
     let (mut sel1_text, mut sel1_color) = query1.single_mut()?;
     let (mut sel2_text, mut sel2_color) = query2.single_mut()?;
+    let (mut sel3_text, mut sel3_color) = query3.single_mut()?;
 
     if menu_action_state.just_pressed(&MenuAction::Up) {
-        *menu_sel = (*menu_sel + 1) % 3;
+        *menu_sel = (*menu_sel + 1) % MENU_ITEMS;
         debug!("menu_sel: {}", *menu_sel);
     }
 
     if menu_action_state.just_pressed(&MenuAction::Down) {
-        if *menu_sel == 0 {
-            *menu_sel = 3;
-        }
-        *menu_sel = (*menu_sel - 1) % 3;
+        *menu_sel = (*menu_sel + MENU_ITEMS - 1) % MENU_ITEMS;
         debug!("menu_sel: {}", *menu_sel);
     }
 
@@ -482,6 +513,7 @@ fn update_menu(
                     &mut sel0_text,
                     &mut sel1_text,
                     &mut sel2_text,
+                    &mut sel3_text,
                 );
             }
             2 => {
@@ -496,12 +528,19 @@ fn update_menu(
                     &mut sel0_text,
                     &mut sel1_text,
                     &mut sel2_text,
+                    &mut sel3_text,
                 );
             }
+            3 => {
+                info!("level select");
+                next_state.set(AppState::LevelSelect);
+            }
             _ => {}
         }
     }
 
+    *sel3_color = TextColor(MenuColor::color(&MenuColor::OtherLang));
+
     match *menu_sel {
         0 => {
             if locale.requested == langid!("fr-FR") {
@@ -533,6 +572,17 @@ fn update_menu(
             }
             *sel0_color = TextColor(MenuColor::color(&MenuColor::OtherLang));
         }
+        3 => {
+            if locale.requested == langid!("fr-FR") {
+                *sel1_color = TextColor(MenuColor::color(&MenuColor::CurrentLang));
+                *sel2_color = TextColor(MenuColor::color(&MenuColor::OtherLang));
+            } else {
+                *sel2_color = TextColor(MenuColor::color(&MenuColor::CurrentLang));
+                *sel1_color = TextColor(MenuColor::color(&MenuColor::OtherLang));
+            }
+            *sel0_color = TextColor(MenuColor::color(&MenuColor::OtherLang));
+            *sel3_color = TextColor(MenuColor::color(&MenuColor::Selected));
+        }
         _ => {}
     }
     Ok(())
@@ -545,6 +595,7 @@ fn refresh_menu_items(
     sel0: &mut Text,
     sel1: &mut Text,
     sel2: &mut Text,
+    sel3: &mut Text,
 ) {
     // Refresh menu items in case we has just changed the locale
     *sel0 = Text::new(get_translation(
@@ -568,6 +619,13 @@ fn refresh_menu_items(
         "lang01",
         None,
     ));
+    *sel3 = Text::new(get_translation(
+        locale,
+        &assets,
+        &rock_run_assets,
+        "select_level",
+        None,
+    ));
 }
 
 fn gamefinished_menu(mut commands: Commands, rock_run_assets: Res<RockRunAssets>) {
@@ -709,6 +767,24 @@ fn menu_blink_system(
     }
 }
 
+fn check_start_level(
+    rock_run_assets: Res<RockRunAssets>,
+    manifests: Res<Assets<LevelManifest>>,
+    mut start_level: ResMut<StartLevel>,
+) {
+    let Some(manifest) = manifests.get(&rock_run_assets.levels) else {
+        return;
+    };
+
+    if start_level.0 > manifest.last_level_id() {
+        warn!(
+            "ROCKRUN_LEVEL {} does not exist, starting at level 1",
+            start_level.0
+        );
+        start_level.0 = 1;
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn menu_input_system(
     state: ResMut<State<AppState>>,
@@ -724,6 +800,7 @@ fn menu_input_system(
     mut game_event_level: EventWriter<NextLevel>,
     mut current_level: ResMut<CurrentLevel>,
     start_level: Res<StartLevel>,
+    levels: Query<&Level>,
 ) {
     let mut rapier_config = rapier_config
         .single_mut()
@@ -743,6 +820,11 @@ fn menu_input_system(
                     app_exit_events.write(AppExit::Success);
                 }
             }
+            AppState::LevelSelect => {
+                if menu_action_state.just_pressed(&MenuAction::Quit) {
+                    next_state.set(AppState::StartMenu);
+                }
+            }
             AppState::GameCreate => {
                 next_state.set(AppState::GameRunning);
                 game_event_start.write(StartGame);
@@ -818,12 +900,15 @@ fn menu_input_system(
                 // This state is used to load assets.
             }
             AppState::FinishLevel => {
-                // Mostly used to despawn stuff
-                if current_level.id == LAST_LEVEL {
-                    next_state.set(AppState::GameFinished);
-                } else {
-                    current_level.id += 1;
-                    next_state.set(AppState::NextLevel);
+                // The level results are displayed until the player accepts them
+                if menu_action_state.just_pressed(&MenuAction::Accept) {
+                    let last_level = levels.iter().map(|level| level.id).max().unwrap_or(1);
+                    if current_level.id >= last_level {
+                        next_state.set(AppState::GameFinished);
+                    } else {
+                        current_level.id += 1;
+                        next_state.set(AppState::NextLevel);
+                    }
                 }
             }
             AppState::NextLevel => {
@@ -1,7 +1,10 @@
 pub mod camera;
 pub mod colliders;
+pub mod entities;
 pub mod level;
+pub mod level_select;
 pub mod localization;
 pub mod menu;
 pub mod plugins;
 pub mod state;
+pub mod triggers;
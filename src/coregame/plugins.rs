@@ -1,6 +1,8 @@
 use bevy::{app::PluginGroupBuilder, prelude::*};
 
-use crate::coregame::{camera, colliders, level, localization, menu, state};
+use crate::coregame::{
+    camera, colliders, entities, level, level_select, localization, menu, state, triggers,
+};
 
 pub struct CoreGamePlugins;
 
@@ -10,8 +12,11 @@ impl PluginGroup for CoreGamePlugins {
             .add(state::StatesPlugin)
             .add(camera::CameraPlugin)
             .add(menu::MenuPlugin)
+            .add(level_select::LevelSelectPlugin)
             .add(level::LevelPlugin)
             .add(colliders::CollidersPlugin)
+            .add(entities::EntitiesPlugin)
             .add(localization::LocalizationPlugin)
+            .add(triggers::TriggersPlugin)
     }
 }
@@ -13,6 +13,7 @@ pub enum AppState {
     #[default]
     Loading,
     StartMenu,
+    LevelSelect,
     GameCreate,
     GameMessage,
     GameRunning,
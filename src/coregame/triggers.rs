@@ -0,0 +1,102 @@
+// Gameplay triggers of the position sensors.
+//
+// The kind of trigger is parsed from the name of the sensor (and its `SensorValues`) when the
+// sensor spawns, see `level_objects`. Sensors with an unknown name, or a kind no plugin handles,
+// are reported at level load.
+//
+// Plugins register the handlers of the kinds they manage with `register_trigger_handler`.
+// Handlers are one-shot systems receiving a `TriggeredSensor` when the player enters or leaves the
+// sensor.
+
+use bevy::{ecs::system::SystemId, platform::collections::HashMap, prelude::*};
+
+use crate::coregame::colliders::ColliderName;
+pub use screen_map::level_objects::{SensorTrigger, TriggerKind};
+
+/// A trigger reached or left by the player, given to its handlers
+#[derive(Clone, Debug)]
+pub struct TriggeredSensor {
+    pub sensor_name: String,
+    pub trigger: SensorTrigger,
+    /// `true` when the player enters the sensor, `false` when they leave it
+    pub entered: bool,
+}
+
+/// Handlers registered by trigger kind
+#[derive(Resource, Default)]
+pub struct TriggerHandlers(HashMap<TriggerKind, Vec<SystemId<In<TriggeredSensor>, Result<()>>>>);
+
+pub trait TriggerHandlerAppExt {
+    /// Registers a system handling the triggers of the given kind
+    fn register_trigger_handler<M>(
+        &mut self,
+        kind: TriggerKind,
+        handler: impl IntoSystem<In<TriggeredSensor>, Result<()>, M> + 'static,
+    ) -> &mut Self;
+}
+
+impl TriggerHandlerAppExt for App {
+    fn register_trigger_handler<M>(
+        &mut self,
+        kind: TriggerKind,
+        handler: impl IntoSystem<In<TriggeredSensor>, Result<()>, M> + 'static,
+    ) -> &mut Self {
+        let world = self.world_mut();
+        let id = world.register_system(handler);
+        world
+            .get_resource_or_init::<TriggerHandlers>()
+            .0
+            .entry(kind)
+            .or_default()
+            .push(id);
+        self
+    }
+}
+
+/// Runs the handlers registered for the kind of the trigger
+pub fn run_trigger_handlers(
+    commands: &mut Commands,
+    handlers: &TriggerHandlers,
+    triggered_sensor: TriggeredSensor,
+) {
+    let Some(ids) = handlers.0.get(&triggered_sensor.trigger.kind()) else {
+        return;
+    };
+
+    for handler in ids.iter().copied() {
+        let triggered_sensor = triggered_sensor.clone();
+        commands.queue(move |world: &mut World| {
+            let name = triggered_sensor.sensor_name.clone();
+            match world.run_system_with(handler, triggered_sensor) {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => error!("Trigger {} failed: {}", name, error),
+                Err(error) => error!("Trigger {} not handled: {}", name, error),
+            }
+        });
+    }
+}
+
+pub struct TriggersPlugin;
+
+impl Plugin for TriggersPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TriggerHandlers>()
+            .add_systems(Update, report_unhandled_triggers);
+    }
+}
+
+/// Reports the sensors of the loaded level nothing reacts to
+fn report_unhandled_triggers(
+    handlers: Res<TriggerHandlers>,
+    triggers: Query<(&ColliderName, &SensorTrigger), Added<SensorTrigger>>,
+) {
+    for (collider_name, trigger) in triggers.iter() {
+        if !handlers.0.contains_key(&trigger.kind()) {
+            warn!(
+                "Sensor {}: no handler for {:?} triggers",
+                collider_name.0,
+                trigger.kind()
+            );
+        }
+    }
+}
@@ -0,0 +1,220 @@
+// Checkpoints are position sensors named `checkpointNN`. Their `start_x` and `start_y`
+// properties give the respawn point (the player position when they are missing).
+//
+// Screens with a `checkpoint` metadata (see `screen_metadata` in the level map properties) are
+// checkpoints as well, reached as soon as the player enters them.
+//
+// Once a checkpoint is reached, the player restarts there instead of the level start. The sensors
+// triggered before it stay disabled so the beasts already met are not sent again, the beasts
+// beaten before it are not spawned again and the screens unlocked before it stay unlocked.
+
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    platform::collections::HashSet,
+    prelude::*,
+};
+use bevy_fluent::{BundleAsset, Locale};
+use bevy_rapier2d::geometry::ActiveCollisionTypes;
+
+use crate::{
+    assets::RockRunAssets,
+    collisions::CollisionSet,
+    coregame::{
+        colliders::{ColliderName, PositionSensor},
+        entities::LevelEntity,
+        level::{CurrentLevel, Level},
+        localization::get_translation,
+        state::AppState,
+        triggers::{
+            SensorTrigger, TriggerHandlerAppExt, TriggerHandlers, TriggerKind, TriggeredSensor,
+            run_trigger_handlers,
+        },
+    },
+    player::Player,
+};
+
+const CHECKPOINT_CUE_DURATION: f32 = 1.5;
+const CHECKPOINT_CUE_SPEED: f32 = 60.0;
+const CHECKPOINT_METADATA: &str = "checkpoint";
+
+/// Last checkpoint reached in the current level
+#[derive(Resource, Default, Debug)]
+pub struct Checkpoint {
+    pub name: Option<String>,
+    /// Respawn position in Bevy coordinates
+    pub position: Option<Vec2>,
+    /// Sensors triggered before reaching the checkpoint
+    pub passed_sensors: HashSet<String>,
+    /// Objects of the Entities layer still spawned when reaching the checkpoint
+    pub alive_objects: HashSet<String>,
+    /// Locked screens already unlocked when reaching the checkpoint
+    pub unlocked_screens: Vec<(usize, usize)>,
+}
+
+#[derive(Component, Deref, DerefMut)]
+struct CheckpointCue(Timer);
+
+pub struct CheckpointPlugin;
+
+impl Plugin for CheckpointPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_trigger_handler(TriggerKind::Checkpoint, activate_checkpoint)
+            .add_systems(
+                OnEnter(AppState::StartMenu),
+                (reset_checkpoint, despawn_cue),
+            )
+            .add_systems(
+                OnEnter(AppState::FinishLevel),
+                (reset_checkpoint, despawn_cue),
+            )
+            .add_systems(
+                Update,
+                (reach_checkpoint_screen, fade_cue)
+                    .after(CollisionSet)
+                    .run_if(in_state(AppState::GameRunning)),
+            )
+            .insert_resource(Checkpoint::default());
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn activate_checkpoint(
+    In(triggered_sensor): In<TriggeredSensor>,
+    mut commands: Commands,
+    rock_run_assets: Res<RockRunAssets>,
+    locale: Res<Locale>,
+    assets: Res<Assets<BundleAsset>>,
+    mut checkpoint: ResMut<Checkpoint>,
+    position_sensors: Query<(&ColliderName, &ActiveCollisionTypes), With<PositionSensor>>,
+    level_entities: Query<&LevelEntity>,
+    current_level: Res<CurrentLevel>,
+    levels: Query<&Level>,
+    player: Query<&Transform, With<Player>>,
+) -> Result<()> {
+    let SensorTrigger::Checkpoint { position } = triggered_sensor.trigger else {
+        return Ok(());
+    };
+    if !triggered_sensor.entered || checkpoint.name.as_ref() == Some(&triggered_sensor.sensor_name)
+    {
+        return Ok(());
+    }
+
+    let player = player.single()?;
+    let position = position.unwrap_or(player.translation.xy());
+    info!(
+        "checkpoint {} reached, respawn at {:?}",
+        triggered_sensor.sensor_name, position
+    );
+
+    checkpoint.name = Some(triggered_sensor.sensor_name);
+    checkpoint.position = Some(position);
+    checkpoint.passed_sensors = position_sensors
+        .iter()
+        .filter(|(_, active_collision_types)| {
+            **active_collision_types == ActiveCollisionTypes::STATIC_STATIC
+        })
+        .map(|(collider_name, _)| collider_name.0.clone())
+        .collect();
+    checkpoint.alive_objects = level_entities
+        .iter()
+        .map(|level_entity| level_entity.name().to_string())
+        .collect();
+    checkpoint.unlocked_screens = levels
+        .iter()
+        .find(|level| level.id == current_level.id)
+        .map(|level| level.map.unlocked_screens().collect())
+        .unwrap_or_default();
+
+    commands.spawn((
+        AudioPlayer::new(rock_run_assets.get_something_sound.clone()),
+        PlaybackSettings {
+            mode: PlaybackMode::Despawn,
+            volume: Volume::Linear(0.8),
+            ..default()
+        },
+    ));
+    commands.spawn((
+        Text2d::new(get_translation(
+            &locale,
+            &assets,
+            &rock_run_assets,
+            "checkpoint",
+            None,
+        )),
+        TextFont {
+            font: rock_run_assets.cute_dino_font.clone(),
+            font_size: 40.0,
+            ..default()
+        },
+        TextColor(Color::srgb_u8(0xF4, 0x78, 0x04)),
+        Transform::from_translation((player.translation.xy() + Vec2::Y * 80.0).extend(30.0)),
+        CheckpointCue(Timer::from_seconds(
+            CHECKPOINT_CUE_DURATION,
+            TimerMode::Once,
+        )),
+    ));
+    Ok(())
+}
+
+/// Triggers the checkpoint of the screen of the player, if it is a checkpoint screen
+fn reach_checkpoint_screen(
+    mut commands: Commands,
+    handlers: Res<TriggerHandlers>,
+    checkpoint: Res<Checkpoint>,
+    current_level: Res<CurrentLevel>,
+    levels: Query<&Level>,
+    player: Query<&Transform, With<Player>>,
+) -> Result<()> {
+    let player = player.single()?;
+    let Some(screen) = levels
+        .iter()
+        .find(|level| level.id == current_level.id)
+        .and_then(|level| level.map.get_screen(player.translation.xy(), 0.0, 0.0))
+        .filter(|screen| screen.get_metadata(CHECKPOINT_METADATA) == Some("true"))
+    else {
+        return Ok(());
+    };
+
+    let (index_x, index_y) = screen.get_indices();
+    let sensor_name = format!("screen_{index_x}_{index_y}");
+    if checkpoint.name.as_ref() != Some(&sensor_name) {
+        run_trigger_handlers(
+            &mut commands,
+            &handlers,
+            TriggeredSensor {
+                sensor_name,
+                trigger: SensorTrigger::Checkpoint { position: None },
+                entered: true,
+            },
+        );
+    }
+    Ok(())
+}
+
+/// The cue rises and fades out
+fn fade_cue(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut cues: Query<(Entity, &mut CheckpointCue, &mut Transform, &mut TextColor)>,
+) {
+    for (entity, mut timer, mut transform, mut text_color) in cues.iter_mut() {
+        timer.tick(time.delta());
+        if timer.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        transform.translation.y += CHECKPOINT_CUE_SPEED * time.delta_secs();
+        text_color.0.set_alpha(timer.fraction_remaining());
+    }
+}
+
+fn reset_checkpoint(mut checkpoint: ResMut<Checkpoint>) {
+    *checkpoint = Checkpoint::default();
+}
+
+fn despawn_cue(mut commands: Commands, cues: Query<Entity, With<CheckpointCue>>) {
+    for entity in cues.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,141 @@
+// Crumbling platforms are rectangles of the Platforms layer with the `crumbling` class. They shake
+// when the player lands on them, fall, and come back to their place a few seconds later, or right
+// away when the player restarts.
+
+use bevy::prelude::*;
+use bevy_rapier2d::geometry::ColliderDisabled;
+
+use crate::{
+    assets::RockRunAssets,
+    collisions::CollisionSet,
+    coregame::colliders::CrumblingPlatform,
+    coregame::state::AppState,
+    events::{CrumblingPlatformCollision, Restart},
+};
+
+const CRUMBLING_PLATFORM_Z: f32 = 8.0;
+const SHAKE_DURATION: f32 = 0.6;
+const SHAKE_AMPLITUDE: f32 = 3.0;
+const SHAKE_FREQUENCY: f32 = 60.0;
+const FALL_SPEED: f32 = 400.0;
+const RESPAWN_DELAY: f32 = 3.0;
+
+#[derive(Component, Deref, DerefMut)]
+struct Shaking(Timer);
+
+#[derive(Component, Deref, DerefMut)]
+struct Falling(Timer);
+
+pub struct CrumblingPlatformPlugin;
+
+impl Plugin for CrumblingPlatformPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, setup_crumbling_platforms)
+            .add_systems(
+                Update,
+                (start_shaking, shake, fall)
+                    .chain()
+                    .after(CollisionSet)
+                    .run_if(in_state(AppState::GameRunning)),
+            )
+            .add_systems(
+                Update,
+                reset_crumbling_platforms
+                    .after(CollisionSet)
+                    .run_if(on_event::<Restart>),
+            );
+    }
+}
+
+/// Platforms colliders have no sprite, crumbling ones get the sprite of the moving platforms
+fn setup_crumbling_platforms(
+    mut commands: Commands,
+    rock_run_assets: Res<RockRunAssets>,
+    mut platforms: Query<
+        (Entity, &mut CrumblingPlatform, &mut Transform),
+        Added<CrumblingPlatform>,
+    >,
+) {
+    for (entity, mut platform, mut transform) in platforms.iter_mut() {
+        platform.origin.z = CRUMBLING_PLATFORM_Z;
+        transform.translation = platform.origin;
+        commands.entity(entity).insert(Sprite {
+            image: rock_run_assets.moving_platform.clone(),
+            custom_size: Some(platform.size),
+            ..default()
+        });
+    }
+}
+
+fn start_shaking(
+    mut commands: Commands,
+    mut crumbling_platform_collision: EventReader<CrumblingPlatformCollision>,
+    platforms: Query<(), (With<CrumblingPlatform>, Without<Shaking>, Without<Falling>)>,
+) {
+    for ev in crumbling_platform_collision.read() {
+        if platforms.contains(ev.entity) {
+            debug!("crumbling platform {:?} shakes", ev.entity);
+            commands
+                .entity(ev.entity)
+                .insert(Shaking(Timer::from_seconds(
+                    SHAKE_DURATION,
+                    TimerMode::Once,
+                )));
+        }
+    }
+}
+
+fn shake(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut platforms: Query<(Entity, &CrumblingPlatform, &mut Shaking, &mut Transform)>,
+) {
+    for (entity, platform, mut timer, mut transform) in platforms.iter_mut() {
+        timer.tick(time.delta());
+        transform.translation.x =
+            platform.origin.x + SHAKE_AMPLITUDE * (timer.elapsed_secs() * SHAKE_FREQUENCY).sin();
+
+        if timer.finished() {
+            transform.translation = platform.origin;
+            commands.entity(entity).remove::<Shaking>().insert((
+                Falling(Timer::from_seconds(RESPAWN_DELAY, TimerMode::Once)),
+                ColliderDisabled,
+            ));
+        }
+    }
+}
+
+/// The platform falls through the level, then comes back to its place
+fn fall(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut platforms: Query<(Entity, &CrumblingPlatform, &mut Falling, &mut Transform)>,
+) {
+    for (entity, platform, mut timer, mut transform) in platforms.iter_mut() {
+        timer.tick(time.delta());
+        transform.translation.y -= FALL_SPEED * time.delta_secs();
+
+        if timer.finished() {
+            debug!("crumbling platform {:?} is back", entity);
+            transform.translation = platform.origin;
+            commands
+                .entity(entity)
+                .remove::<(Falling, ColliderDisabled)>();
+        }
+    }
+}
+
+fn reset_crumbling_platforms(
+    mut commands: Commands,
+    mut platforms: Query<
+        (Entity, &CrumblingPlatform, &mut Transform),
+        Or<(With<Shaking>, With<Falling>)>,
+    >,
+) {
+    for (entity, platform, mut transform) in platforms.iter_mut() {
+        transform.translation = platform.origin;
+        commands
+            .entity(entity)
+            .remove::<(Shaking, Falling, ColliderDisabled)>();
+    }
+}
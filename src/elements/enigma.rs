@@ -3,9 +3,10 @@ use std::mem;
 use crate::{
     assets::RockRunAssets,
     beasts::squirel::Nuts,
+    collision_layers::CollisionLayer,
     coregame::{
         colliders::{ColliderName, Story},
-        level::{CurrentLevel, Level},
+        entities::{EntitySpawn, EntitySpawnerAppExt},
         localization,
         state::AppState,
     },
@@ -14,9 +15,9 @@ use crate::{
         rock::{ROCK_DIAMETER, ROCK_SCALE_FACTOR},
         story::{TextSyllableValues, decompose_selection_msg},
     },
-    events::{EnigmaResult, NoMoreStoryMessages},
+    events::{EnigmaResult, NoMoreStoryMessages, SpawnLevelEntity},
     helpers::texture::cycle_texture,
-    key::{KEY_HEIGHT, KEY_SCALE_FACTOR, KEY_WIDTH, Key, Keys},
+    key::Keys,
 };
 use bevy::{
     audio::{PlaybackMode, Volume},
@@ -26,7 +27,7 @@ use bevy::{
 use bevy_fluent::{BundleAsset, Locale};
 use bevy_rapier2d::{
     dynamics::{Ccd, ExternalImpulse, GravityScale, RigidBody, Velocity},
-    prelude::{ActiveCollisionTypes, ActiveEvents, Collider, CollisionGroups, Group, Sensor},
+    prelude::{ActiveCollisionTypes, ActiveEvents, Collider, Sensor},
 };
 use rand::{Rng, seq::SliceRandom};
 
@@ -87,8 +88,12 @@ pub struct EnigmaPlugin;
 impl Plugin for EnigmaPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(Enigmas::default())
-            .add_systems(OnEnter(AppState::GameCreate), spawn_enigma_materials)
-            .add_systems(OnEnter(AppState::NextLevel), spawn_enigma_materials)
+            .add_systems(OnEnter(AppState::GameCreate), setup_enigmas)
+            .add_systems(OnEnter(AppState::NextLevel), setup_enigmas)
+            .register_entity_spawner("rock_gate", spawn_rock_gate)
+            .register_entity_spawner("gate", spawn_gate)
+            .register_entity_spawner("warrior", spawn_warrior)
+            .register_entity_spawner("story", spawn_story)
             .add_systems(
                 OnEnter(AppState::StartMenu),
                 (despawn_warrior, despawn_gate, despawn_rockgate),
@@ -107,18 +112,13 @@ impl Plugin for EnigmaPlugin {
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-fn spawn_enigma_materials(
-    mut commands: Commands,
+fn setup_enigmas(
     locale: Res<Locale>,
     assets: Res<Assets<BundleAsset>>,
     rock_run_assets: Res<RockRunAssets>,
-    mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
-    current_level: Res<CurrentLevel>,
-    levels: Query<&Level, With<Level>>,
     mut enigmas: ResMut<Enigmas>,
 ) {
-    info!("spawn_enigma_materials");
+    info!("setup_enigmas");
     let mut rng = rand::rng();
 
     let mut mcqs = vec![
@@ -235,135 +235,129 @@ fn spawn_enigma_materials(
     *enigmas = Enigmas {
         enigmas: enigmas_builder,
     };
+}
 
-    let level = levels
-        .iter()
-        .find(|level| level.id == current_level.id)
-        .unwrap();
-
-    match current_level.id {
-        1 => {
-            let texture = rock_run_assets.rock_ball.clone();
+/// Spawns a rock released by the answer (`move_on` property, `correct` or `incorrect`) to the
+/// enigma of the `story` property, pushed by the `impulse_x` property
+fn spawn_rock_gate(
+    In(entity_spawn): In<EntitySpawn>,
+    mut commands: Commands,
+    rock_run_assets: Res<RockRunAssets>,
+) -> Option<Entity> {
+    info!("spawn_rock_gate {}", entity_spawn.name);
+
+    let (Some(story), Some(impulse_x)) = (
+        entity_spawn.get_string("story"),
+        entity_spawn.get_float("impulse_x"),
+    ) else {
+        warn!("Rock gate {}: missing story or impulse", entity_spawn.name);
+        return None;
+    };
+    let move_on = match entity_spawn.get_string("move_on") {
+        Some("correct") => Answer::Correct,
+        Some("incorrect") => Answer::Incorrect,
+        move_on => {
+            warn!(
+                "Rock gate {}: unknown answer {:?}",
+                entity_spawn.name, move_on
+            );
+            return None;
+        }
+    };
 
-            commands.spawn((
+    Some(
+        commands
+            .spawn((
                 Sprite {
-                    image: texture.clone(),
+                    image: rock_run_assets.rock_ball.clone(),
                     ..default()
                 },
                 Transform {
                     scale: Vec3::splat(ROCK_SCALE_FACTOR),
-                    translation: level
-                        .map
-                        .tiled_to_bevy_coord(Vec2::new(2800.0, 160.0 - ROCK_DIAMETER / 2.0))
-                        .extend(20.0),
+                    translation: entity_spawn
+                        .position
+                        .extend(entity_spawn.get_float("depth").unwrap_or(20.0)),
                     ..default()
                 },
                 RigidBody::Dynamic,
                 GravityScale(20.0),
                 Velocity::zero(),
                 Collider::ball(ROCK_DIAMETER / 2.0),
-                // Group definition so far, might evolves in the future...
-                // GROUP_1: rocks (only this one)
-                // GROUP_2: pterodactyls
-                // GROUP_3: squirrels
-                //
-                // Note: All colliders not part of a group (without this component) collides by default.
-                CollisionGroups::new(Group::GROUP_1, Group::GROUP_1),
+                CollisionLayer::Projectile.groups(),
                 Ccd::enabled(),
                 ExternalImpulse::default(),
                 RockGate {
-                    associated_story: "story04-03".to_string(),
-                    impulse: Vec2::new(4096.0 * 120.0, 0.0),
-                    move_on: Answer::Correct,
+                    associated_story: story.to_string(),
+                    impulse: Vec2::new(impulse_x, 0.0),
+                    move_on,
                 },
-            ));
+            ))
+            .id(),
+    )
+}
 
-            commands.spawn((
-                Sprite {
-                    image: texture.clone(),
-                    ..default()
-                },
-                Transform {
-                    scale: Vec3::splat(ROCK_SCALE_FACTOR),
-                    translation: level
-                        .map
-                        .tiled_to_bevy_coord(Vec2::new(7505.0, 208.0 - ROCK_DIAMETER / 2.0))
-                        .extend(20.0),
-                    ..default()
-                },
-                RigidBody::Dynamic,
-                GravityScale(20.0),
-                Velocity::zero(),
-                Collider::ball(ROCK_DIAMETER / 2.0),
-                Ccd::enabled(),
-                ExternalImpulse::default(),
-                RockGate {
-                    associated_story: "story06-03".to_string(),
-                    impulse: Vec2::new(-4096.0 * 120.0, 0.0),
-                    move_on: Answer::Incorrect,
-                },
-            ));
+/// Spawns a gate opened by solving the enigma of the `story` property, upside down with the
+/// `flip_y` property
+fn spawn_gate(
+    In(entity_spawn): In<EntitySpawn>,
+    mut commands: Commands,
+    rock_run_assets: Res<RockRunAssets>,
+) -> Option<Entity> {
+    info!("spawn_gate {}", entity_spawn.name);
 
-            let texture = rock_run_assets.gate.clone();
-            commands.spawn((
-                Sprite {
-                    image: texture,
-                    ..default()
-                },
-                Transform {
-                    scale: Vec3::splat(GATE_SCALE_FACTOR),
-                    translation: level
-                        .map
-                        .tiled_to_bevy_coord(Vec2::new(8032.0, 548.0))
-                        .extend(3.0),
-                    ..default()
-                },
-                Collider::cuboid(GATE_WIDTH / 2.0, GATE_HEIGHT / 2.0),
-                AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
-                Gate {
-                    associated_story: "story06-03".to_string(),
-                },
-            ));
-        }
+    let Some(story) = entity_spawn.get_string("story") else {
+        warn!("Gate {}: missing story", entity_spawn.name);
+        return None;
+    };
 
-        2 => {
-            let texture = rock_run_assets.gate.clone();
-            commands.spawn((
+    Some(
+        commands
+            .spawn((
                 Sprite {
-                    image: texture,
-                    flip_y: true,
+                    image: rock_run_assets.gate.clone(),
+                    flip_y: entity_spawn.get_bool("flip_y").unwrap_or(false),
                     ..default()
                 },
                 Transform {
                     scale: Vec3::splat(GATE_SCALE_FACTOR),
-                    translation: level
-                        .map
-                        .tiled_to_bevy_coord(Vec2::new(12144.0, 2135.0))
-                        .extend(4.0),
+                    translation: entity_spawn
+                        .position
+                        .extend(entity_spawn.get_float("depth").unwrap_or(3.0)),
                     ..default()
                 },
                 Collider::cuboid(GATE_WIDTH / 2.0, GATE_HEIGHT / 2.0),
+                CollisionLayer::Terrain.groups(),
                 AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
                 Gate {
-                    associated_story: "story08-03".to_string(),
+                    associated_story: story.to_string(),
                 },
-            ));
-        }
-
-        3 => {
-            let texture = rock_run_assets.warrior.clone();
-            let layout = TextureAtlasLayout::from_grid(
-                UVec2::new(WARRIOR_WIDTH as u32, WARRIOR_HEIGHT as u32),
-                6,
-                1,
-                None,
-                None,
-            );
-            let texture_atlas_layout = texture_atlases.add(layout);
+            ))
+            .id(),
+    )
+}
 
-            commands.spawn((
+fn spawn_warrior(
+    In(entity_spawn): In<EntitySpawn>,
+    mut commands: Commands,
+    rock_run_assets: Res<RockRunAssets>,
+    mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
+) -> Option<Entity> {
+    info!("spawn_warrior {}", entity_spawn.name);
+
+    let layout = TextureAtlasLayout::from_grid(
+        UVec2::new(WARRIOR_WIDTH as u32, WARRIOR_HEIGHT as u32),
+        6,
+        1,
+        None,
+        None,
+    );
+    let texture_atlas_layout = texture_atlases.add(layout);
+
+    Some(
+        commands
+            .spawn((
                 Sprite {
-                    image: texture,
+                    image: rock_run_assets.warrior.clone(),
                     texture_atlas: Some(TextureAtlas {
                         layout: texture_atlas_layout,
                         index: 0,
@@ -372,39 +366,36 @@ fn spawn_enigma_materials(
                 },
                 Transform {
                     scale: Vec3::splat(WARRIOR_SCALE_FACTOR),
-                    translation: level
-                        .map
-                        .tiled_to_bevy_coord(Vec2::new(5575.0, 608.0))
-                        .extend(2.0),
+                    translation: entity_spawn
+                        .position
+                        .extend(entity_spawn.get_float("depth").unwrap_or(2.0)),
                     ..default()
                 },
                 AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
                 Warrior,
-            ));
+            ))
+            .id(),
+    )
+}
 
-            let texture = rock_run_assets.gate.clone();
-            commands.spawn((
-                Sprite {
-                    image: texture,
-                    ..default()
-                },
-                Transform {
-                    scale: Vec3::splat(GATE_SCALE_FACTOR),
-                    translation: level
-                        .map
-                        .tiled_to_bevy_coord(Vec2::new(5488.0, 615.0))
-                        .extend(2.0),
-                    ..default()
-                },
-                Collider::cuboid(GATE_WIDTH / 2.0, GATE_HEIGHT / 2.0),
-                AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
-                Gate {
-                    associated_story: "story03-03".to_string(),
-                },
-            ));
-        }
-        _ => (),
-    }
+/// Spawns a story sensor named after its object, usually `deferred` until an enigma is solved
+fn spawn_story(In(entity_spawn): In<EntitySpawn>, mut commands: Commands) -> Option<Entity> {
+    info!("spawn_story {}", entity_spawn.name);
+
+    Some(
+        commands
+            .spawn((
+                Collider::cuboid(1.0, 1.0),
+                CollisionLayer::Sensor.groups(),
+                Story,
+                Transform::from_translation(entity_spawn.position.extend(0.0)),
+            ))
+            .insert(Sensor)
+            .insert(ActiveEvents::COLLISION_EVENTS)
+            .insert(ActiveCollisionTypes::KINEMATIC_STATIC)
+            .insert(ColliderName(entity_spawn.name))
+            .id(),
+    )
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -416,19 +407,12 @@ fn check_enigma(
     params: ResMut<TextSyllableValues>,
     mut enigna_result: EventWriter<EnigmaResult>,
     nuts: Res<Nuts>,
-    levels: Query<&Level, With<Level>>,
-    current_level: Res<CurrentLevel>,
     collected_keys: Res<Keys>,
     stories_query: Query<(Entity, &ColliderName), With<Story>>,
 ) {
     for ev in no_more_msg_event.read() {
         debug!("No more story messages: {:?}", ev.latest);
 
-        let level = levels
-            .iter()
-            .find(|level| level.id == current_level.id)
-            .unwrap();
-
         match ev.latest.as_ref() {
             "story03-03" => {
                 let story = "story03-03";
@@ -503,7 +487,6 @@ fn check_enigma(
                     &mut enigna_result,
                     &mut commands,
                     &rock_run_assets,
-                    level,
                     &stories_query,
                     spawn_story_100,
                 );
@@ -516,9 +499,8 @@ fn check_enigma(
                     &mut enigna_result,
                     &mut commands,
                     &rock_run_assets,
-                    level,
                     &stories_query,
-                    |_level, _assets, _commands| {},
+                    |_commands| {},
                 );
             }
             "story07-04" => {
@@ -529,7 +511,6 @@ fn check_enigma(
                     &mut enigna_result,
                     &mut commands,
                     &rock_run_assets,
-                    level,
                     &stories_query,
                     spawn_key,
                 );
@@ -565,7 +546,7 @@ fn check_enigma(
                         &rock_run_assets,
                         &stories_query,
                     );
-                    spawn_story_101(level, &rock_run_assets, &mut commands);
+                    spawn_story_101(&mut commands);
                 } else {
                     debug!(
                         "Incorrect answer: {} - {} = {} | Collected keys: {}",
@@ -633,53 +614,22 @@ fn check_enigma(
     }
 }
 
-fn spawn_story_100(level: &Level, _rock_run_assets: &Res<RockRunAssets>, commands: &mut Commands) {
-    let Vec2 { x, y } = level.map.tiled_to_bevy_coord(Vec2::new(7250.0, 608.0));
-    commands
-        .spawn((
-            Collider::cuboid(1.0, 1.0),
-            Story,
-            Transform::from_xyz(x, y, 0.0),
-        ))
-        .insert(Sensor)
-        .insert(ActiveEvents::COLLISION_EVENTS)
-        .insert(ActiveCollisionTypes::KINEMATIC_STATIC)
-        .insert(ColliderName("story100".to_string()));
+fn spawn_story_100(commands: &mut Commands) {
+    commands.send_event(SpawnLevelEntity {
+        name: "story100".to_string(),
+    });
 }
 
-fn spawn_story_101(level: &Level, _rock_run_assets: &Res<RockRunAssets>, commands: &mut Commands) {
-    let Vec2 { x, y } = level.map.tiled_to_bevy_coord(Vec2::new(12496.0, 2064.0));
-    commands
-        .spawn((
-            Collider::cuboid(1.0, 1.0),
-            Story,
-            Transform::from_xyz(x, y, 0.0),
-        ))
-        .insert(Sensor)
-        .insert(ActiveEvents::COLLISION_EVENTS)
-        .insert(ActiveCollisionTypes::KINEMATIC_STATIC)
-        .insert(ColliderName("story101".to_string()));
+fn spawn_story_101(commands: &mut Commands) {
+    commands.send_event(SpawnLevelEntity {
+        name: "story101".to_string(),
+    });
 }
 
-fn spawn_key(level: &Level, rock_run_assets: &Res<RockRunAssets>, commands: &mut Commands) {
-    let start_pos = level.map.tiled_to_bevy_coord(Vec2::new(5984.0, 2064.0));
-    commands.spawn((
-        Sprite {
-            image: rock_run_assets.key.clone(),
-            ..default()
-        },
-        Transform {
-            scale: Vec3::splat(KEY_SCALE_FACTOR),
-            translation: start_pos.extend(10.0),
-            ..default()
-        },
-        Collider::cuboid(KEY_WIDTH / 2.0, KEY_HEIGHT / 2.0),
-        Sensor,
-        ActiveEvents::COLLISION_EVENTS,
-        ActiveCollisionTypes::KINEMATIC_STATIC,
-        Key,
-        ColliderName("key01".to_string()),
-    ));
+fn spawn_key(commands: &mut Commands) {
+    commands.send_event(SpawnLevelEntity {
+        name: "key01".to_string(),
+    });
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -690,11 +640,10 @@ fn check_mcq<F>(
     enigna_result: &mut EventWriter<EnigmaResult>,
     commands: &mut Commands,
     rock_run_assets: &Res<RockRunAssets>,
-    level: &Level,
     stories_query: &Query<(Entity, &ColliderName), With<Story>>,
     correct_fn: F,
 ) where
-    F: Fn(&Level, &Res<RockRunAssets>, &mut Commands),
+    F: Fn(&mut Commands),
 {
     let mcq_values = enigmas
         .enigmas
@@ -717,7 +666,7 @@ fn check_mcq<F>(
 
     if mcq_values[2].contains(user_answer) {
         debug!("Correct answer: {}", user_answer);
-        correct_fn(level, rock_run_assets, commands);
+        correct_fn(commands);
         correct_answer(
             enigna_result,
             story,
@@ -0,0 +1,338 @@
+// Wind, conveyor and ice zones come from the sensors of the "Wind", "Conveyors" and "Ice" layers.
+//
+// The zones a body is in are tracked from the collision events of their sensors. Winds and
+// conveyors push the bodies, ice keeps them sliding. The movement of the character controllers
+// (player and flying beasts) is adjusted just before the physics step, after every system moving
+// them, while dynamic bodies (rocks, fireballs...) get their velocity changed and their friction
+// lowered as long as they are on ice.
+
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+use bevy_rapier2d::{
+    control::KinematicCharacterController,
+    dynamics::{RigidBody, Velocity},
+    pipeline::CollisionEvent,
+    plugin::PhysicsSet,
+    prelude::{CoefficientCombineRule, Friction},
+};
+
+use crate::{
+    beasts::{bat::Bat, pterodactyl::Pterodactyl},
+    coregame::{
+        camera::no_screen_transition,
+        colliders::{Conveyor, Ice, Wind, ZoneEffect},
+        state::AppState,
+    },
+    player::Player,
+};
+
+/// How fast a body gets back its grip when the ice is not slippery
+const ICE_GRIP: f32 = 10.0;
+/// Dynamic bodies reach the speed of a wind or a conveyor at this rate, per second
+const RIGID_BODY_ACCELERATION: f32 = 4.0;
+/// Default friction of rapier colliders
+const DEFAULT_FRICTION: f32 = 0.5;
+
+/// Bodies in each zone, updated from the collision events
+#[derive(Resource, Default, Debug)]
+struct ZoneContacts(HashMap<Entity, HashSet<Entity>>);
+
+/// Effects of the zones on a body
+#[derive(Component, Default, Debug)]
+pub struct ZoneForces {
+    /// Velocity given by the winds and conveyors in pixels per second
+    pub push: Vec2,
+    /// Between 0.0 (no ice) and 1.0 (no friction)
+    pub slipperiness: f32,
+    /// Horizontal velocity of a character controller sliding on ice
+    slide: f32,
+}
+
+/// Friction of a dynamic body before it slid on ice, `None` for the default friction
+#[derive(Component, Debug)]
+struct GripFriction(Option<Friction>);
+
+impl ZoneForces {
+    fn is_active(&self) -> bool {
+        self.push != Vec2::ZERO || self.slipperiness > 0.0 || self.slide != 0.0
+    }
+
+    /// Applies the effects to the translation of a character controller for this frame
+    fn apply(&mut self, translation: Vec2, delta_secs: f32) -> Vec2 {
+        if delta_secs <= 0.0 {
+            return translation;
+        }
+
+        // On ice, the body keeps part of its previous speed.
+        let grip = 1.0 - self.slipperiness.powf(delta_secs * ICE_GRIP);
+        self.slide += (translation.x / delta_secs - self.slide) * grip;
+        if self.slide.abs() < 1.0 {
+            self.slide = 0.0;
+        }
+
+        Vec2::new(self.slide * delta_secs, translation.y) + self.push * delta_secs
+    }
+}
+
+pub struct ForceZonePlugin;
+
+impl Plugin for ForceZonePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::StartMenu), reset_zone_contacts)
+            .add_systems(OnEnter(AppState::FinishLevel), reset_zone_contacts)
+            .add_systems(
+                Update,
+                (
+                    zone_collisions,
+                    update_zone_forces,
+                    apply_zone_forces_to_rigid_bodies,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::GameRunning)),
+            )
+            .add_systems(
+                PostUpdate,
+                apply_zone_forces_to_controllers
+                    .before(PhysicsSet::SyncBackend)
+                    .run_if(in_state(AppState::GameRunning))
+                    .run_if(no_screen_transition),
+            )
+            .insert_resource(ZoneContacts::default());
+    }
+}
+
+fn reset_zone_contacts(mut contacts: ResMut<ZoneContacts>) {
+    contacts.0.clear();
+}
+
+fn zone_collisions(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut contacts: ResMut<ZoneContacts>,
+    zones: Query<(), With<ZoneEffect>>,
+    controllers: Query<(), Or<(With<Player>, With<Bat>, With<Pterodactyl>)>>,
+    rigid_bodies: Query<&RigidBody>,
+) {
+    let is_body = |entity: Entity| {
+        controllers.contains(entity) || matches!(rigid_bodies.get(entity), Ok(RigidBody::Dynamic))
+    };
+
+    for collision_event in collision_events.read() {
+        // Warning, e1 and e2 can be swapped.
+        let (e1, e2, started) = match collision_event {
+            CollisionEvent::Started(e1, e2, _cf) => (*e1, *e2, true),
+            CollisionEvent::Stopped(e1, e2, _cf) => (*e1, *e2, false),
+        };
+        let (zone, body) = if zones.contains(e1) && is_body(e2) {
+            (e1, e2)
+        } else if zones.contains(e2) && is_body(e1) {
+            (e2, e1)
+        } else {
+            continue;
+        };
+
+        let body_zones = contacts.0.entry(body).or_default();
+        if started {
+            body_zones.insert(zone);
+        } else {
+            body_zones.remove(&zone);
+        }
+    }
+}
+
+fn update_zone_forces(
+    mut commands: Commands,
+    mut contacts: ResMut<ZoneContacts>,
+    zones: Query<(&ZoneEffect, Has<Wind>, Has<Conveyor>, Has<Ice>)>,
+    mut bodies: Query<Option<&mut ZoneForces>>,
+) {
+    contacts.0.retain(|body, body_zones| {
+        let Ok(forces) = bodies.get_mut(*body) else {
+            // The body has been despawned.
+            return false;
+        };
+
+        let mut push = Vec2::ZERO;
+        let mut slipperiness: f32 = 0.0;
+        for (effect, wind, conveyor, ice) in
+            body_zones.iter().filter_map(|zone| zones.get(*zone).ok())
+        {
+            if wind || conveyor {
+                push += effect.direction * effect.strength;
+            }
+            if ice {
+                slipperiness = slipperiness.max(effect.strength.clamp(0.0, 1.0));
+            }
+        }
+
+        match forces {
+            Some(mut forces) => {
+                forces.push = push;
+                forces.slipperiness = slipperiness;
+            }
+            None => {
+                commands.entity(*body).insert(ZoneForces {
+                    push,
+                    slipperiness,
+                    ..default()
+                });
+            }
+        }
+
+        // Bodies out of every zone keep their forces, reset to no effect.
+        !body_zones.is_empty()
+    });
+}
+
+fn apply_zone_forces_to_controllers(
+    time: Res<Time>,
+    mut controllers: Query<(&mut KinematicCharacterController, &mut ZoneForces)>,
+) {
+    for (mut controller, mut forces) in controllers.iter_mut() {
+        if controller.translation.is_none() && !forces.is_active() {
+            continue;
+        }
+
+        let translation = controller.translation.unwrap_or_default();
+        controller.translation = Some(forces.apply(translation, time.delta_secs()));
+    }
+}
+
+fn apply_zone_forces_to_rigid_bodies(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut rigid_bodies: Query<(
+        Entity,
+        &RigidBody,
+        &mut Velocity,
+        &ZoneForces,
+        Option<&Friction>,
+        Option<&GripFriction>,
+    )>,
+) {
+    for (entity, rigid_body, mut velocity, forces, friction, grip) in rigid_bodies.iter_mut() {
+        if *rigid_body != RigidBody::Dynamic {
+            continue;
+        }
+
+        // Winds and conveyors drag the body toward their speed.
+        for axis in [Vec2::X, Vec2::Y] {
+            let push = forces.push.dot(axis);
+            let speed = velocity.linvel.dot(axis);
+            if push != 0.0 && speed * push.signum() < push.abs() {
+                velocity.linvel += axis * push * RIGID_BODY_ACCELERATION * time.delta_secs();
+            }
+        }
+
+        // Ice reduces the friction of the body, until it leaves the ice.
+        if forces.slipperiness > 0.0 {
+            let original = grip.map_or(friction.copied(), |grip| grip.0);
+            let coefficient = original.map_or(DEFAULT_FRICTION, |friction| friction.coefficient)
+                * (1.0 - forces.slipperiness);
+            if friction.is_none_or(|friction| friction.coefficient != coefficient) {
+                let mut body = commands.entity(entity);
+                body.insert(Friction {
+                    coefficient,
+                    combine_rule: CoefficientCombineRule::Min,
+                });
+                if grip.is_none() {
+                    body.insert(GripFriction(original));
+                }
+            }
+        } else if let Some(GripFriction(original)) = grip {
+            let mut body = commands.entity(entity);
+            match original {
+                Some(friction) => body.insert(*friction),
+                None => body.remove::<Friction>(),
+            };
+            body.remove::<GripFriction>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELTA_SECS: f32 = 0.1;
+
+    fn assert_near(actual: Vec2, expected: Vec2) {
+        assert!(
+            actual.abs_diff_eq(expected, 1e-4),
+            "{actual} is not {expected}"
+        );
+    }
+
+    #[test]
+    fn test_wind_pushes() {
+        let mut forces = ZoneForces {
+            push: Vec2::new(0.0, 50.0),
+            ..default()
+        };
+
+        // The wind adds to the movement of the body, jumps included.
+        assert_near(
+            forces.apply(Vec2::new(2.0, 3.0), DELTA_SECS),
+            Vec2::new(2.0, 8.0),
+        );
+        assert_near(forces.apply(Vec2::ZERO, DELTA_SECS), Vec2::new(0.0, 5.0));
+    }
+
+    #[test]
+    fn test_conveyor_carries() {
+        let mut forces = ZoneForces {
+            push: Vec2::new(-40.0, 0.0),
+            ..default()
+        };
+
+        // A body standing on the conveyor moves at its speed, without sliding after.
+        assert_near(
+            forces.apply(Vec2::new(0.0, -1.0), DELTA_SECS),
+            Vec2::new(-4.0, -1.0),
+        );
+        assert_eq!(forces.slide, 0.0);
+        assert_near(
+            forces.apply(Vec2::new(3.0, 0.0), DELTA_SECS),
+            Vec2::new(-1.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn test_ice_slides() {
+        let mut forces = ZoneForces {
+            slipperiness: 0.5,
+            ..default()
+        };
+
+        // The body only gets part of its speed, then keeps sliding when it stops.
+        let first = forces.apply(Vec2::new(10.0, 0.0), DELTA_SECS);
+        assert!(first.x > 0.0 && first.x < 10.0, "{first}");
+        let second = forces.apply(Vec2::ZERO, DELTA_SECS);
+        assert!(second.x > 0.0 && second.x < first.x, "{second}");
+
+        // Without friction, the body slides at a constant speed.
+        forces.slipperiness = 1.0;
+        let speed = forces.slide;
+        assert_near(
+            forces.apply(Vec2::ZERO, DELTA_SECS),
+            Vec2::new(speed * DELTA_SECS, 0.0),
+        );
+
+        // Off the ice, the body stops at once.
+        forces.slipperiness = 0.0;
+        assert_near(forces.apply(Vec2::ZERO, DELTA_SECS), Vec2::ZERO);
+        assert!(!forces.is_active());
+    }
+
+    #[test]
+    fn test_no_time_elapsed() {
+        let mut forces = ZoneForces {
+            push: Vec2::new(40.0, 0.0),
+            slipperiness: 0.5,
+            ..default()
+        };
+
+        assert_near(forces.apply(Vec2::new(1.0, 2.0), 0.0), Vec2::new(1.0, 2.0));
+    }
+}
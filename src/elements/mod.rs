@@ -1,4 +1,7 @@
+pub mod checkpoint;
+pub mod crumbling_platform;
 pub mod enigma;
+pub mod force_zone;
 pub mod moving_platform;
 pub mod plugins;
 pub mod rock;
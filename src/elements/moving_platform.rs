@@ -1,6 +1,6 @@
 use std::f32::consts::PI;
 
-use bevy::{platform::collections::HashMap, prelude::*};
+use bevy::prelude::*;
 use bevy_rapier2d::{
     control::KinematicCharacterController, dynamics::RigidBody, geometry::Collider,
     pipeline::QueryFilterFlags,
@@ -8,8 +8,9 @@ use bevy_rapier2d::{
 
 use crate::{
     assets::RockRunAssets,
+    collision_layers::CollisionLayer,
     coregame::{
-        level::{CurrentLevel, Level},
+        entities::{EntitySpawn, EntitySpawnerAppExt},
         state::AppState,
     },
     events::{MovingPlatformCollision, MovingPlatformDescending},
@@ -26,7 +27,6 @@ pub struct MovingPlatform {
     pub movement: MovingPlatformMovement,
 }
 
-#[allow(dead_code)]
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum MovingPlatformMovement {
     LeftRight(LeftRightData),
@@ -57,7 +57,6 @@ pub struct UpDownData {
     pub speed: f32,
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
 enum MovingPlatformDirection {
     Left,
@@ -74,8 +73,7 @@ impl Plugin for MovingPlatformPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(AppState::StartMenu), despawn_moving_platform)
             .add_systems(OnEnter(AppState::FinishLevel), despawn_moving_platform)
-            .add_systems(OnEnter(AppState::GameCreate), setup_moving_platforms)
-            .add_systems(OnEnter(AppState::NextLevel), setup_moving_platforms)
+            .register_entity_spawner("moving_platform", spawn_moving_platform)
             .add_systems(
                 Update,
                 (move_moving_platform)
@@ -86,131 +84,93 @@ impl Plugin for MovingPlatformPlugin {
     }
 }
 
-fn setup_moving_platforms(
+/// Spawns a moving platform from the properties of its object:
+///
+/// - `movement`: "up_down", "left_right" or "circle"
+/// - `direction`: "up", "down", "left", "right", "clockwise" or "anticlockwise"
+/// - `speed`: speed factor, 1.0 by default
+/// - `max_up`, `max_down`: vertical bounds of an "up_down" movement (Tiled y coordinates)
+/// - `max_left`, `max_right`: horizontal bounds of a "left_right" movement (Tiled x coordinates)
+/// - `center_x`, `center_y`: center of a "circle" movement (Tiled coordinates)
+fn spawn_moving_platform(
+    In(entity_spawn): In<EntitySpawn>,
     mut commands: Commands,
     rock_run_assets: Res<RockRunAssets>,
-    levels: Query<&Level, With<Level>>,
-    current_level: Res<CurrentLevel>,
-) {
-    info!("setup_moving_moving_platforms");
+) -> Option<Entity> {
+    info!("spawn_moving_platform {}", entity_spawn.name);
 
-    let level = levels
-        .iter()
-        .find(|level| level.id == current_level.id)
-        .unwrap();
+    let Some(movement) = get_movement(&entity_spawn) else {
+        warn!("Moving platform {}: invalid movement", entity_spawn.name);
+        return None;
+    };
 
-    let texture = rock_run_assets.moving_platform.clone();
-    let mut level_moving_platforms: HashMap<u8, Vec<MovingPlatform>> = HashMap::new();
-    level_moving_platforms.insert(
-        1,
-        vec![
-            MovingPlatform {
-                start_pos: level.map.tiled_to_bevy_coord(Vec2::new(2145.0, 550.0)),
-                movement: MovingPlatformMovement::UpDown(UpDownData {
-                    direction: MovingPlatformDirection::Up,
-                    max_down: level.map.tiled_to_bevy_coord(Vec2::new(2145.0, 575.0)).y,
-                    max_up: level.map.tiled_to_bevy_coord(Vec2::new(2145.0, 335.0)).y,
-                    speed: 1.0,
-                }),
-            },
-            MovingPlatform {
-                start_pos: level.map.tiled_to_bevy_coord(Vec2::new(5750.0, 368.0)),
-                movement: MovingPlatformMovement::Circle(CircleData {
-                    center: level.map.tiled_to_bevy_coord(Vec2::new(5875.0, 368.0)),
-                    direction: MovingPlatformDirection::Anticlockwise,
-                    speed: 1.0,
-                }),
-            },
-            MovingPlatform {
-                start_pos: level.map.tiled_to_bevy_coord(Vec2::new(6165.0, 368.0)),
-                movement: MovingPlatformMovement::Circle(CircleData {
-                    center: level.map.tiled_to_bevy_coord(Vec2::new(6290.0, 368.0)),
-                    direction: MovingPlatformDirection::Clockwise,
-                    speed: 2.0,
-                }),
-            },
-            MovingPlatform {
-                start_pos: level.map.tiled_to_bevy_coord(Vec2::new(6515.0, 368.0)),
-                movement: MovingPlatformMovement::LeftRight(LeftRightData {
-                    direction: MovingPlatformDirection::Right,
-                    max_left: level.map.tiled_to_bevy_coord(Vec2::new(6515.0, 0.0)).x,
-                    max_right: level.map.tiled_to_bevy_coord(Vec2::new(6915.0, 0.0)).x,
-                    speed: 2.5,
-                }),
-            },
-            MovingPlatform {
-                start_pos: level.map.tiled_to_bevy_coord(Vec2::new(7100.0, 400.0)),
-                movement: MovingPlatformMovement::UpDown(UpDownData {
-                    direction: MovingPlatformDirection::Up,
-                    max_down: level.map.tiled_to_bevy_coord(Vec2::new(7100.0, 549.0)).y,
-                    max_up: level.map.tiled_to_bevy_coord(Vec2::new(7100.0, 400.0)).y,
-                    speed: 0.0,
-                }),
-            },
-        ],
-    );
-    level_moving_platforms.insert(
-        3,
-        vec![
-            MovingPlatform {
-                start_pos: level.map.tiled_to_bevy_coord(Vec2::new(5920.0, 576.0)),
-                movement: MovingPlatformMovement::Circle(CircleData {
-                    center: level.map.tiled_to_bevy_coord(Vec2::new(5920.0, 451.0)),
-                    direction: MovingPlatformDirection::Clockwise,
-                    speed: 1.0,
-                }),
-            },
-            MovingPlatform {
-                start_pos: level.map.tiled_to_bevy_coord(Vec2::new(5920.0, 326.0)),
-                movement: MovingPlatformMovement::Circle(CircleData {
-                    center: level.map.tiled_to_bevy_coord(Vec2::new(5920.0, 451.0)),
-                    direction: MovingPlatformDirection::Clockwise,
-                    speed: 1.0,
-                }),
-            },
-            // MovingPlatform {
-            //     start_pos: level.map.tiled_to_bevy_coord(Vec2::new(5920.0, 176.0)),
-            //     movement: MovingPlatformMovement::LeftRight(LeftRightData {
-            //         direction: MovingPlatformDirection::Right,
-            //         max_left: level.map.tiled_to_bevy_coord(Vec2::new(5800.0, 0.0)).x,
-            //         max_right: level.map.tiled_to_bevy_coord(Vec2::new(6000.0, 0.0)).x,
-            //     }),
-            // },
-            // MovingPlatform {
-            //     start_pos: level.map.tiled_to_bevy_coord(Vec2::new(5600.0, 176.0)),
-            //     movement: MovingPlatformMovement::UpDown(UpDownData {
-            //         direction: MovingPlatformDirection::Up,
-            //         max_up: level.map.tiled_to_bevy_coord(Vec2::new(0.0, 426.0)).y,
-            //         max_down: level.map.tiled_to_bevy_coord(Vec2::new(0.0, 170.0)).y,
-            //     }),
-            // },
-        ],
-    );
+    Some(
+        commands
+            .spawn((
+                Sprite {
+                    image: rock_run_assets.moving_platform.clone(),
+                    ..default()
+                },
+                Transform {
+                    scale: Vec3::splat(MOVING_PLATFORM_SCALE_FACTOR),
+                    translation: entity_spawn.position.extend(8.0),
+                    ..default()
+                },
+                RigidBody::KinematicPositionBased,
+                Collider::cuboid(MOVING_PLATFORM_WIDTH / 2.0, MOVING_PLATFORM_HEIGHT / 2.0),
+                CollisionLayer::Terrain.groups(),
+                KinematicCharacterController {
+                    filter_flags: QueryFilterFlags::ONLY_KINEMATIC,
+                    ..default()
+                },
+                MovingPlatform {
+                    start_pos: entity_spawn.position,
+                    movement,
+                },
+            ))
+            .id(),
+    )
+}
 
-    let moving_platforms = match level_moving_platforms.get(&current_level.id) {
-        Some(items) => items,
-        None => return,
+fn get_movement(entity_spawn: &EntitySpawn) -> Option<MovingPlatformMovement> {
+    let direction = match entity_spawn.get_string("direction")? {
+        "left" => MovingPlatformDirection::Left,
+        "right" => MovingPlatformDirection::Right,
+        "up" => MovingPlatformDirection::Up,
+        "down" => MovingPlatformDirection::Down,
+        "clockwise" => MovingPlatformDirection::Clockwise,
+        "anticlockwise" => MovingPlatformDirection::Anticlockwise,
+        _ => return None,
     };
+    let speed = entity_spawn.get_float("speed").unwrap_or(1.0);
 
-    for moving_platform in moving_platforms {
-        commands.spawn((
-            Sprite {
-                image: texture.clone(),
-                ..default()
-            },
-            Transform {
-                scale: Vec3::splat(MOVING_PLATFORM_SCALE_FACTOR),
-                translation: moving_platform.start_pos.extend(8.0),
-                ..default()
-            },
-            RigidBody::KinematicPositionBased,
-            Collider::cuboid(MOVING_PLATFORM_WIDTH / 2.0, MOVING_PLATFORM_HEIGHT / 2.0),
-            KinematicCharacterController {
-                filter_flags: QueryFilterFlags::ONLY_KINEMATIC,
-                ..default()
-            },
-            moving_platform.clone(),
-        ));
+    match entity_spawn.get_string("movement")? {
+        "up_down" => Some(MovingPlatformMovement::UpDown(UpDownData {
+            direction,
+            max_down: entity_spawn
+                .tiled_to_bevy_coord(Vec2::new(0.0, entity_spawn.get_float("max_down")?))
+                .y,
+            max_up: entity_spawn
+                .tiled_to_bevy_coord(Vec2::new(0.0, entity_spawn.get_float("max_up")?))
+                .y,
+            speed,
+        })),
+        "left_right" => Some(MovingPlatformMovement::LeftRight(LeftRightData {
+            direction,
+            max_left: entity_spawn
+                .tiled_to_bevy_coord(Vec2::new(entity_spawn.get_float("max_left")?, 0.0))
+                .x,
+            max_right: entity_spawn
+                .tiled_to_bevy_coord(Vec2::new(entity_spawn.get_float("max_right")?, 0.0))
+                .x,
+            speed,
+        })),
+        "circle" => Some(MovingPlatformMovement::Circle(CircleData {
+            direction,
+            center: entity_spawn.get_point("center")?,
+            speed,
+        })),
+        _ => None,
     }
 }
 
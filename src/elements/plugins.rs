@@ -1,13 +1,18 @@
 use bevy::{app::PluginGroupBuilder, prelude::*};
 
-use crate::elements::{enigma, moving_platform, rock, story, volcano};
+use crate::elements::{
+    checkpoint, crumbling_platform, enigma, force_zone, moving_platform, rock, story, volcano,
+};
 
 pub struct ElementsPlugins;
 
 impl PluginGroup for ElementsPlugins {
     fn build(self) -> PluginGroupBuilder {
         PluginGroupBuilder::start::<Self>()
+            .add(checkpoint::CheckpointPlugin)
+            .add(crumbling_platform::CrumblingPlatformPlugin)
             .add(enigma::EnigmaPlugin)
+            .add(force_zone::ForceZonePlugin)
             .add(moving_platform::MovingPlatformPlugin)
             .add(rock::RockPlugin)
             .add(story::StoryPlugin::default())
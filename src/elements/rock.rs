@@ -10,12 +10,14 @@ use rand::{Rng, rng};
 
 use crate::{
     assets::RockRunAssets,
+    collision_layers::CollisionLayer,
     collisions::CollisionSet,
     coregame::{
-        level::{CurrentLevel, Level},
+        entities::{EntitySpawn, EntitySpawnerAppExt},
         state::AppState,
+        triggers::{SensorTrigger, TriggerHandlerAppExt, TriggerKind, TriggeredSensor},
     },
-    events::{PositionSensorCollisionStart, Restart, SmallRockAboutToRelease},
+    events::{Restart, SmallRockAboutToRelease},
 };
 
 use super::volcano::Lava;
@@ -29,6 +31,12 @@ pub struct Rock;
 #[derive(Component)]
 struct SmallRock;
 
+/// Where small rocks are regularly released, spawned from the Entities layer
+#[derive(Component)]
+struct SmallRockSource {
+    angular_damping: f32,
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, Reflect)]
 pub enum RockMovement {
     Run(RockDirection),
@@ -52,12 +60,19 @@ pub struct RockPlugin;
 
 impl Plugin for RockPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::StartMenu), despawn_rock)
-            .add_systems(OnEnter(AppState::FinishLevel), despawn_rock)
+        app.register_trigger_handler(TriggerKind::Rock, spawn_rock)
+            .register_entity_spawner("small_rock", spawn_small_rock_source)
+            .add_systems(
+                OnEnter(AppState::StartMenu),
+                (despawn_rock, despawn_small_rock_sources),
+            )
+            .add_systems(
+                OnEnter(AppState::FinishLevel),
+                (despawn_rock, despawn_small_rock_sources),
+            )
             .add_systems(
                 Update,
                 (
-                    spawn_rock,
                     spawn_small_rocks,
                     despawn_rock_on_restart,
                     despawn_smallrock,
@@ -88,63 +103,78 @@ fn get_collider_shapes(y_mirror: bool) -> Vec<(Vec2, f32, Collider)> {
 }
 
 fn spawn_rock(
+    In(triggered_sensor): In<TriggeredSensor>,
     mut commands: Commands,
     rock_run_assets: Res<RockRunAssets>,
-    mut rock_sensor_collision: EventReader<PositionSensorCollisionStart>,
-) {
-    for collision_event in rock_sensor_collision.read() {
-        if !collision_event.sensor_name.contains("rock") {
-            return;
-        }
+) -> Result<()> {
+    let SensorTrigger::Rock { spawn_pos } = triggered_sensor.trigger else {
+        return Ok(());
+    };
+    if !triggered_sensor.entered {
+        return Ok(());
+    }
 
-        let texture = rock_run_assets.rock_ball.clone();
+    let texture = rock_run_assets.rock_ball.clone();
 
+    commands
+        .spawn((
+            Sprite {
+                image: texture,
+                ..default()
+            },
+            Transform {
+                scale: Vec3::splat(ROCK_SCALE_FACTOR),
+                translation: spawn_pos.extend(20.0),
+                ..default()
+            },
+            RigidBody::Dynamic,
+            GravityScale(20.0),
+            Velocity::zero(),
+            Collider::compound(get_collider_shapes(false)),
+            CollisionLayer::Projectile.groups(),
+            ActiveCollisionTypes::DYNAMIC_KINEMATIC | ActiveCollisionTypes::DYNAMIC_DYNAMIC,
+            Ccd::enabled(),
+            Rock,
+        ))
+        .insert(ExternalImpulse {
+            impulse: Vec2::new(-4096.0 * 120.0, 0.0),
+            ..default()
+        });
+    Ok(())
+}
+
+/// Spawns a place releasing small rocks, spinning less with the `angular_damping` property
+fn spawn_small_rock_source(
+    In(entity_spawn): In<EntitySpawn>,
+    mut commands: Commands,
+) -> Option<Entity> {
+    info!("spawn_small_rock_source {}", entity_spawn.name);
+
+    Some(
         commands
             .spawn((
-                Sprite {
-                    image: texture,
-                    ..default()
+                Transform::from_translation(entity_spawn.position.extend(4.0)),
+                SmallRockSource {
+                    angular_damping: entity_spawn.get_float("angular_damping").unwrap_or(7.0),
                 },
-                Transform {
-                    scale: Vec3::splat(ROCK_SCALE_FACTOR),
-                    translation: collision_event.spawn_pos.extend(20.0),
-                    ..default()
-                },
-                RigidBody::Dynamic,
-                GravityScale(20.0),
-                Velocity::zero(),
-                Collider::compound(get_collider_shapes(false)),
-                ActiveCollisionTypes::DYNAMIC_KINEMATIC | ActiveCollisionTypes::DYNAMIC_DYNAMIC,
-                Ccd::enabled(),
-                Rock,
             ))
-            .insert(ExternalImpulse {
-                impulse: Vec2::new(-4096.0 * 120.0, 0.0),
-                ..default()
-            });
-    }
+            .id(),
+    )
 }
 
-#[allow(clippy::too_many_arguments)]
 fn spawn_small_rocks(
     mut commands: Commands,
     time: Res<Time>,
     rock_run_assets: Res<RockRunAssets>,
     mut spawn_timer: Local<Timer>,
-    levels: Query<&Level, With<Level>>,
-    current_level: Res<CurrentLevel>,
+    sources: Query<(&Transform, &SmallRockSource)>,
     mut small_rock_event: EventWriter<SmallRockAboutToRelease>,
     mut event_send: Local<bool>,
 ) {
-    if current_level.id != 2 {
+    if sources.is_empty() {
         return;
     }
 
-    let level = levels
-        .iter()
-        .find(|level| level.id == current_level.id)
-        .unwrap();
-
     spawn_timer.tick(time.delta());
     if spawn_timer.remaining() <= Duration::from_secs(1) && !*event_send {
         small_rock_event.write(SmallRockAboutToRelease);
@@ -157,59 +187,32 @@ fn spawn_small_rocks(
         let spawn_time: f32 = rng.random_range(1.0..=3.5);
         *spawn_timer = Timer::from_seconds(spawn_time, TimerMode::Once);
         let texture = rock_run_assets.small_rock.clone();
-        commands.spawn((
-            Sprite {
-                image: texture.clone(),
-                ..default()
-            },
-            Transform {
-                scale: Vec3::splat(1.0),
-                translation: level
-                    .map
-                    .tiled_to_bevy_coord(Vec2::new(1475.0, 750.0))
-                    .extend(4.0),
-                ..default()
-            },
-            RigidBody::Dynamic,
-            GravityScale(20.0),
-            Velocity::zero(),
-            Collider::ball(16.0),
-            ActiveCollisionTypes::DYNAMIC_KINEMATIC | ActiveCollisionTypes::DYNAMIC_DYNAMIC,
-            Ccd::enabled(),
-            Damping {
-                angular_damping: 7.0,
-                ..default()
-            },
-            Rock,
-            SmallRock,
-        ));
-
-        commands.spawn((
-            Sprite {
-                image: texture.clone(),
-                ..default()
-            },
-            Transform {
-                scale: Vec3::splat(1.0),
-                translation: level
-                    .map
-                    .tiled_to_bevy_coord(Vec2::new(5600.0, 750.0))
-                    .extend(4.0),
-                ..default()
-            },
-            RigidBody::Dynamic,
-            GravityScale(20.0),
-            Velocity::zero(),
-            Collider::ball(16.0),
-            ActiveCollisionTypes::DYNAMIC_KINEMATIC | ActiveCollisionTypes::DYNAMIC_DYNAMIC,
-            Ccd::enabled(),
-            Damping {
-                angular_damping: 7.5,
-                ..default()
-            },
-            Rock,
-            SmallRock,
-        ));
+        for (transform, source) in sources.iter() {
+            commands.spawn((
+                Sprite {
+                    image: texture.clone(),
+                    ..default()
+                },
+                Transform {
+                    scale: Vec3::splat(1.0),
+                    translation: transform.translation,
+                    ..default()
+                },
+                RigidBody::Dynamic,
+                GravityScale(20.0),
+                Velocity::zero(),
+                Collider::ball(16.0),
+                CollisionLayer::Projectile.groups(),
+                ActiveCollisionTypes::DYNAMIC_KINEMATIC | ActiveCollisionTypes::DYNAMIC_DYNAMIC,
+                Ccd::enabled(),
+                Damping {
+                    angular_damping: source.angular_damping,
+                    ..default()
+                },
+                Rock,
+                SmallRock,
+            ));
+        }
     }
 }
 
@@ -233,6 +236,15 @@ fn despawn_rock(mut commands: Commands, rocks: Query<Entity, With<Rock>>) {
     }
 }
 
+fn despawn_small_rock_sources(
+    mut commands: Commands,
+    sources: Query<Entity, With<SmallRockSource>>,
+) {
+    for source in sources.iter() {
+        commands.entity(source).despawn();
+    }
+}
+
 fn despawn_rock_on_restart(
     mut commands: Commands,
     rocks: Query<Entity, With<Rock>>,
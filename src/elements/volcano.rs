@@ -1,17 +1,18 @@
 use crate::{
     assets::RockRunAssets,
+    collision_layers::CollisionLayer,
     coregame::{
         colliders::ColliderName,
-        level::{CurrentLevel, Level},
+        entities::{EntitySpawn, EntitySpawnerAppExt},
         state::AppState,
+        triggers::{SensorTrigger, TriggerHandlerAppExt, TriggerKind, TriggeredSensor},
     },
-    events::{NextLevel, PositionSensorCollisionStart, Restart, ShakeCamera, StartGame},
+    events::{NextLevel, Restart, ShakeCamera, StartGame},
 };
 
 use bevy::{
     audio::PlaybackMode,
     color,
-    platform::collections::HashMap,
     prelude::*,
     render::render_resource::{AsBindGroup, ShaderRef},
     sprite::{Material2d, Material2dPlugin},
@@ -35,6 +36,14 @@ struct Volcano {
 #[derive(Component)]
 pub struct Lava;
 
+/// Fireballs thrown by the erupting volcano
+#[derive(Resource, Default)]
+struct Eruption {
+    fireballs: bool,
+    spawn_timer: Timer,
+    spawn_pos: Vec2,
+}
+
 #[derive(Component)]
 pub struct Fireball;
 
@@ -42,8 +51,10 @@ pub struct VolcanoPlugin;
 
 impl Plugin for VolcanoPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::GameCreate), (setup_volcano, setup_lava))
-            .add_systems(OnEnter(AppState::NextLevel), (setup_volcano, setup_lava))
+        app.register_entity_spawner("volcano", spawn_volcano)
+            .register_trigger_handler(TriggerKind::VolcanoEruption, erupt)
+            .register_trigger_handler(TriggerKind::VolcanoRumble, erupt)
+            .register_entity_spawner("lava", spawn_lava)
             .add_systems(
                 OnEnter(AppState::StartMenu),
                 (despawn_volcano, despawn_fireballs, despawn_lava),
@@ -56,134 +67,103 @@ impl Plugin for VolcanoPlugin {
                 Update,
                 (spawn_fireball, despawn_fireballs_offscreen)
                     .run_if(in_state(AppState::GameRunning)),
-            );
+            )
+            .insert_resource(Eruption::default());
         app.add_plugins(Material2dPlugin::<LavaMaterial>::default());
     }
 }
 
-fn setup_volcano(
+/// Spawns a volcano, scaled by the `scale` property and drawn at the `depth` property
+fn spawn_volcano(
+    In(entity_spawn): In<EntitySpawn>,
     mut commands: Commands,
     rock_run_assets: Res<RockRunAssets>,
-    levels: Query<&Level, With<Level>>,
-    current_level: Res<CurrentLevel>,
-) {
-    info!("setup_volcano");
+) -> Option<Entity> {
+    info!("spawn_volcano {}", entity_spawn.name);
 
-    let level = levels
-        .iter()
-        .find(|level| level.id == current_level.id)
-        .unwrap();
-
-    let texture = rock_run_assets.volcano.clone();
-    let mut level_volcano: HashMap<u8, Vec<Volcano>> = HashMap::new();
-    level_volcano.insert(
-        1,
-        vec![Volcano {
-            start_pos: level.map.tiled_to_bevy_coord(Vec2::new(10000.0, 250.0)),
-            scale_factor: 0.48,
-            depth: 2.0,
-        }],
-    );
+    let volcano = Volcano {
+        start_pos: entity_spawn.position,
+        scale_factor: entity_spawn.get_float("scale").unwrap_or(1.0),
+        depth: entity_spawn.get_float("depth").unwrap_or(2.0),
+    };
 
-    level_volcano.insert(
-        2,
-        vec![Volcano {
-            start_pos: level.map.tiled_to_bevy_coord(Vec2::new(2780.0, 1920.0)),
-            scale_factor: 0.30,
-            depth: 3.0,
-        }],
-    );
+    Some(
+        commands
+            .spawn((
+                Sprite {
+                    image: rock_run_assets.volcano.clone(),
+                    ..default()
+                },
+                Transform {
+                    scale: Vec3::splat(volcano.scale_factor),
+                    translation: volcano.start_pos.extend(volcano.depth),
+                    ..default()
+                },
+                volcano,
+            ))
+            .id(),
+    )
+}
 
-    let volcanos = match level_volcano.get(&current_level.id) {
-        Some(positions) => positions,
-        None => return,
-    };
+/// An eruption throws fireballs, a rumble only shakes the camera
+fn erupt(
+    In(triggered_sensor): In<TriggeredSensor>,
+    mut commands: Commands,
+    rock_run_assets: Res<RockRunAssets>,
+    mut eruption: ResMut<Eruption>,
+    mut shake_event: EventWriter<ShakeCamera>,
+) -> Result<()> {
+    if !triggered_sensor.entered {
+        return Ok(());
+    }
 
-    for volcano in volcanos {
-        commands.spawn((
-            Sprite {
-                image: texture.clone(),
-                ..default()
-            },
-            Transform {
-                scale: Vec3::splat(volcano.scale_factor),
-                translation: volcano.start_pos.extend(volcano.depth),
-                ..default()
-            },
-            Volcano {
-                start_pos: volcano.start_pos,
-                scale_factor: volcano.scale_factor,
-                depth: volcano.depth,
-            },
-        ));
+    if let SensorTrigger::VolcanoEruption { spawn_pos } = triggered_sensor.trigger {
+        eruption.fireballs = true;
+        eruption.spawn_pos = spawn_pos;
+        eruption.spawn_timer = Timer::from_seconds(0.1, TimerMode::Repeating);
     }
+
+    shake_event.write(ShakeCamera);
+    commands.spawn((
+        AudioPlayer::new(rock_run_assets.eruption_sound.clone()),
+        PlaybackSettings {
+            mode: PlaybackMode::Despawn,
+            ..default()
+        },
+    ));
+    Ok(())
 }
 
-#[allow(clippy::too_many_arguments)]
 fn spawn_fireball(
     mut commands: Commands,
     time: Res<Time>,
     rock_run_assets: Res<RockRunAssets>,
-    mut fireball_sensor_collision: EventReader<PositionSensorCollisionStart>,
-    mut fireballs: Local<bool>,
-    mut spawn_timer: Local<Timer>,
-    mut spawn_pos: Local<Vec2>,
+    mut eruption: ResMut<Eruption>,
     mut game_event: EventReader<StartGame>,
     mut restart_event: EventReader<Restart>,
     mut next_level_event: EventReader<NextLevel>,
-    mut shake_event: EventWriter<ShakeCamera>,
 ) {
     if !game_event.is_empty() {
-        *fireballs = false;
+        eruption.fireballs = false;
         game_event.clear();
         return;
     }
 
     if !restart_event.is_empty() {
-        *fireballs = false;
+        eruption.fireballs = false;
         restart_event.clear();
         return;
     }
 
     if !next_level_event.is_empty() {
-        *fireballs = false;
+        eruption.fireballs = false;
         next_level_event.clear();
         return;
     }
 
-    spawn_timer.tick(time.delta());
-
-    for collision_event in fireball_sensor_collision.read() {
-        if !collision_event.sensor_name.contains("volcano") {
-            return;
-        }
+    eruption.spawn_timer.tick(time.delta());
 
-        if collision_event.sensor_name.contains("volcano01_02") {
-            shake_event.write(ShakeCamera);
-            commands.spawn((
-                AudioPlayer::new(rock_run_assets.eruption_sound.clone()),
-                PlaybackSettings {
-                    mode: PlaybackMode::Despawn,
-                    ..default()
-                },
-            ));
-            return;
-        }
-
-        *fireballs = true;
-        *spawn_pos = collision_event.spawn_pos;
-        *spawn_timer = Timer::from_seconds(0.1, TimerMode::Repeating);
-        shake_event.write(ShakeCamera);
-        commands.spawn((
-            AudioPlayer::new(rock_run_assets.eruption_sound.clone()),
-            PlaybackSettings {
-                mode: PlaybackMode::Despawn,
-                ..default()
-            },
-        ));
-    }
-
-    if *fireballs && spawn_timer.finished() {
+    if eruption.fireballs && eruption.spawn_timer.finished() {
         let mut rng = rng();
         let impulse_x: f32 = rng.random_range(-15.0..=15.0);
         let impulse_y: f32 = rng.random_range(3.0..=4.0);
@@ -196,11 +176,12 @@ fn spawn_fireball(
             },
             Transform {
                 scale: Vec3::splat(FIREBALL_SCALE_FACTOR),
-                translation: spawn_pos.extend(20.0),
+                translation: eruption.spawn_pos.extend(20.0),
                 ..default()
             },
             RigidBody::Dynamic,
             Collider::ball(16.0),
+            CollisionLayer::Projectile.groups(),
             ExternalImpulse {
                 impulse: Vec2::new(50000.0 * impulse_x, 100000.0 * impulse_y),
                 torque_impulse: 1000000.0 * torque_impulse,
@@ -214,45 +195,38 @@ fn spawn_fireball(
     }
 }
 
-fn setup_lava(
+/// Spawns a lava pool covering its rectangle object, drawn at the `depth` property
+fn spawn_lava(
+    In(entity_spawn): In<EntitySpawn>,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut lava: ResMut<Assets<LavaMaterial>>,
-    levels: Query<&Level, With<Level>>,
-    current_level: Res<CurrentLevel>,
-) {
-    info!("setup_lava");
-
-    let level = levels
-        .iter()
-        .find(|level| level.id == current_level.id)
-        .unwrap();
+) -> Option<Entity> {
+    info!("spawn_lava {}", entity_spawn.name);
 
-    let mut level_lava_pos: HashMap<u8, Vec<Vec2>> = HashMap::new();
-    level_lava_pos.insert(
-        2,
-        vec![level.map.tiled_to_bevy_coord(Vec2::new(3520.0, 2128.0))],
-    );
-
-    let start_positions = match level_lava_pos.get(&current_level.id) {
-        Some(positions) => positions,
-        None => return,
+    let Some(size) = entity_spawn.size else {
+        warn!("Lava {}: not a rectangle", entity_spawn.name);
+        return None;
     };
 
-    for start_pos in start_positions {
-        commands.spawn((
-            Mesh2d(meshes.add(Rectangle::default())),
-            MeshMaterial2d(lava.add(LavaMaterial {
-                color: LinearRgba::from(color::palettes::css::GOLD),
-            })),
-            Transform {
-                translation: start_pos.extend(5.0),
-                scale: Vec3::new(2000.0, 66.0, 1.0),
-                ..default()
-            },
-            Lava,
-        ));
-    }
+    Some(
+        commands
+            .spawn((
+                Mesh2d(meshes.add(Rectangle::default())),
+                MeshMaterial2d(lava.add(LavaMaterial {
+                    color: LinearRgba::from(color::palettes::css::GOLD),
+                })),
+                Transform {
+                    translation: entity_spawn
+                        .position
+                        .extend(entity_spawn.get_float("depth").unwrap_or(5.0)),
+                    scale: size.extend(1.0),
+                    ..default()
+                },
+                Lava,
+            ))
+            .id(),
+    )
 }
 
 // This is the struct that will be passed to your shader
@@ -32,6 +32,20 @@ pub struct NextLevel;
 #[derive(Event)]
 pub struct ShakeCamera;
 
+/// The map of the current level was modified on disk and reloaded
+#[derive(Event)]
+pub struct LevelReloaded;
+
+/// Spawns a deferred object of the Entities layer of the current level
+#[derive(Event)]
+pub struct SpawnLevelEntity {
+    pub name: String,
+}
+
+/// Unlocks the locked screen the player is in, sent when the last chasing beast is gone
+#[derive(Event)]
+pub struct UnlockScreen;
+
 #[derive(Event)]
 pub enum LifeEvent {
     Win,
@@ -43,24 +57,24 @@ pub struct TriceratopsCollision {
     pub id: Entity,
 }
 
-#[derive(Event, Debug)]
-pub struct PositionSensorCollisionStart {
-    pub sensor_name: String,
-    pub spawn_pos: Vec2,
-    pub exit_pos: Vec2,
-}
-
-#[derive(Event, Debug)]
-pub struct PositionSensorCollisionStop {
-    pub sensor_name: String,
-}
-
 #[derive(Event)]
 pub struct LadderCollisionStart;
 
 #[derive(Event)]
 pub struct LadderCollisionStop;
 
+#[derive(Event)]
+pub struct WaterCollisionStart {
+    pub water: Entity,
+    /// Seconds the player can stay in the water, unlimited if `None`
+    pub breath: Option<f32>,
+}
+
+#[derive(Event)]
+pub struct WaterCollisionStop {
+    pub water: Entity,
+}
+
 #[derive(Event)]
 pub struct MovingPlatformCollision {
     pub entity: Entity,
@@ -71,6 +85,16 @@ pub struct MovingPlatformDescending {
     pub movement: Vec2,
 }
 
+#[derive(Event)]
+pub struct CrumblingPlatformCollision {
+    pub entity: Entity,
+}
+
+#[derive(Event)]
+pub struct BouncePadCollision {
+    pub strength: f32,
+}
+
 #[derive(Event)]
 pub struct SelectionChanged {
     pub movement: SelectionDirection,
@@ -0,0 +1,142 @@
+// Loads the `levels.ron` manifest describing the levels of the game.
+//
+// Levels are numbered from 1 in the order of the manifest. The TMX maps and musics referenced by
+// the manifest are loaded as dependencies, so they are ready once the manifest is loaded.
+//
+// Example:
+//
+// (
+//     levels: [
+//         (
+//             map: "level01.tmx",
+//             music: "musics/theme_01.ogg",
+//         ),
+//         (
+//             map: "level02.tmx",
+//             // For TMX maps without a `screen_layout` property, it must be the same otherwise
+//             screen_layout: Some("SOO\nXXO"),
+//             music: "musics/theme_07.ogg",
+//             shaders: [
+//                 (
+//                     kind: MysteriousFog,
+//                     color: "FFD700",
+//                     area: (x: 3024.0, y: 720.0, width: 3376.0, height: 720.0),
+//                 ),
+//             ],
+//             // Fluent message id, `current_level` by default
+//             display_name: Some("level_cave"),
+//         ),
+//     ],
+// )
+
+use bevy::asset::AssetLoader;
+use bevy::asset::io::Reader;
+use bevy::{log, prelude::*};
+use screen_map::level_manifest::LevelManifestDescription;
+use thiserror::Error;
+
+use crate::helpers::tiled::TiledMap;
+
+pub use screen_map::level_manifest::{LevelShader, LevelShaderKind};
+
+#[derive(Default)]
+pub struct LevelManifestPlugin;
+
+impl Plugin for LevelManifestPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<LevelManifest>()
+            .register_asset_loader(LevelManifestLoader);
+    }
+}
+
+/// A level of the manifest, with its assets
+#[derive(Debug, Clone)]
+pub struct LevelInfo {
+    pub map: Handle<TiledMap>,
+    /// Screen layout of maps without the `screen_layout` property, the same as the property otherwise
+    pub screen_layout: Option<String>,
+    pub music: Handle<AudioSource>,
+    pub shaders: Vec<LevelShader>,
+    /// Fluent message id of the level name
+    pub display_name: Option<String>,
+}
+
+#[derive(TypePath, Asset, Debug)]
+pub struct LevelManifest {
+    pub levels: Vec<LevelInfo>,
+}
+
+impl LevelManifest {
+    /// Returns the levels with their id, starting from 1
+    pub fn iter(&self) -> impl Iterator<Item = (u8, &LevelInfo)> {
+        (1..=u8::MAX).zip(self.levels.iter())
+    }
+
+    /// Returns the id of the last level
+    pub fn last_level_id(&self) -> u8 {
+        self.levels.len() as u8
+    }
+}
+
+pub struct LevelManifestLoader;
+
+#[derive(Debug, Error)]
+pub enum LevelManifestLoaderError {
+    /// An [IO](std::io) Error
+    #[error("Could not load level manifest: {0}")]
+    Io(#[from] std::io::Error),
+    /// A [RON](ron) Error
+    #[error("Could not parse level manifest: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("Level manifest has no level")]
+    NoLevel,
+    #[error("Level manifest has more than 255 levels")]
+    TooManyLevels,
+}
+
+impl AssetLoader for LevelManifestLoader {
+    type Asset = LevelManifest;
+    type Settings = ();
+    type Error = LevelManifestLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut bevy::asset::LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let description: LevelManifestDescription = ron::de::from_bytes(&bytes)?;
+        match description.levels.len() {
+            0 => return Err(LevelManifestLoaderError::NoLevel),
+            len if len > u8::MAX as usize => return Err(LevelManifestLoaderError::TooManyLevels),
+            _ => {}
+        }
+
+        let levels = description
+            .levels
+            .into_iter()
+            .map(|level| LevelInfo {
+                map: load_context.load(level.map),
+                screen_layout: level.screen_layout,
+                music: load_context.load(level.music),
+                shaders: level.shaders,
+                display_name: level.display_name,
+            })
+            .collect::<Vec<_>>();
+
+        log::info!(
+            "Loaded level manifest {} with {} levels",
+            load_context.path().display(),
+            levels.len()
+        );
+        Ok(LevelManifest { levels })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        static EXTENSIONS: &[&str] = &["ron"];
+        EXTENSIONS
+    }
+}
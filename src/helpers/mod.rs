@@ -1,3 +1,4 @@
 pub mod camera;
+pub mod level_manifest;
 pub mod texture;
 pub mod tiled;
@@ -10,15 +10,28 @@
 //
 // Functional limitations:
 //   * When the 'atlas' feature is enabled tilesets using a collection of images will be skipped.
-//   * Only finite tile layers are loaded. Infinite tile layers and object layers will be skipped.
+//   * Object layers will be skipped.
+//   * The layers of infinite maps cover the extent of their chunks, which can start before the
+//     top-left corner of the map. The tiles of their chunks are only spawned in the
+//     `ChunkLoadingArea` of the map entity.
+//   * Tile animations are played by bevy_ecs_tilemap with the same duration for every frame (the
+//     average of the Tiled durations). In single image tilesets the frames must be consecutive
+//     tiles of the tileset.
+//   * Layer parallax follows the top-left corner of the view: layers are at their Tiled position
+//     when it is at the top-left corner of the map (the default parallax origin of Tiled). Hidden
+//     layers are not loaded.
+//   * External tilesets (.tsx) and object templates (.tx) are read on demand: the map is parsed
+//     again each time the tiled loader asks for a file not read yet, so a map with many external
+//     files is parsed once per file.
 
 use std::io::Cursor;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
 
 use bevy::asset::io::Reader;
-use bevy::asset::{AssetLoader, AssetPath};
-use bevy::platform::collections::HashMap;
+use bevy::asset::{AssetLoader, AssetPath, ReadAssetBytesError};
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::transform::TransformSystem;
 use bevy::{log, prelude::*};
 use bevy_ecs_tilemap::prelude::*;
 
@@ -31,10 +44,25 @@ impl Plugin for TiledMapPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.init_asset::<TiledMap>()
             .register_asset_loader(TiledLoader)
-            .add_systems(Update, process_loaded_maps);
+            .add_systems(Update, (process_loaded_maps, update_chunks).chain())
+            .add_systems(
+                PostUpdate,
+                update_layer_parallax.before(TransformSystem::TransformPropagate),
+            );
     }
 }
 
+/// Scrolling of a tile layer relative to the camera, from the Tiled layer parallax factor
+#[derive(Component, Debug)]
+pub struct LayerParallax {
+    /// 1.0 scrolls with the map, 0.0 stays fixed on the screen
+    pub factor: Vec2,
+    /// Layer translation when the top-left corner of the view is at the parallax origin
+    pub position: Vec2,
+    /// Parallax origin in Bevy coordinates
+    pub origin: Vec2,
+}
+
 #[derive(TypePath, Asset)]
 pub struct TiledMap {
     pub map: tiled::Map,
@@ -44,6 +72,61 @@ pub struct TiledMap {
     // The offset into the tileset_images for each tile id within each tileset.
     #[cfg(not(feature = "atlas"))]
     pub tile_image_offsets: HashMap<(usize, tiled::TileId), u32>,
+
+    // The animation of each animated tile id within each tileset.
+    pub tile_animations: HashMap<(usize, tiled::TileId), AnimatedTile>,
+}
+
+impl TiledMap {
+    /// Returns the value of a string custom property defined on the map
+    pub fn get_string_property(&self, name: &str) -> Option<&str> {
+        match self.map.properties.get(name) {
+            Some(tiled::PropertyValue::StringValue(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the map size in pixels
+    pub fn get_pixel_size(&self) -> (usize, usize) {
+        (
+            (self.map.width * self.map.tile_width) as usize,
+            (self.map.height * self.map.tile_height) as usize,
+        )
+    }
+}
+
+/// Returns the value of a numeric custom property, integers are converted to `f32`
+pub fn get_float_property(properties: &tiled::Properties, name: &str) -> Option<f32> {
+    match properties.get(name)? {
+        tiled::PropertyValue::FloatValue(value) => Some(*value),
+        tiled::PropertyValue::IntValue(value) => Some(*value as f32),
+        value => {
+            log::warn!("Property {} is not a number: {:?}", name, value);
+            None
+        }
+    }
+}
+
+/// Returns the value of a boolean custom property
+pub fn get_bool_property(properties: &tiled::Properties, name: &str) -> Option<bool> {
+    match properties.get(name)? {
+        tiled::PropertyValue::BoolValue(value) => Some(*value),
+        value => {
+            log::warn!("Property {} is not a boolean: {:?}", name, value);
+            None
+        }
+    }
+}
+
+/// Returns the value of a string custom property
+pub fn get_string_property<'a>(properties: &'a tiled::Properties, name: &str) -> Option<&'a str> {
+    match properties.get(name)? {
+        tiled::PropertyValue::StringValue(value) => Some(value),
+        value => {
+            log::warn!("Property {} is not a string: {:?}", name, value);
+            None
+        }
+    }
 }
 
 // Stores a list of tiled layers.
@@ -55,6 +138,26 @@ pub struct TilesetLayerToStorageEntity {
 #[derive(Component, Default)]
 pub struct TiledMapHandle(pub Handle<TiledMap>);
 
+/// Area, in Bevy coordinates, whose chunks are spawned for the infinite layers of the map
+///
+/// `None` despawns all the chunks.
+#[derive(Component, Default, Debug)]
+pub struct ChunkLoadingArea(pub Option<Rect>);
+
+/// A layer of an infinite map, its tiles are spawned by chunks
+#[derive(Component, Debug)]
+struct InfiniteLayer {
+    map_entity: Entity,
+    tileset_index: usize,
+    layer_index: usize,
+    tile_color: TileColor,
+    /// Tiled coordinates of the top-left tile of the layer
+    tiles_origin: IVec2,
+    /// Size of the layer in tiles
+    size: TilemapSize,
+    loaded_chunks: HashSet<(i32, i32)>,
+}
+
 impl TilesetLayerToStorageEntity {
     pub fn get_entities(&self) -> Vec<&Entity> {
         self.storage
@@ -68,19 +171,30 @@ impl TilesetLayerToStorageEntity {
 pub struct TiledMapBundle {
     pub tiled_map: TiledMapHandle,
     pub storage: TilesetLayerToStorageEntity,
+    pub chunk_loading_area: ChunkLoadingArea,
     pub transform: Transform,
     pub global_transform: GlobalTransform,
     pub render_settings: TilemapRenderSettings,
 }
 
+// The tiled crate reads files synchronously, the map and the files it references are read through
+// the asset server beforehand. The referenced files are found by the tiled crate itself: a file
+// it asks for and that was not read yet is recorded as missing, read, and the map loaded again.
 struct BytesResourceReader {
+    map_path: PathBuf,
     bytes: Arc<[u8]>,
+    // External tilesets and templates, by path relative to the assets directory.
+    dependencies: HashMap<PathBuf, Arc<[u8]>>,
+    missing: Option<PathBuf>,
 }
 
 impl BytesResourceReader {
-    fn new(bytes: &[u8]) -> Self {
+    fn new(map_path: &Path, bytes: &[u8], dependencies: HashMap<PathBuf, Arc<[u8]>>) -> Self {
         Self {
+            map_path: normalize_path(map_path),
             bytes: Arc::from(bytes),
+            dependencies,
+            missing: None,
         }
     }
 }
@@ -89,12 +203,41 @@ impl tiled::ResourceReader for BytesResourceReader {
     type Resource = Cursor<Arc<[u8]>>;
     type Error = std::io::Error;
 
-    fn read_from(&mut self, _path: &Path) -> std::result::Result<Self::Resource, Self::Error> {
-        // In this case, the path is ignored because the byte data is already provided.
-        Ok(Cursor::new(self.bytes.clone()))
+    fn read_from(&mut self, path: &Path) -> std::result::Result<Self::Resource, Self::Error> {
+        let path = normalize_path(path);
+        if path == self.map_path {
+            return Ok(Cursor::new(self.bytes.clone()));
+        }
+
+        match self.dependencies.get(&path) {
+            Some(bytes) => Ok(Cursor::new(bytes.clone())),
+            None => {
+                let error = std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("{} is not read yet", path.display()),
+                );
+                self.missing = Some(path);
+                Err(error)
+            }
+        }
     }
 }
 
+/// Resolves the `.` and `..` components of a path relative to the assets directory
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
 pub struct TiledLoader;
 
 #[derive(Debug, Error)]
@@ -102,6 +245,12 @@ pub enum TiledAssetLoaderError {
     /// An [IO](std::io) Error
     #[error("Could not load Tiled file: {0}")]
     Io(#[from] std::io::Error),
+    #[error("Could not read {path}: {error}")]
+    Dependency {
+        path: String,
+        #[source]
+        error: ReadAssetBytesError,
+    },
 }
 
 impl AssetLoader for TiledLoader {
@@ -118,17 +267,46 @@ impl AssetLoader for TiledLoader {
         let mut bytes = Vec::new();
         reader.read_to_end(&mut bytes).await?;
 
-        let mut loader = tiled::Loader::with_cache_and_reader(
-            tiled::DefaultResourceCache::new(),
-            BytesResourceReader::new(&bytes),
-        );
-        let map = loader
-            .load_tmx_map(load_context.path())
-            .map_err(|e| std::io::Error::other(format!("Could not load TMX map: {e}")))?;
+        // Reading the referenced files through the load context makes them dependencies of the
+        // map: the map is reloaded when one of them is modified.
+        let map_path = load_context.path().to_path_buf();
+        let mut dependencies = HashMap::default();
+        let map = loop {
+            let mut loader = tiled::Loader::with_cache_and_reader(
+                tiled::DefaultResourceCache::new(),
+                BytesResourceReader::new(&map_path, &bytes, dependencies),
+            );
+            let result = loader.load_tmx_map(&map_path);
+            let (_, resource_reader) = loader.into_inner();
+            dependencies = resource_reader.dependencies;
+
+            // The load stops at the first file not read yet: read it and load the map again.
+            let path = match (result, resource_reader.missing) {
+                (Ok(map), _) => break map,
+                (Err(_), Some(path)) if !dependencies.contains_key(&path) => path,
+                (Err(error), _) => {
+                    return Err(
+                        std::io::Error::other(format!("Could not load TMX map: {error}")).into(),
+                    );
+                }
+            };
+
+            let dependency_bytes =
+                load_context
+                    .read_asset_bytes(path.clone())
+                    .await
+                    .map_err(|error| TiledAssetLoaderError::Dependency {
+                        path: path.display().to_string(),
+                        error,
+                    })?;
+            log::info!("Loaded Tiled dependency: {}", path.display());
+            dependencies.insert(path, Arc::from(dependency_bytes));
+        };
 
         let mut tilemap_textures = HashMap::default();
         #[cfg(not(feature = "atlas"))]
         let mut tile_image_offsets = HashMap::default();
+        let mut tile_animations = HashMap::default();
 
         for (tileset_index, tileset) in map.tilesets().iter().enumerate() {
             let tilemap_texture = match &tileset.image {
@@ -147,13 +325,9 @@ impl AssetLoader for TiledLoader {
                         let mut tile_images: Vec<Handle<Image>> = Vec::new();
                         for (tile_id, tile) in tileset.tiles() {
                             if let Some(img) = &tile.image {
-                                // The load context path is the TMX file itself. If the file is at the root of the
-                                // assets/ directory structure then the tmx_dir will be empty, which is fine.
-                                let tmx_dir = load_context
-                                    .path()
-                                    .parent()
-                                    .expect("The asset load context was empty.");
-                                let tile_path = tmx_dir.join(&img.source);
+                                // The tiled crate resolves the image source relative to the file
+                                // declaring the tileset (TMX or TSX), that is relative to assets/.
+                                let tile_path = normalize_path(&img.source);
                                 let asset_path = AssetPath::from(tile_path);
                                 log::info!(
                                     "Loading tile image from {asset_path:?} as image ({tileset_index}, {tile_id})"
@@ -165,20 +339,74 @@ impl AssetLoader for TiledLoader {
                             }
                         }
 
+                        // bevy_ecs_tilemap animates consecutive textures, the frames of each
+                        // animation are appended to the images in their playing order.
+                        for (tile_id, tile) in tileset.tiles() {
+                            let Some(frames) = &tile.animation else {
+                                continue;
+                            };
+                            let start = tile_images.len() as u32;
+                            for frame in frames {
+                                let Some(offset) =
+                                    tile_image_offsets.get(&(tileset_index, frame.tile_id))
+                                else {
+                                    log::warn!(
+                                        "Skipping animation of tile ({tileset_index}, {tile_id}): frame {} has no image",
+                                        frame.tile_id
+                                    );
+                                    tile_images.truncate(start as usize);
+                                    break;
+                                };
+                                tile_images.push(tile_images[*offset as usize].clone());
+                            }
+                            if tile_images.len() as u32 > start {
+                                tile_animations.insert(
+                                    (tileset_index, tile_id),
+                                    AnimatedTile {
+                                        start,
+                                        end: tile_images.len() as u32,
+                                        speed: get_animation_speed(frames),
+                                    },
+                                );
+                            }
+                        }
+
                         TilemapTexture::Vector(tile_images)
                     }
                 }
                 Some(img) => {
-                    // The load context path is the TMX file itself. If the file is at the root of the
-                    // assets/ directory structure then the tmx_dir will be empty, which is fine.
-                    let tmx_dir = load_context
-                        .path()
-                        .parent()
-                        .expect("The asset load context was empty.");
-                    let tile_path = tmx_dir.join(&img.source);
+                    // The tiled crate resolves the image source relative to the file declaring the
+                    // tileset (TMX or TSX), that is relative to assets/.
+                    let tile_path = normalize_path(&img.source);
                     let asset_path = AssetPath::from(tile_path);
                     let texture: Handle<Image> = load_context.load(asset_path.clone());
 
+                    // The texture index of a tile is its id, the frames must be consecutive tiles.
+                    for (tile_id, tile) in tileset.tiles() {
+                        let Some(frames) = &tile.animation else {
+                            continue;
+                        };
+                        let Some(first_frame) = frames.first() else {
+                            continue;
+                        };
+                        if frames.iter().enumerate().any(|(index, frame)| {
+                            frame.tile_id != first_frame.tile_id + index as u32
+                        }) {
+                            log::warn!(
+                                "Skipping animation of tile ({tileset_index}, {tile_id}): frames are not consecutive tiles"
+                            );
+                            continue;
+                        }
+                        tile_animations.insert(
+                            (tileset_index, tile_id),
+                            AnimatedTile {
+                                start: first_frame.tile_id,
+                                end: first_frame.tile_id + frames.len() as u32,
+                                speed: get_animation_speed(frames),
+                            },
+                        );
+                    }
+
                     TilemapTexture::Single(texture.clone())
                 }
             };
@@ -191,6 +419,7 @@ impl AssetLoader for TiledLoader {
             tilemap_textures,
             #[cfg(not(feature = "atlas"))]
             tile_image_offsets,
+            tile_animations,
         };
 
         log::info!("Loaded map: {}", load_context.path().display());
@@ -203,12 +432,22 @@ impl AssetLoader for TiledLoader {
     }
 }
 
+/// Returns the frames per second of a Tiled animation
+fn get_animation_speed(frames: &[tiled::Frame]) -> f32 {
+    let duration: u32 = frames.iter().map(|frame| frame.duration).sum();
+    if duration == 0 {
+        return 0.0;
+    }
+    frames.len() as f32 * 1000.0 / duration as f32
+}
+
 pub fn process_loaded_maps(
     mut commands: Commands,
     mut map_events: EventReader<AssetEvent<TiledMap>>,
     maps: Res<Assets<TiledMap>>,
     tile_storage_query: Query<(Entity, &TileStorage)>,
     mut map_query: Query<(
+        Entity,
         &TiledMapHandle,
         &mut TilesetLayerToStorageEntity,
         &TilemapRenderSettings,
@@ -242,7 +481,9 @@ pub fn process_loaded_maps(
     }
 
     for changed_map in changed_maps.iter() {
-        for (map_handle, mut tileset_layer_entity, render_settings) in map_query.iter_mut() {
+        for (map_entity, map_handle, mut tileset_layer_entity, render_settings) in
+            map_query.iter_mut()
+        {
             // only deal with currently changed map
             if map_handle.0.id() != *changed_map {
                 continue;
@@ -255,6 +496,8 @@ pub fn process_loaded_maps(
                             commands.entity(*tile).despawn()
                         }
                     }
+                    // The chunks of the rebuilt layer are loaded in its new entity
+                    commands.entity(*entity).remove::<InfiniteLayer>();
                     // commands.entity(*layer_entity).despawn();
                 }
 
@@ -286,17 +529,28 @@ pub fn process_loaded_maps(
                         let offset_x = layer.offset_x;
                         let offset_y = layer.offset_y;
 
-                        let tiled::LayerType::Tiles(tile_layer) = layer.layer_type() else {
-                            log::debug!(
-                                "Skipping layer {} because only tile layers are supported.",
-                                layer.id()
-                            );
+                        if !layer.visible {
+                            log::debug!("Skipping layer {} because it is hidden.", layer.id());
                             continue;
-                        };
+                        }
 
-                        let tiled::TileLayer::Finite(layer_data) = tile_layer else {
+                        // The tint colour and the opacity are applied to every tile of the layer
+                        let tint = layer.tint_color.unwrap_or(tiled::Color {
+                            red: 0xFF,
+                            green: 0xFF,
+                            blue: 0xFF,
+                            alpha: 0xFF,
+                        });
+                        let tile_color = TileColor(Color::srgba_u8(
+                            tint.red,
+                            tint.green,
+                            tint.blue,
+                            (tint.alpha as f32 * layer.opacity) as u8,
+                        ));
+
+                        let tiled::LayerType::Tiles(tile_layer) = layer.layer_type() else {
                             log::debug!(
-                                "Skipping layer {} because only finite layers are supported.",
+                                "Skipping layer {} because only tile layers are supported.",
                                 layer.id()
                             );
                             continue;
@@ -325,77 +579,97 @@ pub fn process_loaded_maps(
                             tiled::Orientation::Orthogonal => TilemapType::Square,
                         };
 
-                        let mut tile_storage = TileStorage::empty(map_size);
-                        let layer_entity = commands.spawn_empty().id();
+                        // Infinite layers cover the extent of their chunks
+                        let (tiles_origin, layer_size) = match &tile_layer {
+                            tiled::TileLayer::Finite(_) => (IVec2::ZERO, map_size),
+                            tiled::TileLayer::Infinite(layer_data) => get_chunk_bounds(layer_data),
+                        };
+                        let layer_position = Vec2::new(offset_x, -offset_y)
+                            + Vec2::new(
+                                (tiles_origin.x as f32
+                                    + (layer_size.x as f32 - map_size.x as f32) / 2.0)
+                                    * grid_size.x,
+                                -(tiles_origin.y as f32
+                                    + (layer_size.y as f32 - map_size.y as f32) / 2.0)
+                                    * grid_size.y,
+                            );
 
-                        for x in 0..map_size.x {
-                            for y in 0..map_size.y {
-                                // Transform TMX coords into bevy coords.
-                                let mapped_y = tiled_map.map.height - 1 - y;
+                        let mut tile_storage = TileStorage::empty(layer_size);
+                        let layer_entity = commands.spawn_empty().id();
 
-                                let mapped_x = x as i32;
-                                let mapped_y = mapped_y as i32;
+                        match tile_layer {
+                            tiled::TileLayer::Finite(layer_data) => {
+                                for x in 0..map_size.x {
+                                    for y in 0..map_size.y {
+                                        // Transform TMX coords into bevy coords.
+                                        let mapped_y = tiled_map.map.height - 1 - y;
 
-                                let layer_tile = match layer_data.get_tile(mapped_x, mapped_y) {
-                                    Some(t) => t,
-                                    None => {
-                                        continue;
-                                    }
-                                };
-                                if tileset_index != layer_tile.tileset_index() {
-                                    continue;
-                                }
-                                let layer_tile_data =
-                                    match layer_data.get_tile_data(mapped_x, mapped_y) {
-                                        Some(d) => d,
-                                        None => {
+                                        let Some(layer_tile_data) =
+                                            layer_data.get_tile_data(x as i32, mapped_y as i32)
+                                        else {
+                                            continue;
+                                        };
+                                        if tileset_index != layer_tile_data.tileset_index() {
                                             continue;
                                         }
-                                    };
-
-                                let texture_index = match tilemap_texture {
-                                    TilemapTexture::Single(_) => layer_tile.id(),
-                                    #[cfg(not(feature = "atlas"))]
-                                    TilemapTexture::Vector(_) =>
-                                        *tiled_map.tile_image_offsets.get(&(tileset_index, layer_tile.id()))
-                                        .expect("The offset into to image vector should have been saved during the initial load."),
-                                    #[cfg(not(feature = "atlas"))]
-                                    _ => unreachable!()
-                                };
 
-                                let tile_pos = TilePos { x, y };
-                                let tile_entity = commands
-                                    .spawn(TileBundle {
-                                        position: tile_pos,
-                                        tilemap_id: TilemapId(layer_entity),
-                                        texture_index: TileTextureIndex(texture_index),
-                                        flip: TileFlip {
-                                            x: layer_tile_data.flip_h,
-                                            y: layer_tile_data.flip_v,
-                                            d: layer_tile_data.flip_d,
-                                        },
-                                        visible: TileVisible(false),
-                                        ..Default::default()
-                                    })
-                                    .id();
-                                tile_storage.set(&tile_pos, tile_entity);
+                                        let tile_pos = TilePos { x, y };
+                                        let tile_entity = spawn_tile(
+                                            &mut commands,
+                                            tiled_map,
+                                            layer_entity,
+                                            tileset_index,
+                                            tile_pos,
+                                            layer_tile_data,
+                                            tile_color,
+                                            false,
+                                        );
+                                        tile_storage.set(&tile_pos, tile_entity);
+                                    }
+                                }
+                            }
+                            tiled::TileLayer::Infinite(_) => {
+                                // The tiles are spawned around the camera by update_chunks
+                                commands.entity(layer_entity).insert(InfiniteLayer {
+                                    map_entity,
+                                    tileset_index,
+                                    layer_index,
+                                    tile_color,
+                                    tiles_origin,
+                                    size: layer_size,
+                                    loaded_chunks: HashSet::new(),
+                                });
                             }
                         }
 
                         commands.entity(layer_entity).insert(TilemapBundle {
                             grid_size,
-                            size: map_size,
+                            size: layer_size,
                             storage: tile_storage,
                             texture: tilemap_texture.clone(),
                             tile_size,
                             spacing: tile_spacing,
                             anchor: TilemapAnchor::Center,
-                            transform: Transform::from_xyz(offset_x, -offset_y, layer_index as f32),
+                            transform: Transform::from_translation(
+                                layer_position.extend(layer_index as f32),
+                            ),
                             map_type,
                             render_settings: *render_settings,
                             ..Default::default()
                         });
 
+                        let parallax_factor = Vec2::new(layer.parallax_x, layer.parallax_y);
+                        if parallax_factor != Vec2::ONE {
+                            commands.entity(layer_entity).insert(LayerParallax {
+                                factor: parallax_factor,
+                                position: layer_position,
+                                origin: Vec2::new(
+                                    -(map_size.x as f32 * grid_size.x) / 2.0,
+                                    map_size.y as f32 * grid_size.y / 2.0,
+                                ),
+                            });
+                        }
+
                         layers_map.insert(layer_index as u32, layer_entity);
                     }
                     tileset_layer_entity
@@ -406,3 +680,200 @@ pub fn process_loaded_maps(
         }
     }
 }
+
+/// Spawns a tile of a layer, the tile must come from the tileset of the layer
+#[allow(clippy::too_many_arguments)]
+fn spawn_tile(
+    commands: &mut Commands,
+    tiled_map: &TiledMap,
+    layer_entity: Entity,
+    tileset_index: usize,
+    tile_pos: TilePos,
+    layer_tile_data: &tiled::LayerTileData,
+    color: TileColor,
+    visible: bool,
+) -> Entity {
+    let texture_index = match tiled_map.tilemap_textures.get(&tileset_index) {
+        #[cfg(not(feature = "atlas"))]
+        Some(TilemapTexture::Vector(_)) => *tiled_map
+            .tile_image_offsets
+            .get(&(tileset_index, layer_tile_data.id()))
+            .expect(
+                "The offset into to image vector should have been saved during the initial load.",
+            ),
+        _ => layer_tile_data.id(),
+    };
+
+    let mut tile_commands = commands.spawn(TileBundle {
+        position: tile_pos,
+        tilemap_id: TilemapId(layer_entity),
+        texture_index: TileTextureIndex(texture_index),
+        flip: TileFlip {
+            x: layer_tile_data.flip_h,
+            y: layer_tile_data.flip_v,
+            d: layer_tile_data.flip_d,
+        },
+        visible: TileVisible(visible),
+        color,
+        ..Default::default()
+    });
+    if let Some(animated_tile) = tiled_map
+        .tile_animations
+        .get(&(tileset_index, layer_tile_data.id()))
+    {
+        tile_commands.insert(*animated_tile);
+    }
+    tile_commands.id()
+}
+
+/// Returns the Tiled coordinates of the top-left tile of the chunks of an infinite layer, and
+/// their size in tiles
+fn get_chunk_bounds(layer_data: &tiled::InfiniteTileLayer) -> (IVec2, TilemapSize) {
+    let chunk_size = IVec2::new(
+        tiled::ChunkData::WIDTH as i32,
+        tiled::ChunkData::HEIGHT as i32,
+    );
+    let (min, max) = layer_data
+        .chunks()
+        .map(|((chunk_x, chunk_y), _)| IVec2::new(chunk_x, chunk_y))
+        .fold(None, |bounds, chunk| match bounds {
+            None => Some((chunk, chunk)),
+            Some((min, max)) => Some((chunk.min(min), chunk.max(max))),
+        })
+        .unwrap_or_default();
+
+    let size = (max - min + IVec2::ONE) * chunk_size;
+    (
+        min * chunk_size,
+        TilemapSize {
+            x: size.x as u32,
+            y: size.y as u32,
+        },
+    )
+}
+
+/// Returns the positions of the tiles of a chunk in its layer, as tilemap and Tiled coordinates
+fn get_chunk_tiles(layer: &InfiniteLayer, (chunk_x, chunk_y): (i32, i32)) -> Vec<(TilePos, IVec2)> {
+    let chunk_width = tiled::ChunkData::WIDTH as i32;
+    let chunk_height = tiled::ChunkData::HEIGHT as i32;
+    let bottom = layer.tiles_origin.y + layer.size.y as i32 - 1;
+
+    let mut tiles = Vec::new();
+    for x in chunk_x * chunk_width..(chunk_x + 1) * chunk_width {
+        for y in chunk_y * chunk_height..(chunk_y + 1) * chunk_height {
+            let tile_pos = TilePos {
+                x: (x - layer.tiles_origin.x) as u32,
+                y: (bottom - y) as u32,
+            };
+            tiles.push((tile_pos, IVec2::new(x, y)));
+        }
+    }
+    tiles
+}
+
+/// Spawns the chunks of the infinite layers in the loading area of their map, and despawns the
+/// chunks out of it
+pub fn update_chunks(
+    mut commands: Commands,
+    maps: Res<Assets<TiledMap>>,
+    map_query: Query<(&TiledMapHandle, &ChunkLoadingArea)>,
+    mut layer_query: Query<(Entity, &mut InfiniteLayer, &mut TileStorage)>,
+) {
+    for (layer_entity, mut layer, mut tile_storage) in layer_query.iter_mut() {
+        let Ok((map_handle, loading_area)) = map_query.get(layer.map_entity) else {
+            continue;
+        };
+        let Some(tiled_map) = maps.get(&map_handle.0) else {
+            continue;
+        };
+        let Some(tiled::LayerType::Tiles(tiled::TileLayer::Infinite(layer_data))) = tiled_map
+            .map
+            .get_layer(layer.layer_index)
+            .map(|tiled_layer| tiled_layer.layer_type())
+        else {
+            continue;
+        };
+
+        let chunk_size = Vec2::new(
+            (tiled::ChunkData::WIDTH * tiled_map.map.tile_width) as f32,
+            (tiled::ChunkData::HEIGHT * tiled_map.map.tile_height) as f32,
+        );
+        let (map_width, map_height) = tiled_map.get_pixel_size();
+        let chunks = match loading_area.0 {
+            Some(area) => layer_data
+                .chunks()
+                .map(|(chunk_pos, _)| chunk_pos)
+                .filter(|(chunk_x, chunk_y)| {
+                    // Chunks are ordered from the top left, as the tiles
+                    let top_left = Vec2::new(
+                        *chunk_x as f32 * chunk_size.x - map_width as f32 / 2.0,
+                        map_height as f32 / 2.0 - *chunk_y as f32 * chunk_size.y,
+                    );
+                    let chunk_rect = Rect::from_corners(
+                        top_left,
+                        top_left + Vec2::new(chunk_size.x, -chunk_size.y),
+                    );
+                    !chunk_rect.intersect(area).is_empty()
+                })
+                .collect::<HashSet<_>>(),
+            None => HashSet::new(),
+        };
+
+        for chunk_pos in layer.loaded_chunks.difference(&chunks) {
+            for (tile_pos, _) in get_chunk_tiles(&layer, *chunk_pos) {
+                if let Some(tile_entity) = tile_storage.get(&tile_pos) {
+                    commands.entity(tile_entity).despawn();
+                    tile_storage.remove(&tile_pos);
+                }
+            }
+        }
+
+        for chunk_pos in chunks.difference(&layer.loaded_chunks) {
+            for (tile_pos, tiled_pos) in get_chunk_tiles(&layer, *chunk_pos) {
+                let Some(layer_tile_data) = layer_data.get_tile_data(tiled_pos.x, tiled_pos.y)
+                else {
+                    continue;
+                };
+                if layer.tileset_index != layer_tile_data.tileset_index() {
+                    continue;
+                }
+
+                let tile_entity = spawn_tile(
+                    &mut commands,
+                    tiled_map,
+                    layer_entity,
+                    layer.tileset_index,
+                    tile_pos,
+                    layer_tile_data,
+                    layer.tile_color,
+                    true,
+                );
+                tile_storage.set(&tile_pos, tile_entity);
+            }
+        }
+
+        layer.loaded_chunks = chunks;
+    }
+}
+
+/// Moves the parallax layers according to the top-left corner of the camera view
+fn update_layer_parallax(
+    camera_query: Query<(&Transform, &Projection), (With<Camera2d>, Without<LayerParallax>)>,
+    mut layer_query: Query<(&LayerParallax, &mut Transform)>,
+) {
+    let Ok((camera, projection)) = camera_query.single() else {
+        return;
+    };
+    let view_top_left = match projection {
+        Projection::Orthographic(projection) => {
+            camera.translation.xy() + Vec2::new(projection.area.min.x, projection.area.max.y)
+        }
+        _ => camera.translation.xy(),
+    };
+
+    for (parallax, mut transform) in layer_query.iter_mut() {
+        let translation =
+            parallax.position + (view_top_left - parallax.origin) * (Vec2::ONE - parallax.factor);
+        transform.translation = translation.extend(transform.translation.z);
+    }
+}
@@ -2,16 +2,23 @@ use bevy::{
     audio::{PlaybackMode, Volume},
     prelude::*,
 };
+use bevy_rapier2d::geometry::{ActiveCollisionTypes, ActiveEvents, Collider, Sensor};
 
 use crate::{
     assets::RockRunAssets,
-    coregame::{camera::CameraSet, state::AppState},
+    collision_layers::CollisionLayer,
+    coregame::{
+        camera::CameraSet,
+        colliders::ColliderName,
+        entities::{EntitySpawn, EntitySpawnerAppExt},
+        state::AppState,
+    },
     events::{KeyCollision, Restart},
 };
 
-pub const KEY_SCALE_FACTOR: f32 = 2.0;
-pub const KEY_WIDTH: f32 = 16.0;
-pub const KEY_HEIGHT: f32 = 16.0;
+const KEY_SCALE_FACTOR: f32 = 2.0;
+const KEY_WIDTH: f32 = 16.0;
+const KEY_HEIGHT: f32 = 16.0;
 
 #[derive(Resource, Default)]
 pub struct Keys {
@@ -25,7 +32,8 @@ pub struct KeyPlugin;
 
 impl Plugin for KeyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::StartMenu), despawn_key)
+        app.register_entity_spawner("key", spawn_key)
+            .add_systems(OnEnter(AppState::StartMenu), despawn_key)
             .add_systems(OnEnter(AppState::FinishLevel), despawn_key)
             .add_systems(
                 Update,
@@ -37,6 +45,35 @@ impl Plugin for KeyPlugin {
     }
 }
 
+fn spawn_key(
+    In(entity_spawn): In<EntitySpawn>,
+    mut commands: Commands,
+    rock_run_assets: Res<RockRunAssets>,
+) -> Option<Entity> {
+    Some(
+        commands
+            .spawn((
+                Sprite {
+                    image: rock_run_assets.key.clone(),
+                    ..default()
+                },
+                Transform {
+                    scale: Vec3::splat(KEY_SCALE_FACTOR),
+                    translation: entity_spawn.position.extend(10.0),
+                    ..default()
+                },
+                Collider::cuboid(KEY_WIDTH / 2.0, KEY_HEIGHT / 2.0),
+                CollisionLayer::Collectible.groups(),
+                Sensor,
+                ActiveEvents::COLLISION_EVENTS,
+                ActiveCollisionTypes::KINEMATIC_STATIC,
+                Key,
+                ColliderName(entity_spawn.name),
+            ))
+            .id(),
+    )
+}
+
 fn check_get_key(
     mut commands: Commands,
     rock_run_assets: Res<RockRunAssets>,
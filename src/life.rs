@@ -1,6 +1,5 @@
 use bevy::{
     audio::{PlaybackMode, Volume},
-    platform::collections::HashMap,
     prelude::*,
 };
 use bevy_rapier2d::geometry::{ActiveCollisionTypes, ActiveEvents, Collider, Sensor};
@@ -8,10 +7,11 @@ use bevy_rapier2d::geometry::{ActiveCollisionTypes, ActiveEvents, Collider, Sens
 use crate::{
     WINDOW_HEIGHT, WINDOW_WIDTH,
     assets::RockRunAssets,
+    collision_layers::CollisionLayer,
     coregame::{
         camera::CameraSet,
         colliders::ColliderName,
-        level::{CurrentLevel, Level},
+        entities::{EntitySpawn, EntitySpawnerAppExt},
         state::AppState,
     },
     events::{ExtraLifeCollision, LifeEvent},
@@ -36,8 +36,8 @@ pub struct LifePlugin;
 
 impl Plugin for LifePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::GameCreate), (setup_life, setup_extralife))
-            .add_systems(OnEnter(AppState::NextLevel), setup_extralife)
+        app.register_entity_spawner("extra_life", spawn_extralife)
+            .add_systems(OnEnter(AppState::GameCreate), setup_life)
             .add_systems(
                 OnEnter(AppState::StartMenu),
                 (despawn_life, despawn_extralife),
@@ -167,55 +167,35 @@ fn despawn_life(
     }
 }
 
-fn setup_extralife(
+fn spawn_extralife(
+    In(entity_spawn): In<EntitySpawn>,
     mut commands: Commands,
     rock_run_assets: Res<RockRunAssets>,
-    levels: Query<&Level, With<Level>>,
-    current_level: Res<CurrentLevel>,
-) {
-    info!("setup_extralife");
-
-    let level = levels
-        .iter()
-        .find(|level| level.id == current_level.id)
-        .unwrap();
-
-    let texture = &rock_run_assets.life;
-    let mut extra_life_pos: HashMap<u8, Vec<Vec2>> = HashMap::new();
-    extra_life_pos.insert(
-        1,
-        vec![level.map.tiled_to_bevy_coord(Vec2::new(1056.0, 112.0))],
-    );
-
-    extra_life_pos.insert(
-        2,
-        vec![level.map.tiled_to_bevy_coord(Vec2::new(5416.0, 1518.0))],
-    );
-
-    let start_positions = match extra_life_pos.get(&current_level.id) {
-        Some(positions) => positions,
-        None => return,
-    };
-
-    for start_pos in start_positions {
-        commands.spawn((
-            Sprite {
-                image: texture.clone(),
-                ..default()
-            },
-            Transform {
-                scale: Vec3::splat(LIFE_SCALE_FACTOR),
-                translation: start_pos.extend(10.0),
-                ..default()
-            },
-            Collider::cuboid(LIFE_WIDTH / 2.0, LIFE_HEIGHT / 2.0),
-            Sensor,
-            ActiveEvents::COLLISION_EVENTS,
-            ActiveCollisionTypes::KINEMATIC_STATIC,
-            ExtraLife,
-            ColliderName("extralife01".to_string()),
-        ));
-    }
+) -> Option<Entity> {
+    info!("spawn_extralife {}", entity_spawn.name);
+
+    Some(
+        commands
+            .spawn((
+                Sprite {
+                    image: rock_run_assets.life.clone(),
+                    ..default()
+                },
+                Transform {
+                    scale: Vec3::splat(LIFE_SCALE_FACTOR),
+                    translation: entity_spawn.position.extend(10.0),
+                    ..default()
+                },
+                Collider::cuboid(LIFE_WIDTH / 2.0, LIFE_HEIGHT / 2.0),
+                CollisionLayer::Collectible.groups(),
+                Sensor,
+                ActiveEvents::COLLISION_EVENTS,
+                ActiveCollisionTypes::KINEMATIC_STATIC,
+                ExtraLife,
+                ColliderName(entity_spawn.name),
+            ))
+            .id(),
+    )
 }
 
 fn check_get_extralife(
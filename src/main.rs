@@ -1,5 +1,7 @@
 mod assets;
 mod beasts;
+mod breath;
+mod collision_layers;
 mod collisions;
 mod coregame;
 mod elements;
@@ -10,7 +12,7 @@ mod key;
 mod life;
 mod music;
 mod player;
-mod screen_map;
+mod stats;
 
 use bevy::{asset::AssetMetaCheck, prelude::*, window::WindowResolution};
 use key::KeyPlugin;
@@ -18,6 +20,8 @@ use key::KeyPlugin;
 use crate::{
     assets::RockRunAssets,
     beasts::plugins::BeastsPlugins,
+    breath::BreathPlugin,
+    collision_layers::CollisionLayersPlugin,
     collisions::CollisionsPlugin,
     coregame::{plugins::CoreGamePlugins, state::AppState},
     elements::plugins::ElementsPlugins,
@@ -26,6 +30,7 @@ use crate::{
     life::LifePlugin,
     music::MusicPlugin,
     player::PlayerPlugin,
+    stats::StatsPlugin,
 };
 
 use bevy_asset_loader::prelude::*;
@@ -62,11 +67,15 @@ fn main() {
         BeastsPlugins,
         ElementsPlugins,
         helpers::tiled::TiledMapPlugin,
+        helpers::level_manifest::LevelManifestPlugin,
         MusicPlugin,
         PlayerPlugin,
         LifePlugin,
         KeyPlugin,
         CollisionsPlugin,
+        CollisionLayersPlugin,
+        StatsPlugin,
+        BreathPlugin,
     ))
     // with 0.14, init_state needs to be declared after plugins
     // https://github.com/bevyengine/bevy/issues/14154
@@ -1,6 +1,6 @@
-use crate::{
-    assets::RockRunAssets,
-    coregame::{level::CurrentLevel, state::AppState},
+use crate::coregame::{
+    level::{CurrentLevel, Level},
+    state::AppState,
 };
 use bevy::{
     audio::{PlaybackMode, Volume},
@@ -24,48 +24,25 @@ impl Plugin for MusicPlugin {
 
 fn setup_music(
     mut commands: Commands,
-    rock_run_assets: Res<RockRunAssets>,
-    // levels: Query<&Level, With<Level>>,
+    levels: Query<&Level, With<Level>>,
     current_level: Res<CurrentLevel>,
 ) {
     info!("setup_music");
 
-    match current_level.id {
-        1 => {
-            commands.spawn((
-                AudioPlayer::new(rock_run_assets.music_level01.clone()),
-                PlaybackSettings {
-                    mode: PlaybackMode::Loop,
-                    volume: Volume::Linear(0.3),
-                    ..default()
-                },
-                Music,
-            ));
-        }
-        2 => {
-            commands.spawn((
-                AudioPlayer::new(rock_run_assets.music_level02.clone()),
-                PlaybackSettings {
-                    mode: PlaybackMode::Loop,
-                    volume: Volume::Linear(0.3),
-                    ..default()
-                },
-                Music,
-            ));
-        }
-        3 => {
-            commands.spawn((
-                AudioPlayer::new(rock_run_assets.music_level03.clone()),
-                PlaybackSettings {
-                    mode: PlaybackMode::Loop,
-                    volume: Volume::Linear(0.3),
-                    ..default()
-                },
-                Music,
-            ));
-        }
-        _ => {}
-    }
+    let Some(level) = levels.iter().find(|level| level.id == current_level.id) else {
+        warn!("no music for level {}", current_level.id);
+        return;
+    };
+
+    commands.spawn((
+        AudioPlayer::new(level.music.clone()),
+        PlaybackSettings {
+            mode: PlaybackMode::Loop,
+            volume: Volume::Linear(0.3),
+            ..default()
+        },
+        Music,
+    ));
 }
 
 fn despawn_music(mut commands: Commands, musics: Query<Entity, With<Music>>) {
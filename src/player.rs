@@ -1,4 +1,6 @@
-use bevy::{audio::PlaybackMode, prelude::*};
+use std::time::Duration;
+
+use bevy::{audio::PlaybackMode, platform::collections::HashSet, prelude::*};
 use bevy_rapier2d::{
     control::KinematicCharacterController, dynamics::RigidBody, geometry::Collider, prelude::Ccd,
 };
@@ -9,15 +11,21 @@ use leafwing_input_manager::{
 
 use crate::{
     assets::RockRunAssets,
+    breath::Breath,
+    collision_layers::CollisionLayer,
     collisions::CollisionSet,
     coregame::{
+        camera::no_screen_transition,
+        colliders::Water,
         level::{CurrentLevel, Level},
         menu::StartPos,
         state::AppState,
     },
+    elements::checkpoint::Checkpoint,
     events::{
-        Hit, LadderCollisionStart, LadderCollisionStop, LifeEvent, MovingPlatformDescending,
-        Restart, StartGame,
+        BouncePadCollision, Hit, LadderCollisionStart, LadderCollisionStop, LifeEvent,
+        MovingPlatformDescending, NextLevel, Restart, StartGame, WaterCollisionStart,
+        WaterCollisionStop,
     },
     helpers::texture::{IndexDirection, cycle_texture, swing_texture},
 };
@@ -28,6 +36,12 @@ pub const PLAYER_WIDTH: f32 = 100.0;
 pub const PLAYER_HEIGHT: f32 = 75.0;
 const PLAYER_HITBOX: (Vec2, Vec2, f32) = (Vec2::new(-4.0, -9.0), Vec2::new(-4.0, 8.0), 22.0);
 const PLAYER_HITBOX_TRANSLATION: Vec2 = Vec2::new(8.0, 0.0);
+/// Distance between the player position and the bottom of the hitbox
+pub const PLAYER_FEET_OFFSET: f32 = PLAYER_HITBOX.2 - PLAYER_HITBOX.0.y;
+const JUMP_DURATION: f32 = 0.250;
+const SWIM_SPEED: f32 = PLAYER_SPEED * 0.4;
+/// Sinking speed in the water, relative to the swimming speed
+const SWIM_SINK_FACTOR: f32 = 0.3;
 const PLAYER_START_OFFSET: Vec3 = Vec3::new(-480.0, 0.0, 0.0);
 
 #[derive(Component)]
@@ -37,6 +51,7 @@ pub struct Player;
 struct PlayerAudio {
     jump_sound: Handle<AudioSource>,
     hit_sound: Handle<AudioSource>,
+    splash_sound: Handle<AudioSource>,
 }
 
 #[derive(Component, Deref, DerefMut)]
@@ -53,6 +68,7 @@ pub enum PlayerState {
     Falling,
     Hit,
     Climbing,
+    Swimming,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Actionlike, Hash, Reflect)]
@@ -86,10 +102,17 @@ impl Plugin for PlayerPlugin {
             .add_systems(OnEnter(AppState::FinishLevel), despawn_player)
             .add_systems(
                 Update,
-                (move_player, check_out_of_screen, check_hit, restart_level)
+                (
+                    check_water,
+                    move_player,
+                    check_out_of_screen,
+                    check_hit,
+                    restart_level,
+                )
                     .in_set(PlayerSet)
                     .after(CollisionSet)
-                    .run_if(in_state(AppState::GameRunning)),
+                    .run_if(in_state(AppState::GameRunning))
+                    .run_if(no_screen_transition),
             );
     }
 }
@@ -101,6 +124,7 @@ fn setup_player(
     levels: Query<&Level, With<Level>>,
     current_level: Res<CurrentLevel>,
     start_position: Res<StartPos>,
+    checkpoint: Res<Checkpoint>,
 ) {
     info!("setup_player");
 
@@ -172,12 +196,15 @@ fn setup_player(
         },
     );
 
-    let start_position: Vec3 = match start_position.0 {
-        Some(position) => {
+    let start_position: Vec3 = match (start_position.0, checkpoint.position) {
+        (Some(position), _) => {
             info!("Tiled start_position: {:?}", position);
             level.map.tiled_to_bevy_coord(position).extend(20.0)
         }
-        None => level.map.get_start_screen().get_center().extend(20.0) + PLAYER_START_OFFSET,
+        (None, Some(position)) => position.extend(20.0),
+        (None, None) => {
+            level.map.get_start_screen().get_center().extend(20.0) + PLAYER_START_OFFSET
+        }
     };
 
     commands.spawn((
@@ -196,21 +223,24 @@ fn setup_player(
         },
         RigidBody::KinematicPositionBased,
         AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
-        JumpTimer(Timer::from_seconds(0.250, TimerMode::Once)),
+        JumpTimer(Timer::from_seconds(JUMP_DURATION, TimerMode::Once)),
         Collider::capsule(PLAYER_HITBOX.0, PLAYER_HITBOX.1, PLAYER_HITBOX.2),
+        CollisionLayer::Player.groups(),
         KinematicCharacterController {
             max_slope_climb_angle: 30.0f32.to_radians(),
             // Automatically slide down on slopes smaller than 30 degrees.
             min_slope_slide_angle: 30.0f32.to_radians(),
             // normal_nudge_factor: 0.03,
             // offset: CharacterLength::Absolute(0.02),
-            ..default()
+            // One-way platforms are made passable for the player only by their groups.
+            ..CollisionLayer::Player.controller()
         },
         Ccd::enabled(),
         Player,
         PlayerAudio {
             jump_sound: rock_run_assets.jump_sound.clone(),
             hit_sound: rock_run_assets.hit_sound.clone(),
+            splash_sound: rock_run_assets.splash_sound.clone(),
         },
         input_map,
     ));
@@ -238,6 +268,7 @@ fn move_player(
     mut ladder_collision_start: EventReader<LadderCollisionStart>,
     mut ladder_collision_stop: EventReader<LadderCollisionStop>,
     mut moving_platform_descending: EventReader<MovingPlatformDescending>,
+    mut bounce_pad_collision: EventReader<BouncePadCollision>,
     mut game_event: EventReader<StartGame>,
     mut ladder_collision: Local<bool>,
     mut toggle: Local<bool>,
@@ -389,9 +420,28 @@ fn move_player(
     }
 
     if input_state.just_pressed(&PlayerMovement::Jump)
-        && !(state.get() == &PlayerState::Jumping || state.get() == &PlayerState::Falling)
+        && !(state.get() == &PlayerState::Jumping
+            || state.get() == &PlayerState::Falling
+            || state.get() == &PlayerState::Swimming)
     {
         next_state.set(PlayerState::Jumping);
+        jump_timer.set_duration(Duration::from_secs_f32(JUMP_DURATION));
+        jump_timer.reset();
+        commands.spawn((
+            AudioPlayer::new(player_audio.jump_sound.clone()),
+            PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                ..default()
+            },
+        ));
+        current_movement = PlayerMovement::Jump;
+        let _ = anim(current_movement);
+    }
+
+    // Bounce pads launch the player with a longer jump
+    if let Some(bounce_pad) = bounce_pad_collision.read().last() {
+        next_state.set(PlayerState::Jumping);
+        jump_timer.set_duration(Duration::from_secs_f32(JUMP_DURATION * bounce_pad.strength));
         jump_timer.reset();
         commands.spawn((
             AudioPlayer::new(player_audio.jump_sound.clone()),
@@ -449,6 +499,21 @@ fn move_player(
                 direction_y * PLAYER_SPEED * time.delta_secs(),
             ));
         }
+    } else if state.get() == &PlayerState::Swimming {
+        // The water slows down the player, jump or climb to swim up
+        direction_y = if input_state.pressed(&PlayerMovement::Jump)
+            || input_state.pressed(&PlayerMovement::Climb)
+        {
+            1.0
+        } else if input_state.pressed(&PlayerMovement::Crouch) {
+            -1.0
+        } else {
+            -SWIM_SINK_FACTOR
+        };
+        player_controller.translation = Some(Vec2::new(
+            direction_x * SWIM_SPEED * time.delta_secs(),
+            direction_y * SWIM_SPEED * time.delta_secs(),
+        ));
     } else {
         // Check if we are on a moving platform that goes down
         let events: Vec<&MovingPlatformDescending> = moving_platform_descending.read().collect();
@@ -470,6 +535,95 @@ fn move_player(
     Ok(())
 }
 
+/// Water sensors switch the player to swimming, leaving the water while swimming up gives a jump
+/// to reach the shore.
+///
+/// The water zones the player is in are tracked by entity: zones despawned by a reload of the
+/// level are left, as no collision stop is sent for them.
+#[allow(clippy::too_many_arguments)]
+fn check_water(
+    mut commands: Commands,
+    input: Query<&ActionState<PlayerMovement>, With<Player>>,
+    player_query: Query<&PlayerAudio, With<Player>>,
+    mut jump_timer: Query<&mut JumpTimer>,
+    state: Res<State<PlayerState>>,
+    mut next_state: ResMut<NextState<PlayerState>>,
+    mut water_collision_start: EventReader<WaterCollisionStart>,
+    mut water_collision_stop: EventReader<WaterCollisionStop>,
+    mut game_event: EventReader<StartGame>,
+    mut restart: EventReader<Restart>,
+    mut next_level: EventReader<NextLevel>,
+    mut breath: ResMut<Breath>,
+    waters: Query<(), With<Water>>,
+    mut water_zones: Local<HashSet<Entity>>,
+) -> Result<()> {
+    if !game_event.is_empty() || !restart.is_empty() || !next_level.is_empty() {
+        game_event.clear();
+        restart.clear();
+        next_level.clear();
+        water_zones.clear();
+        breath.stop();
+    }
+
+    // Water zones can overlap, the player is out of the water when leaving all of them.
+    let in_water = !water_zones.is_empty();
+    water_zones.retain(|water| waters.contains(*water));
+    let entered: Vec<&WaterCollisionStart> = water_collision_start.read().collect();
+    water_zones.extend(entered.iter().map(|ev| ev.water));
+    for ev in water_collision_stop.read() {
+        water_zones.remove(&ev.water);
+    }
+
+    if state.get() == &PlayerState::Hit {
+        return Ok(());
+    }
+
+    let player_audio = player_query.single()?;
+    let splash = |commands: &mut Commands| {
+        commands.spawn((
+            AudioPlayer::new(player_audio.splash_sound.clone()),
+            PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                ..default()
+            },
+        ));
+    };
+
+    if !in_water && !water_zones.is_empty() {
+        debug!("enter water");
+        splash(&mut commands);
+        breath.start(entered.iter().find_map(|ev| ev.breath));
+    } else if in_water && water_zones.is_empty() {
+        debug!("leave water");
+        splash(&mut commands);
+        breath.stop();
+
+        let input_state = input.single()?;
+        if state.get() == &PlayerState::Swimming
+            && (input_state.pressed(&PlayerMovement::Jump)
+                || input_state.pressed(&PlayerMovement::Climb))
+        {
+            let mut jump_timer = jump_timer.single_mut()?;
+            jump_timer.set_duration(Duration::from_secs_f32(JUMP_DURATION));
+            jump_timer.reset();
+            next_state.set(PlayerState::Jumping);
+        } else {
+            next_state.set(PlayerState::Falling);
+        }
+    }
+
+    // Jumps end in the water
+    if !water_zones.is_empty()
+        && !matches!(
+            state.get(),
+            PlayerState::Swimming | PlayerState::Jumping | PlayerState::Hit
+        )
+    {
+        next_state.set(PlayerState::Swimming);
+    }
+    Ok(())
+}
+
 fn check_out_of_screen(
     levels: Query<&Level, With<Level>>,
     current_level: Res<CurrentLevel>,
@@ -516,6 +670,7 @@ fn check_hit(
         debug!("justhit {}", *just_hit);
         if !*just_hit {
             let player_audio = player_query.single_mut()?;
+            jump_timer.set_duration(Duration::from_secs_f32(JUMP_DURATION));
             jump_timer.reset();
             *just_hit = true;
             commands.spawn((
@@ -545,6 +700,7 @@ fn restart_level(
     mut life_event: EventWriter<LifeEvent>,
     mut next_state: ResMut<NextState<PlayerState>>,
     mut ladder_collision_stop: EventWriter<LadderCollisionStop>,
+    checkpoint: Res<Checkpoint>,
 ) -> Result<()> {
     if restart.is_empty() {
         return Ok(());
@@ -561,8 +717,10 @@ fn restart_level(
 
     life_event.write(LifeEvent::Lost);
     ladder_collision_stop.write(LadderCollisionStop);
-    player.translation =
-        level.map.get_start_screen().get_center().extend(20.00) + PLAYER_START_OFFSET;
+    player.translation = match checkpoint.position {
+        Some(position) => position.extend(20.0),
+        None => level.map.get_start_screen().get_center().extend(20.00) + PLAYER_START_OFFSET,
+    };
     next_state.set(PlayerState::Falling);
     Ok(())
 }
@@ -1,6 +1,13 @@
 #![allow(dead_code)] // TODO: remove
+pub mod level_manifest;
+pub mod level_objects;
+
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque, hash_map::Entry};
 use std::ops::Range;
+use std::time::Duration;
+use thiserror::Error;
 
 #[cfg(test)]
 const SMOOTH_FACTOR_X: f32 = 1.0;
@@ -12,15 +19,255 @@ const SMOOTH_FACTOR_X: f32 = 3.0;
 #[cfg(not(test))]
 const SMOOTH_FACTOR_Y: f32 = 20.0;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+const DEAD_ZONE_HALF_WIDTH: f32 = 160.0;
+const DEAD_ZONE_HALF_HEIGHT: f32 = 90.0;
+const LOOK_AHEAD_DISTANCE: f32 = 200.0;
+
+/// Speed of the camera on auto-scroll screens in pixels per second
+const AUTO_SCROLL_SPEED: f32 = 100.0;
+
+const FADE_DURATION: Duration = Duration::from_millis(800);
+const SLIDE_DURATION: Duration = Duration::from_millis(500);
+const IRIS_DURATION: Duration = Duration::from_millis(1000);
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Serialize, Deserialize)]
 pub enum Transition {
     #[default]
     Smooth,
     Hard,
+    /// Fade to black, then back from black on the new screen
+    Fade(Duration),
+    /// Slide the camera from the old screen to the new one
+    Slide(Duration),
+    /// Close a circle around the player, then open it on the new screen
+    Iris(Duration),
+}
+
+impl Transition {
+    /// Returns the duration of an animated transition, None for smooth and hard transitions
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use screen_map::Transition;
+    ///  use std::time::Duration;
+    ///
+    ///  assert_eq!(Transition::Hard.get_duration(), None);
+    ///  assert_eq!(
+    ///      Transition::Fade(Duration::from_secs(1)).get_duration(),
+    ///      Some(Duration::from_secs(1))
+    ///  );
+    /// ```
+    pub const fn get_duration(&self) -> Option<Duration> {
+        match *self {
+            Self::Smooth | Self::Hard => None,
+            Self::Fade(duration) | Self::Slide(duration) | Self::Iris(duration) => Some(duration),
+        }
+    }
+
+    /// Returns `true` if the transition is animated (fade, slide or iris)
+    pub const fn is_animated(&self) -> bool {
+        self.get_duration().is_some()
+    }
+}
+
+/// Axes the camera can scroll along on a screen
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub enum ScrollAxis {
+    #[default]
+    Both,
+    Horizontal,
+    Vertical,
+}
+
+/// Errors returned when building a [`Map`]
+///
+/// Rows and columns are screen indices, the origin is at the top left.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ScreenMapError {
+    #[error("Screen map is empty")]
+    Empty,
+    #[error("Unknown screen cell '{cell}' at row {row}, column {column}")]
+    InvalidCell {
+        cell: char,
+        row: usize,
+        column: usize,
+    },
+    #[error("Screen map row {row} has {found} screens, expected {expected}")]
+    UnevenRows {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("Screen map has no start screen ('S')")]
+    MissingStartScreen,
+    #[error(
+        "Screen map has several start screens ('S'): row {}, column {} and row {}, column {}",
+        first.0, first.1, second.0, second.1
+    )]
+    MultipleStartScreens {
+        /// (row, column) of the first start screen
+        first: (usize, usize),
+        /// (row, column) of the second start screen
+        second: (usize, usize),
+    },
+    #[error("Unknown camera policy \"{0}\"")]
+    UnknownCameraPolicy(String),
+    #[error(
+        "Screen map is {}x{} px, expected {}x{} px for its screens",
+        found.0, found.1, expected.0, expected.1
+    )]
+    SizeMismatch {
+        /// (width, height) of the screens
+        expected: (usize, usize),
+        /// (width, height) of the map
+        found: (usize, usize),
+    },
+    #[error("Screen at row {row}, column {column} doesn't match its place in the map")]
+    MisplacedScreen { row: usize, column: usize },
+}
+
+/// How the camera follows its target
+///
+/// A policy can be set for a whole map with [`Map::set_camera_policy()`] and overridden for a
+/// screen with [`Map::set_screen_camera_policy()`].
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum CameraPolicy {
+    /// The camera follows the target, smoothly on the x axis
+    #[default]
+    SmoothFollow,
+    /// The camera only moves when the target leaves a zone centered on the camera
+    DeadZone { half_width: f32, half_height: f32 },
+    /// The camera looks ahead of the target in its direction of travel
+    LookAhead { distance: f32 },
+    /// The camera only follows the target on the y axis when the target is grounded
+    PlatformSnap,
+}
+
+impl CameraPolicy {
+    /// Returns the position the camera should move to, before screen boundaries are applied
+    fn get_goal(&self, camera_pos: Vec2, target: &CameraTarget) -> Vec2 {
+        match *self {
+            Self::SmoothFollow => target.position,
+            Self::DeadZone {
+                half_width,
+                half_height,
+            } => {
+                let follow = |camera: f32, target: f32, half_size: f32| {
+                    if target - camera > half_size {
+                        target - half_size
+                    } else if camera - target > half_size {
+                        target + half_size
+                    } else {
+                        camera
+                    }
+                };
+                Vec2::new(
+                    follow(camera_pos.x, target.position.x, half_width),
+                    follow(camera_pos.y, target.position.y, half_height),
+                )
+            }
+            Self::LookAhead { distance } => {
+                let look_ahead = if target.velocity.x == 0.0 {
+                    0.0
+                } else {
+                    target.velocity.x.signum() * distance
+                };
+                Vec2::new(target.position.x + look_ahead, target.position.y)
+            }
+            Self::PlatformSnap => {
+                if target.grounded {
+                    target.position
+                } else {
+                    Vec2::new(target.position.x, camera_pos.y)
+                }
+            }
+        }
+    }
+
+    /// Smoothes the camera movement from `old_pos` to `new_pos`
+    ///
+    /// Warning: the SMOOTH_FACTOR is not the same in prod and test.
+    fn smooth(&self, delta_secs: f32, old_pos: Vec2, new_pos: Vec2) -> Vec2 {
+        let smooth_factor = Vec2::new(SMOOTH_FACTOR_X, SMOOTH_FACTOR_Y);
+
+        match self {
+            Self::SmoothFollow | Self::LookAhead { .. } => Vec2::new(
+                old_pos.x.lerp(new_pos.x, smooth_factor.x * delta_secs),
+                new_pos.y,
+            ),
+            Self::DeadZone { .. } => new_pos,
+            Self::PlatformSnap => Vec2::new(
+                old_pos.x.lerp(new_pos.x, smooth_factor.x * delta_secs),
+                // Don't overshoot on slow frames
+                old_pos
+                    .y
+                    .lerp(new_pos.y, (smooth_factor.y * delta_secs).min(1.0)),
+            ),
+        }
+    }
+}
+
+impl std::str::FromStr for CameraPolicy {
+    type Err = ScreenMapError;
+
+    /// Parses a camera policy name, policies with parameters use default values
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use screen_map::CameraPolicy;
+    ///
+    ///  assert_eq!("smooth_follow".parse(), Ok(CameraPolicy::SmoothFollow));
+    ///  assert_eq!("platform_snap".parse(), Ok(CameraPolicy::PlatformSnap));
+    ///  assert!("look_behind".parse::<CameraPolicy>().is_err());
+    /// ```
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "smooth_follow" => Ok(Self::SmoothFollow),
+            "dead_zone" => Ok(Self::DeadZone {
+                half_width: DEAD_ZONE_HALF_WIDTH,
+                half_height: DEAD_ZONE_HALF_HEIGHT,
+            }),
+            "look_ahead" => Ok(Self::LookAhead {
+                distance: LOOK_AHEAD_DISTANCE,
+            }),
+            "platform_snap" => Ok(Self::PlatformSnap),
+            _ => Err(ScreenMapError::UnknownCameraPolicy(name.to_string())),
+        }
+    }
+}
+
+/// What the camera follows
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CameraTarget {
+    /// Position of the target as bevy coordinates
+    pub position: Vec2,
+    /// Velocity of the target in pixels per second
+    pub velocity: Vec2,
+    /// `true` if the target stands on the ground or on a platform
+    pub grounded: bool,
+}
+
+impl From<Vec2> for CameraTarget {
+    /// A grounded target that doesn't move
+    fn from(position: Vec2) -> Self {
+        Self {
+            position,
+            velocity: Vec2::ZERO,
+            grounded: true,
+        }
+    }
 }
 
+/// Optional data attached to a screen, as values by key
+///
+/// The map doesn't interpret it, the game reads the keys it knows about (e.g. `music`, `shader`,
+/// `display_name` or `checkpoint`).
+pub type ScreenMetadata = HashMap<String, String>;
+
 /// A struct that describe a Screen
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Screen {
     x_index: usize,
     y_index: usize,
@@ -30,6 +277,12 @@ pub struct Screen {
     allowed_screen: bool,
     fixed_screen: bool,
     transition: Transition,
+    camera_policy: Option<CameraPolicy>,
+    locked_screen: bool,
+    scroll_axis: ScrollAxis,
+    auto_scroll: Option<Vec2>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<ScreenMetadata>,
 }
 
 impl Screen {
@@ -73,22 +326,166 @@ impl Screen {
         self.fixed_screen
     }
 
-    /// Returns the transition type (smooth or hard)
+    /// Returns the transition type (smooth, hard or animated)
     pub const fn get_transition(&self) -> Transition {
         self.transition
     }
+
+    /// Returns the camera policy of the screen, None if it uses the policy of the map
+    pub const fn get_camera_policy(&self) -> Option<CameraPolicy> {
+        self.camera_policy
+    }
+
+    /// Returns `true` if the screen is a locked screen (defined in map with a 'L')
+    ///
+    /// See [`Map::is_screen_locked()`] to know if it is still locked.
+    pub const fn is_locked_screen(&self) -> bool {
+        self.locked_screen
+    }
+
+    /// Returns the axes the camera can scroll along (restricted in map with a '-' or a '|')
+    pub const fn get_scroll_axis(&self) -> ScrollAxis {
+        self.scroll_axis
+    }
+
+    /// Returns the camera velocity in pixels per second if the screen is an auto-scroll screen
+    /// (defined in map with a '>', '<', '^' or 'v')
+    pub const fn get_auto_scroll(&self) -> Option<Vec2> {
+        self.auto_scroll
+    }
+
+    /// Returns the metadata value attached to the screen for the key, if any
+    pub fn get_metadata(&self, key: &str) -> Option<&str> {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get(key))
+            .map(String::as_str)
+    }
 }
 
 /// A struct to manage map of Screen
 ///
-/// This `struct` is created by the [`Map::new()`] function. See its documentation for more.
-#[derive(Debug, PartialEq)]
+/// This `struct` is created by the [`Map::new()`] or [`Map::try_new()`] functions. See its documentation for more.
+///
+/// A map can be serialized, e.g. to JSON or RON. Deserialization checks the map is still valid:
+/// rows of the same length, a size matching its screens, screens whose indices and ranges match
+/// their place in the map, and exactly one start screen.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "MapData")]
 pub struct Map {
     width: usize,
     height: usize,
     screen_width: usize,
     screen_height: usize,
     data: Vec<Vec<Screen>>,
+    camera_policy: CameraPolicy,
+    /// Indices of the locked screens unlocked during the game, not part of the layout
+    #[serde(skip)]
+    unlocked_screens: HashSet<(usize, usize)>,
+}
+
+/// Unchecked [`Map`] used for deserialization
+#[derive(Deserialize)]
+struct MapData {
+    width: usize,
+    height: usize,
+    screen_width: usize,
+    screen_height: usize,
+    data: Vec<Vec<Screen>>,
+    #[serde(default)]
+    camera_policy: CameraPolicy,
+}
+
+impl TryFrom<MapData> for Map {
+    type Error = ScreenMapError;
+
+    fn try_from(map: MapData) -> Result<Self, Self::Error> {
+        let columns = map.data.first().ok_or(ScreenMapError::Empty)?.len();
+        if let Some((row, found)) = map
+            .data
+            .iter()
+            .map(Vec::len)
+            .enumerate()
+            .find(|(_, found)| *found != columns)
+        {
+            return Err(ScreenMapError::UnevenRows {
+                row,
+                expected: columns,
+                found,
+            });
+        }
+
+        let found = (map.width, map.height);
+        let expected = (
+            columns * map.screen_width,
+            map.data.len() * map.screen_height,
+        );
+        if found != expected {
+            return Err(ScreenMapError::SizeMismatch { expected, found });
+        }
+
+        for (row, screens) in map.data.iter().enumerate() {
+            for (column, screen) in screens.iter().enumerate() {
+                let (x_range, y_range) = screen_ranges(
+                    column,
+                    row,
+                    (map.width, map.height),
+                    (map.screen_width, map.screen_height),
+                );
+                if screen.get_indices() != (column, row)
+                    || screen.x_range != x_range
+                    || screen.y_range != y_range
+                {
+                    return Err(ScreenMapError::MisplacedScreen { row, column });
+                }
+            }
+        }
+
+        check_start_screen(&map.data)?;
+
+        Ok(Self {
+            width: map.width,
+            height: map.height,
+            screen_width: map.screen_width,
+            screen_height: map.screen_height,
+            data: map.data,
+            camera_policy: map.camera_policy,
+            unlocked_screens: HashSet::new(),
+        })
+    }
+}
+
+/// Returns the ranges in pixels of the screen at index (index_x, index_y)
+fn screen_ranges(
+    index_x: usize,
+    index_y: usize,
+    (map_width, map_height): (usize, usize),
+    (screen_width, screen_height): (usize, usize),
+) -> (Range<f32>, Range<f32>) {
+    let horiz_center = map_width / 2;
+    let vert_center = map_height / 2;
+
+    let x_range = (index_x * screen_width) as f32 - horiz_center as f32
+        ..(index_x * screen_width + screen_width) as f32 - horiz_center as f32;
+    let y_range =
+        (map_height as f32 - (index_y * screen_height + screen_height) as f32 - vert_center as f32)
+            + 1f32
+            ..(map_height as f32 - (index_y * screen_height) as f32 - vert_center as f32) + 1f32;
+    (x_range, y_range)
+}
+
+/// Checks there is exactly one start screen
+fn check_start_screen(data: &[Vec<Screen>]) -> Result<(), ScreenMapError> {
+    let mut start_screens = data
+        .iter()
+        .flatten()
+        .filter(|screen| screen.start_screen)
+        .map(|screen| (screen.y_index, screen.x_index));
+    match (start_screens.next(), start_screens.next()) {
+        (None, _) => Err(ScreenMapError::MissingStartScreen),
+        (Some(first), Some(second)) => Err(ScreenMapError::MultipleStartScreens { first, second }),
+        (Some(_), None) => Ok(()),
+    }
 }
 
 impl Map {
@@ -99,9 +496,18 @@ impl Map {
     /// - 'S' start screen
     /// - 'F' fixed screen, smooth transition
     /// - 'H' fixed screen, hard transition
+    /// - 'B' fixed screen, fade to black transition
+    /// - 'W' fixed screen, slide transition
+    /// - 'I' fixed screen, iris transition
+    /// - 'L' locked screen, the camera is held until the screen is unlocked
+    /// - '-' screen where the camera only scrolls horizontally
+    /// - '|' screen where the camera only scrolls vertically
+    /// - '>', '<', '^', 'v' auto-scroll screen, the camera moves by itself in the arrow direction
     ///
     /// Transitions:
     /// - Smooth transition is the default
+    /// - Animated transitions (fade, slide and iris) are played when entering or leaving the
+    ///   screen, their duration can be changed with [`Map::set_screen_transition()`]
     ///
     /// # Example
     ///
@@ -137,101 +543,169 @@ impl Map {
     ///      false
     ///  );
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the screen map is not valid, see [`Map::try_new()`] for a fallible version.
     pub fn new(screen_map: &str, screen_width: usize, screen_height: usize) -> Self {
-        let vert_size = screen_map.split('\n').count() * screen_height;
-        let vert_center = vert_size / 2;
-        let horiz_size = screen_map.split('\n').next().unwrap().len() * screen_width;
-        let horiz_center = horiz_size / 2;
+        match Self::try_new(screen_map, screen_width, screen_height) {
+            Ok(map) => map,
+            Err(error) => panic!("Invalid screen map: {error}"),
+        }
+    }
+
+    /// Creates a map of Screen, returning an error if the screen map is not valid
+    ///
+    /// A valid screen map has rows of the same length, only uses the cells described in
+    /// [`Map::new()`] and has exactly one start screen.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use screen_map::Map;
+    ///  use screen_map::ScreenMapError;
+    ///
+    ///  let screen_width = 1280;
+    ///  let screen_height = 720;
+    ///
+    ///  assert!(Map::try_new("XXO\nSOO", screen_width, screen_height).is_ok());
+    ///  assert_eq!(
+    ///      Map::try_new("XXO\nSOA", screen_width, screen_height),
+    ///      Err(ScreenMapError::InvalidCell {
+    ///          cell: 'A',
+    ///          row: 1,
+    ///          column: 2
+    ///      })
+    ///  );
+    /// ```
+    pub fn try_new(
+        screen_map: &str,
+        screen_width: usize,
+        screen_height: usize,
+    ) -> Result<Self, ScreenMapError> {
+        if screen_map.is_empty() {
+            return Err(ScreenMapError::Empty);
+        }
+
+        let rows = screen_map.split('\n').collect::<Vec<_>>();
+        let expected = rows[0].chars().count();
+        if let Some((row, found)) = rows
+            .iter()
+            .map(|horiz_screen| horiz_screen.chars().count())
+            .enumerate()
+            .find(|(_, found)| *found != expected)
+        {
+            return Err(ScreenMapError::UnevenRows {
+                row,
+                expected,
+                found,
+            });
+        }
 
-        let data = screen_map
-            .split('\n')
+        let vert_size = rows.len() * screen_height;
+        let horiz_size = expected * screen_width;
+
+        let data = rows
+            .iter()
             .enumerate()
             .map(|(cell_vert_index, horiz_screen)| {
                 horiz_screen
                     .chars()
                     .enumerate()
                     .map(|(cell_horiz_index, screen_cell)| {
-                        let x_range = (cell_horiz_index * screen_width) as f32 - horiz_center as f32
-                            ..(cell_horiz_index * screen_width + screen_width) as f32
-                                - horiz_center as f32;
-                        let y_range = (vert_size as f32
-                            - (cell_vert_index * screen_height + screen_height) as f32
-                            - vert_center as f32)
-                            + 1f32
-                            ..(vert_size as f32
-                                - (cell_vert_index * screen_height) as f32
-                                - vert_center as f32)
-                                + 1f32;
-                        if screen_cell == 'X' {
-                            Screen {
-                                x_range,
-                                y_range,
-                                x_index: cell_horiz_index,
-                                y_index: cell_vert_index,
-                                start_screen: false,
-                                allowed_screen: false,
-                                fixed_screen: false,
-                                transition: Transition::Smooth,
+                        let (x_range, y_range) = screen_ranges(
+                            cell_horiz_index,
+                            cell_vert_index,
+                            (horiz_size, vert_size),
+                            (screen_width, screen_height),
+                        );
+                        let mut screen = Screen {
+                            x_range,
+                            y_range,
+                            x_index: cell_horiz_index,
+                            y_index: cell_vert_index,
+                            start_screen: false,
+                            allowed_screen: true,
+                            fixed_screen: false,
+                            transition: Transition::Smooth,
+                            camera_policy: None,
+                            locked_screen: false,
+                            scroll_axis: ScrollAxis::Both,
+                            auto_scroll: None,
+                            metadata: None,
+                        };
+                        match screen_cell {
+                            'X' => screen.allowed_screen = false,
+                            'O' => {}
+                            'S' => screen.start_screen = true,
+                            'F' => screen.fixed_screen = true,
+                            'H' => {
+                                screen.fixed_screen = true;
+                                screen.transition = Transition::Hard;
                             }
-                        } else if screen_cell == 'O' {
-                            Screen {
-                                x_range,
-                                y_range,
-                                x_index: cell_horiz_index,
-                                y_index: cell_vert_index,
-                                start_screen: false,
-                                allowed_screen: true,
-                                fixed_screen: false,
-                                transition: Transition::Smooth,
+                            'B' => {
+                                screen.fixed_screen = true;
+                                screen.transition = Transition::Fade(FADE_DURATION);
                             }
-                        } else if screen_cell == 'S' {
-                            Screen {
-                                x_range,
-                                y_range,
-                                x_index: cell_horiz_index,
-                                y_index: cell_vert_index,
-                                start_screen: true,
-                                allowed_screen: true,
-                                fixed_screen: false,
-                                transition: Transition::Smooth,
+                            'W' => {
+                                screen.fixed_screen = true;
+                                screen.transition = Transition::Slide(SLIDE_DURATION);
                             }
-                        } else if screen_cell == 'F' {
-                            Screen {
-                                x_range,
-                                y_range,
-                                x_index: cell_horiz_index,
-                                y_index: cell_vert_index,
-                                start_screen: false,
-                                allowed_screen: true,
-                                fixed_screen: true,
-                                transition: Transition::Smooth,
+                            'I' => {
+                                screen.fixed_screen = true;
+                                screen.transition = Transition::Iris(IRIS_DURATION);
                             }
-                        } else if screen_cell == 'H' {
-                            Screen {
-                                x_range,
-                                y_range,
-                                x_index: cell_horiz_index,
-                                y_index: cell_vert_index,
-                                start_screen: false,
-                                allowed_screen: true,
-                                fixed_screen: true,
-                                transition: Transition::Hard,
+                            'L' => screen.locked_screen = true,
+                            '-' => screen.scroll_axis = ScrollAxis::Horizontal,
+                            '|' => screen.scroll_axis = ScrollAxis::Vertical,
+                            '>' => screen.auto_scroll = Some(Vec2::new(AUTO_SCROLL_SPEED, 0.0)),
+                            '<' => screen.auto_scroll = Some(Vec2::new(-AUTO_SCROLL_SPEED, 0.0)),
+                            '^' => screen.auto_scroll = Some(Vec2::new(0.0, AUTO_SCROLL_SPEED)),
+                            'v' => screen.auto_scroll = Some(Vec2::new(0.0, -AUTO_SCROLL_SPEED)),
+                            _ => {
+                                return Err(ScreenMapError::InvalidCell {
+                                    cell: screen_cell,
+                                    row: cell_vert_index,
+                                    column: cell_horiz_index,
+                                });
                             }
-                        } else {
-                            unreachable!();
                         }
+                        Ok(screen)
                     })
-                    .collect::<Vec<_>>()
+                    .collect::<Result<Vec<_>, _>>()
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, _>>()?;
 
-        Self {
+        check_start_screen(&data)?;
+
+        Ok(Self {
             width: horiz_size,
             height: vert_size,
             screen_width,
             screen_height,
             data,
-        }
+            camera_policy: CameraPolicy::default(),
+            unlocked_screens: HashSet::new(),
+        })
+    }
+
+    /// Returns the size of the map (width, height) in pixels
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use screen_map::Map;
+    ///
+    ///  let screen_map = "XXO\nSOO";
+    ///  let screen_width = 1280;
+    ///  let screen_height = 720;
+    ///
+    ///  let map = Map::new(screen_map, screen_width, screen_height);
+    ///
+    ///  assert_eq!(map.get_size(), (3840, 1440));
+    /// ```
+    pub const fn get_size(&self) -> (usize, usize) {
+        (self.width, self.height)
     }
 
     /// Convert Tiled coordinates to bevy coordinates
@@ -465,6 +939,94 @@ impl Map {
     ///  );
     /// ```
     pub fn move_camera(&self, time: &Res<Time>, old_pos: Vec2, new_pos: Vec2) -> Vec2 {
+        self.move_camera_to_target(time, old_pos, &CameraTarget::from(new_pos))
+    }
+
+    /// Move the camera towards the target using the camera policy of the target screen
+    ///
+    /// See [`Map::get_camera_policy()`] for the policy selection and [`Map::move_camera()`] for
+    /// the screen boundaries.
+    ///
+    /// The screen containing the target can also constrain the camera:
+    /// - a locked screen holds the camera at its center
+    /// - a horizontal (or vertical) only screen keeps the camera at its center on the other axis
+    ///
+    /// On an auto-scroll screen, the camera moves by itself and the target is ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use screen_map::{CameraPolicy, CameraTarget, Map};
+    ///  use bevy::prelude::*;
+    ///
+    ///  let screen_map = "SOO";
+    ///  let screen_width = 1280;
+    ///  let screen_height = 720;
+    ///
+    ///  let mut world = World::default();
+    ///  let mut time: Time = Time::default();
+    ///
+    ///  time.advance_by(std::time::Duration::from_secs(1));
+    ///  world.insert_resource(time);
+    ///  let time = world.resource_ref::<Time>();
+    ///
+    ///  let mut map = Map::new(screen_map, screen_width, screen_height);
+    ///  map.set_camera_policy(CameraPolicy::DeadZone {
+    ///      half_width: 100.0,
+    ///      half_height: 100.0,
+    ///  });
+    ///  // TODO: Remove this unsafe
+    ///  // https://github.com/bevyengine/bevy/issues/16831
+    ///  let time = unsafe { std::mem::transmute::<Ref<'_, Time>, Res<'_, Time>>(time) };
+    ///
+    ///  // The target is in the dead zone, the camera doesn't move
+    ///  let target = CameraTarget::from(Vec2::new(50.0, 0.0));
+    ///  assert_eq!(
+    ///      map.move_camera_to_target(&time, Vec2::new(0.0, 0.0), &target),
+    ///      Vec2::new(0.0, 0.0)
+    ///  );
+    /// ```
+    pub fn move_camera_to_target(
+        &self,
+        time: &Res<Time>,
+        old_pos: Vec2,
+        target: &CameraTarget,
+    ) -> Vec2 {
+        // Auto-scroll screens drive the camera whatever the target does
+        if let Some(velocity) = self
+            .get_screen(old_pos, 0.0, 0.0)
+            .and_then(|screen| screen.auto_scroll)
+        {
+            let new_pos = old_pos + velocity * time.delta_secs();
+            return self.keep_camera_on_allowed_screens(old_pos, new_pos, new_pos);
+        }
+
+        let policy = self.get_camera_policy(target.position);
+        let mut new_pos = policy.get_goal(old_pos, target);
+
+        if let Some(screen) = self.get_screen(target.position, 0.0, 0.0) {
+            let screen_center = screen.get_center();
+            let (index_x, index_y) = screen.get_indices();
+            if self.is_screen_locked(index_x, index_y) {
+                new_pos = screen_center;
+            }
+            match screen.scroll_axis {
+                ScrollAxis::Both => {}
+                ScrollAxis::Horizontal => new_pos.y = screen_center.y,
+                ScrollAxis::Vertical => new_pos.x = screen_center.x,
+            }
+        }
+
+        let camera_pos = self.keep_camera_on_allowed_screens(old_pos, new_pos, target.position);
+
+        policy.smooth(time.delta_secs(), old_pos, camera_pos)
+    }
+
+    /// Keeps the camera edges on allowed screens while moving from `old_pos` to `new_pos`
+    ///
+    /// If the camera reaches the boundary of the allowed screen area, it sticks to the center of
+    /// the screen containing `anchor`.
+    fn keep_camera_on_allowed_screens(&self, old_pos: Vec2, new_pos: Vec2, anchor: Vec2) -> Vec2 {
         let mut camera_pos = old_pos;
         let direction = new_pos - old_pos;
 
@@ -507,7 +1069,7 @@ impl Map {
             camera_pos.x = new_pos.x;
         } else {
             // Stick the camera horizontally to the center of the screen
-            if let Some(screen) = self.get_screen(new_pos, 0.0, 0.0) {
+            if let Some(screen) = self.get_screen(anchor, 0.0, 0.0) {
                 let screen_center = screen.get_center();
                 camera_pos.x = screen_center.x;
             }
@@ -533,95 +1095,277 @@ impl Map {
             camera_pos.y = new_pos.y;
         } else {
             // Stick the camera vertically to the center of the screen
-            if let Some(screen) = self.get_screen(new_pos, 0.0, 0.0) {
+            if let Some(screen) = self.get_screen(anchor, 0.0, 0.0) {
                 let screen_center = screen.get_center();
                 camera_pos.y = screen_center.y;
             }
         }
 
-        // Smooth camera transition on x axis.
-        // TODO: review to enable on y axis
-        let smooth_factor = Vec2::new(SMOOTH_FACTOR_X, SMOOTH_FACTOR_Y);
-
-        camera_pos.x = old_pos
-            .x
-            .lerp(camera_pos.x, smooth_factor.x * time.delta_secs());
-        // camera_pos.y = old_pos
-        //     .y
-        //     .lerp(camera_pos.y, smooth_factor.y * time.delta_seconds());
-
         camera_pos
     }
 
-    /// Checks whether an edge of the camera's rectangle is visible on the screen.
-    ///
-    /// The `(usize, usize)` tuple represents the indices of the points defining the edge.
-    ///
-    /// Points are ordered clockwise
-    ///  p2 +------------+ p3
-    ///     |            |
-    ///     |            |
-    ///  p1 +------------+ p0
+    /// Returns the camera policy used when the target is at `point`
     ///
-    fn is_camera_edge_on_screen(&self, camera_points: &[Vec2], (p1, p2): (usize, usize)) -> bool {
-        self.get_screen(camera_points[p1], 0.0, 0.0).is_some()
-            && self.get_screen(camera_points[p2], 0.0, 0.0).is_some()
-            && self
-                .get_screen(camera_points[p1], 0.0, 0.0)
-                .unwrap()
-                .allowed_screen
-            && self
-                .get_screen(camera_points[p2], 0.0, 0.0)
-                .unwrap()
-                .allowed_screen
-    }
-
-    /// Returns the start screen
+    /// The policy of the screen containing the point is used if it has one, otherwise the policy
+    /// of the map.
     ///
     /// # Example
     ///
     /// ```rust
-    ///  use screen_map::Map;
-    ///  use screen_map::Screen;
+    ///  use screen_map::{CameraPolicy, Map};
     ///  use bevy::math::Vec2;
-    ///  let screen_map = "XXO\nSOO\nOXX";
+    ///
+    ///  let screen_map = "SOO";
     ///  let screen_width = 1280;
     ///  let screen_height = 720;
     ///
-    ///  let map = Map::new(screen_map, screen_width, screen_height);
+    ///  let mut map = Map::new(screen_map, screen_width, screen_height);
+    ///  map.set_camera_policy(CameraPolicy::PlatformSnap);
+    ///  map.set_screen_camera_policy(2, 0, Some(CameraPolicy::SmoothFollow));
     ///
-    ///  assert_eq!(
-    ///      map.get_start_screen().get_indices(),
-    ///      (
-    ///           0,
-    ///           1
-    ///      )
-    ///  );
+    ///  assert_eq!(map.get_camera_policy(Vec2::new(0.0, 0.0)), CameraPolicy::PlatformSnap);
+    ///  assert_eq!(map.get_camera_policy(Vec2::new(1280.0, 0.0)), CameraPolicy::SmoothFollow);
     /// ```
-    pub fn get_start_screen(&self) -> &Screen {
-        self.data
-            .iter()
-            .flatten()
-            .find(|screen| screen.start_screen)
-            .unwrap()
+    pub fn get_camera_policy(&self, point: Vec2) -> CameraPolicy {
+        self.get_screen(point, 0.0, 0.0)
+            .and_then(|screen| screen.camera_policy)
+            .unwrap_or(self.camera_policy)
     }
 
-    /// Returns the screen at index (index_x, index_y) or None if it doesn't exist
+    /// Sets the camera policy of the map
+    pub const fn set_camera_policy(&mut self, policy: CameraPolicy) {
+        self.camera_policy = policy;
+    }
+
+    /// Sets the camera policy of the screen at index (index_x, index_y)
+    ///
+    /// None makes the screen use the policy of the map. Returns `false` if the screen doesn't exist.
+    pub fn set_screen_camera_policy(
+        &mut self,
+        index_x: usize,
+        index_y: usize,
+        policy: Option<CameraPolicy>,
+    ) -> bool {
+        match self
+            .data
+            .get_mut(index_y)
+            .and_then(|row| row.get_mut(index_x))
+        {
+            Some(screen) => {
+                screen.camera_policy = policy;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets the metadata of the screen at index (index_x, index_y)
+    ///
+    /// Returns `false` if the screen doesn't exist.
     ///
     /// # Example
     ///
     /// ```rust
-    ///  use screen_map::Map;
-    ///  use screen_map::Screen;
-    ///  use bevy::math::Vec2;
-    ///  let screen_map = "XXO\nSOO\nOXX";
+    ///  use screen_map::{Map, ScreenMetadata};
+    ///
+    ///  let screen_map = "SO";
     ///  let screen_width = 1280;
     ///  let screen_height = 720;
     ///
-    ///  let map = Map::new(screen_map, screen_width, screen_height);
+    ///  let mut map = Map::new(screen_map, screen_width, screen_height);
     ///
-    ///  assert_eq!(
-    ///      map.get_screen_from_index(1, 1).unwrap().get_ranges(),
+    ///  let metadata = ScreenMetadata::from([("display_name".to_string(), "The cave".to_string())]);
+    ///  assert!(map.set_screen_metadata(1, 0, Some(metadata)));
+    ///
+    ///  let screen = map.get_screen_from_index(1, 0).unwrap();
+    ///  assert_eq!(screen.get_metadata("display_name"), Some("The cave"));
+    ///  assert_eq!(screen.get_metadata("music"), None);
+    ///  assert_eq!(map.get_screen_from_index(0, 0).unwrap().get_metadata("display_name"), None);
+    /// ```
+    pub fn set_screen_metadata(
+        &mut self,
+        index_x: usize,
+        index_y: usize,
+        metadata: Option<ScreenMetadata>,
+    ) -> bool {
+        match self
+            .data
+            .get_mut(index_y)
+            .and_then(|row| row.get_mut(index_x))
+        {
+            Some(screen) => {
+                screen.metadata = metadata;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets the transition of the screen at index (index_x, index_y)
+    ///
+    /// Returns `false` if the screen doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use screen_map::{Map, Transition};
+    ///  use std::time::Duration;
+    ///
+    ///  let screen_map = "SB";
+    ///  let screen_width = 1280;
+    ///  let screen_height = 720;
+    ///
+    ///  let mut map = Map::new(screen_map, screen_width, screen_height);
+    ///
+    ///  let transition = Transition::Fade(Duration::from_secs(2));
+    ///  assert!(map.set_screen_transition(1, 0, transition));
+    ///  assert_eq!(map.get_screen_from_index(1, 0).unwrap().get_transition(), transition);
+    /// ```
+    pub fn set_screen_transition(
+        &mut self,
+        index_x: usize,
+        index_y: usize,
+        transition: Transition,
+    ) -> bool {
+        match self
+            .data
+            .get_mut(index_y)
+            .and_then(|row| row.get_mut(index_x))
+        {
+            Some(screen) => {
+                screen.transition = transition;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Unlocks the locked screen at index (index_x, index_y)
+    ///
+    /// Returns `false` if there is no locked screen to unlock at this index.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use screen_map::Map;
+    ///
+    ///  let screen_map = "SLO";
+    ///  let screen_width = 1280;
+    ///  let screen_height = 720;
+    ///
+    ///  let mut map = Map::new(screen_map, screen_width, screen_height);
+    ///
+    ///  assert!(map.is_screen_locked(1, 0));
+    ///  assert!(map.unlock_screen(1, 0));
+    ///  assert!(!map.is_screen_locked(1, 0));
+    ///  assert!(map.get_screen_from_index(1, 0).unwrap().is_locked_screen());
+    ///  assert!(!map.unlock_screen(1, 0));
+    ///
+    ///  map.lock_screens();
+    ///  assert!(map.is_screen_locked(1, 0));
+    /// ```
+    pub fn unlock_screen(&mut self, index_x: usize, index_y: usize) -> bool {
+        self.is_screen_locked(index_x, index_y) && self.unlocked_screens.insert((index_x, index_y))
+    }
+
+    /// Returns `true` if the screen at index (index_x, index_y) is a locked screen that has not
+    /// been unlocked yet
+    pub fn is_screen_locked(&self, index_x: usize, index_y: usize) -> bool {
+        self.get_screen_from_index(index_x, index_y)
+            .is_some_and(Screen::is_locked_screen)
+            && !self.unlocked_screens.contains(&(index_x, index_y))
+    }
+
+    /// Locks again every locked screen of the map, e.g. when the level restarts
+    pub fn lock_screens(&mut self) {
+        self.unlocked_screens.clear();
+    }
+
+    /// Returns the indices (index_x, index_y) of the locked screens unlocked so far
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use screen_map::Map;
+    ///
+    ///  let mut map = Map::new("SLL", 1280, 720);
+    ///
+    ///  map.unlock_screen(2, 0);
+    ///  assert_eq!(map.unlocked_screens().collect::<Vec<_>>(), vec![(2, 0)]);
+    /// ```
+    pub fn unlocked_screens(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.unlocked_screens.iter().copied()
+    }
+
+    /// Checks whether an edge of the camera's rectangle is visible on the screen.
+    ///
+    /// The `(usize, usize)` tuple represents the indices of the points defining the edge.
+    ///
+    /// Points are ordered clockwise
+    ///  p2 +------------+ p3
+    ///     |            |
+    ///     |            |
+    ///  p1 +------------+ p0
+    ///
+    fn is_camera_edge_on_screen(&self, camera_points: &[Vec2], (p1, p2): (usize, usize)) -> bool {
+        self.get_screen(camera_points[p1], 0.0, 0.0).is_some()
+            && self.get_screen(camera_points[p2], 0.0, 0.0).is_some()
+            && self
+                .get_screen(camera_points[p1], 0.0, 0.0)
+                .unwrap()
+                .allowed_screen
+            && self
+                .get_screen(camera_points[p2], 0.0, 0.0)
+                .unwrap()
+                .allowed_screen
+    }
+
+    /// Returns the start screen
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use screen_map::Map;
+    ///  use screen_map::Screen;
+    ///  use bevy::math::Vec2;
+    ///  let screen_map = "XXO\nSOO\nOXX";
+    ///  let screen_width = 1280;
+    ///  let screen_height = 720;
+    ///
+    ///  let map = Map::new(screen_map, screen_width, screen_height);
+    ///
+    ///  assert_eq!(
+    ///      map.get_start_screen().get_indices(),
+    ///      (
+    ///           0,
+    ///           1
+    ///      )
+    ///  );
+    /// ```
+    pub fn get_start_screen(&self) -> &Screen {
+        self.data
+            .iter()
+            .flatten()
+            .find(|screen| screen.start_screen)
+            .expect("a map is always built with exactly one start screen")
+    }
+
+    /// Returns the screen at index (index_x, index_y) or None if it doesn't exist
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use screen_map::Map;
+    ///  use screen_map::Screen;
+    ///  use bevy::math::Vec2;
+    ///  let screen_map = "XXO\nSOO\nOXX";
+    ///  let screen_width = 1280;
+    ///  let screen_height = 720;
+    ///
+    ///  let map = Map::new(screen_map, screen_width, screen_height);
+    ///
+    ///  assert_eq!(
+    ///      map.get_screen_from_index(1, 1).unwrap().get_ranges(),
     ///      (
     ///           -640.0..640.0,
     ///           -359.0..361.0
@@ -635,6 +1379,221 @@ impl Map {
             .flatten()
             .find(|screen| screen.x_index == index_x && screen.y_index == index_y)
     }
+
+    /// Returns the allowed screens next to the screen at index (index_x, index_y)
+    ///
+    /// Neighbours are the screens above, on the right, below and on the left, in this order.
+    /// Diagonal screens are not neighbours as the camera can't scroll diagonally between them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use screen_map::Map;
+    ///
+    ///  let screen_map = "XXO\nSOO\nOXX";
+    ///  let screen_width = 1280;
+    ///  let screen_height = 720;
+    ///
+    ///  let map = Map::new(screen_map, screen_width, screen_height);
+    ///
+    ///  let neighbours = map
+    ///      .get_neighbour_screens(1, 1)
+    ///      .iter()
+    ///      .map(|screen| screen.get_indices())
+    ///      .collect::<Vec<_>>();
+    ///  assert_eq!(neighbours, vec![(2, 1), (0, 1)]);
+    /// ```
+    pub fn get_neighbour_screens(&self, index_x: usize, index_y: usize) -> Vec<&Screen> {
+        let above = index_y
+            .checked_sub(1)
+            .and_then(|y| self.get_screen_from_index(index_x, y));
+        let right = self.get_screen_from_index(index_x + 1, index_y);
+        let below = self.get_screen_from_index(index_x, index_y + 1);
+        let left = index_x
+            .checked_sub(1)
+            .and_then(|x| self.get_screen_from_index(x, index_y));
+
+        [above, right, below, left]
+            .into_iter()
+            .flatten()
+            .filter(|screen| screen.allowed_screen)
+            .collect()
+    }
+
+    /// Returns the groups of allowed screens connected to each other
+    ///
+    /// Groups are ordered by their first screen, screens are ordered from the top left.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use screen_map::Map;
+    ///
+    ///  let screen_map = "XXO\nSOO\nOXX";
+    ///  let screen_width = 1280;
+    ///  let screen_height = 720;
+    ///
+    ///  let map = Map::new(screen_map, screen_width, screen_height);
+    ///
+    ///  let components = map
+    ///      .get_connected_screens()
+    ///      .iter()
+    ///      .map(|component| component.iter().map(|screen| screen.get_indices()).collect())
+    ///      .collect::<Vec<Vec<_>>>();
+    ///  assert_eq!(components, vec![vec![(2, 0), (0, 1), (1, 1), (2, 1), (0, 2)]]);
+    /// ```
+    pub fn get_connected_screens(&self) -> Vec<Vec<&Screen>> {
+        let mut components: Vec<Vec<&Screen>> = Vec::new();
+
+        for screen in self.data.iter().flatten() {
+            if !screen.allowed_screen
+                || components
+                    .iter()
+                    .any(|component| component.contains(&screen))
+            {
+                continue;
+            }
+
+            let (index_x, index_y) = screen.get_indices();
+            let reached = self.explore(index_x, index_y);
+            components.push(
+                self.data
+                    .iter()
+                    .flatten()
+                    .filter(|screen| reached.contains_key(&screen.get_indices()))
+                    .collect(),
+            );
+        }
+
+        components
+    }
+
+    /// Returns `true` if the screen at index `to` can be reached from the screen at index `from`
+    ///
+    /// Both screens must be allowed screens.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use screen_map::Map;
+    ///
+    ///  let screen_map = "XXO\nSOX\nOXO";
+    ///  let screen_width = 1280;
+    ///  let screen_height = 720;
+    ///
+    ///  let map = Map::new(screen_map, screen_width, screen_height);
+    ///
+    ///  assert!(map.is_reachable((0, 1), (0, 2)));
+    ///  assert!(!map.is_reachable((0, 1), (2, 0)));
+    ///  assert!(!map.is_reachable((0, 1), (1, 0)));
+    /// ```
+    pub fn is_reachable(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        self.get_screen_path(from, to).is_some()
+    }
+
+    /// Returns the shortest path of screens from the screen at index `from` to the screen at
+    /// index `to`, both included, or None if `to` can't be reached
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use screen_map::Map;
+    ///
+    ///  let screen_map = "XXO\nSOO\nOXX";
+    ///  let screen_width = 1280;
+    ///  let screen_height = 720;
+    ///
+    ///  let map = Map::new(screen_map, screen_width, screen_height);
+    ///
+    ///  let path = map
+    ///      .get_screen_path((0, 2), (2, 0))
+    ///      .unwrap()
+    ///      .iter()
+    ///      .map(|screen| screen.get_indices())
+    ///      .collect::<Vec<_>>();
+    ///  assert_eq!(path, vec![(0, 2), (0, 1), (1, 1), (2, 1), (2, 0)]);
+    /// ```
+    pub fn get_screen_path(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+    ) -> Option<Vec<&Screen>> {
+        let previous = self.explore(from.0, from.1);
+
+        let mut path = vec![to];
+        let mut current = *previous.get(&to)?;
+        while let Some(indices) = current {
+            path.push(indices);
+            current = previous[&indices];
+        }
+
+        path.iter()
+            .rev()
+            .map(|(index_x, index_y)| self.get_screen_from_index(*index_x, *index_y))
+            .collect()
+    }
+
+    /// Returns the allowed screens that can't be reached from the start screen
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///  use screen_map::Map;
+    ///
+    ///  let screen_map = "XXO\nSOX\nOXX";
+    ///  let screen_width = 1280;
+    ///  let screen_height = 720;
+    ///
+    ///  let map = Map::new(screen_map, screen_width, screen_height);
+    ///
+    ///  let unreachable = map.get_unreachable_screens();
+    ///  assert_eq!(unreachable.len(), 1);
+    ///  assert_eq!(unreachable[0].get_indices(), (2, 0));
+    /// ```
+    pub fn get_unreachable_screens(&self) -> Vec<&Screen> {
+        let (start_x, start_y) = self.get_start_screen().get_indices();
+        let reachable = self.explore(start_x, start_y);
+
+        self.data
+            .iter()
+            .flatten()
+            .filter(|screen| {
+                screen.allowed_screen && !reachable.contains_key(&screen.get_indices())
+            })
+            .collect()
+    }
+
+    /// Breadth first search of the allowed screens from the screen at index (index_x, index_y)
+    ///
+    /// Returns the indices of every reached screen with the indices of the screen it was reached
+    /// from (None for the first one).
+    fn explore(
+        &self,
+        index_x: usize,
+        index_y: usize,
+    ) -> HashMap<(usize, usize), Option<(usize, usize)>> {
+        let mut previous = HashMap::new();
+        if !self
+            .get_screen_from_index(index_x, index_y)
+            .is_some_and(|screen| screen.allowed_screen)
+        {
+            return previous;
+        }
+
+        previous.insert((index_x, index_y), None);
+        let mut queue = VecDeque::from([(index_x, index_y)]);
+        while let Some((index_x, index_y)) = queue.pop_front() {
+            for neighbour in self.get_neighbour_screens(index_x, index_y) {
+                let indices = neighbour.get_indices();
+                if let Entry::Vacant(entry) = previous.entry(indices) {
+                    entry.insert(Some((index_x, index_y)));
+                    queue.push_back(indices);
+                }
+            }
+        }
+
+        previous
+    }
 }
 
 #[cfg(test)]
@@ -667,6 +1626,11 @@ mod tests {
                             allowed_screen: false,
                             fixed_screen: false,
                             transition: Transition::Smooth,
+                            camera_policy: None,
+                            locked_screen: false,
+                            scroll_axis: ScrollAxis::Both,
+                            auto_scroll: None,
+                            metadata: None,
                         },
                         Screen {
                             x_range: -640.0..640.0,
@@ -677,6 +1641,11 @@ mod tests {
                             allowed_screen: true,
                             fixed_screen: false,
                             transition: Transition::Smooth,
+                            camera_policy: None,
+                            locked_screen: false,
+                            scroll_axis: ScrollAxis::Both,
+                            auto_scroll: None,
+                            metadata: None,
                         },
                         Screen {
                             x_range: 640.0..1920.0,
@@ -687,6 +1656,11 @@ mod tests {
                             allowed_screen: false,
                             fixed_screen: false,
                             transition: Transition::Smooth,
+                            camera_policy: None,
+                            locked_screen: false,
+                            scroll_axis: ScrollAxis::Both,
+                            auto_scroll: None,
+                            metadata: None,
                         },
                     ],
                     vec![
@@ -699,6 +1673,11 @@ mod tests {
                             allowed_screen: true,
                             fixed_screen: false,
                             transition: Transition::Smooth,
+                            camera_policy: None,
+                            locked_screen: false,
+                            scroll_axis: ScrollAxis::Both,
+                            auto_scroll: None,
+                            metadata: None,
                         },
                         Screen {
                             x_range: -640.0..640.0,
@@ -709,6 +1688,11 @@ mod tests {
                             allowed_screen: true,
                             fixed_screen: false,
                             transition: Transition::Smooth,
+                            camera_policy: None,
+                            locked_screen: false,
+                            scroll_axis: ScrollAxis::Both,
+                            auto_scroll: None,
+                            metadata: None,
                         },
                         Screen {
                             x_range: 640.0..1920.0,
@@ -719,6 +1703,11 @@ mod tests {
                             allowed_screen: true,
                             fixed_screen: false,
                             transition: Transition::Smooth,
+                            camera_policy: None,
+                            locked_screen: false,
+                            scroll_axis: ScrollAxis::Both,
+                            auto_scroll: None,
+                            metadata: None,
                         },
                     ],
                     vec![
@@ -731,6 +1720,11 @@ mod tests {
                             allowed_screen: false,
                             fixed_screen: false,
                             transition: Transition::Smooth,
+                            camera_policy: None,
+                            locked_screen: false,
+                            scroll_axis: ScrollAxis::Both,
+                            auto_scroll: None,
+                            metadata: None,
                         },
                         Screen {
                             x_range: -640.0..640.0,
@@ -741,6 +1735,11 @@ mod tests {
                             allowed_screen: false,
                             fixed_screen: false,
                             transition: Transition::Smooth,
+                            camera_policy: None,
+                            locked_screen: false,
+                            scroll_axis: ScrollAxis::Both,
+                            auto_scroll: None,
+                            metadata: None,
                         },
                         Screen {
                             x_range: 640.0..1920.0,
@@ -751,9 +1750,16 @@ mod tests {
                             allowed_screen: false,
                             fixed_screen: false,
                             transition: Transition::Smooth,
+                            camera_policy: None,
+                            locked_screen: false,
+                            scroll_axis: ScrollAxis::Both,
+                            auto_scroll: None,
+                            metadata: None,
                         },
                     ],
-                ]
+                ],
+                camera_policy: CameraPolicy::SmoothFollow,
+                unlocked_screens: HashSet::new(),
             }
         );
     }
@@ -829,6 +1835,11 @@ mod tests {
                 allowed_screen: true,
                 fixed_screen: false,
                 transition: Transition::Smooth,
+                camera_policy: None,
+                locked_screen: false,
+                scroll_axis: ScrollAxis::Both,
+                auto_scroll: None,
+                metadata: None,
             })
         )
     }
@@ -1036,6 +2047,11 @@ mod tests {
                 allowed_screen: true,
                 fixed_screen: true,
                 transition: Transition::Hard,
+                camera_policy: None,
+                locked_screen: false,
+                scroll_axis: ScrollAxis::Both,
+                auto_scroll: None,
+                metadata: None,
             })
         );
 
@@ -1050,8 +2066,672 @@ mod tests {
                 allowed_screen: false,
                 fixed_screen: false,
                 transition: Transition::Smooth,
+                camera_policy: None,
+                locked_screen: false,
+                scroll_axis: ScrollAxis::Both,
+                auto_scroll: None,
+                metadata: None,
             })
         );
         assert_eq!(map.get_screen_from_index(2, 3), None);
     }
+
+    #[test]
+    fn test_try_new() {
+        let screen_width = 1280;
+        let screen_height = 720;
+
+        assert_eq!(
+            Map::try_new("XOX\nSOO\nXXX", screen_width, screen_height),
+            Ok(Map::new("XOX\nSOO\nXXX", screen_width, screen_height))
+        );
+        assert_eq!(
+            Map::try_new("", screen_width, screen_height),
+            Err(ScreenMapError::Empty)
+        );
+        assert_eq!(
+            Map::try_new("XOX\nSO?\nXXX", screen_width, screen_height),
+            Err(ScreenMapError::InvalidCell {
+                cell: '?',
+                row: 1,
+                column: 2
+            })
+        );
+        assert_eq!(
+            Map::try_new("XOX\nSOO\nXX", screen_width, screen_height),
+            Err(ScreenMapError::UnevenRows {
+                row: 2,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            Map::try_new("XOX\nSOO\n", screen_width, screen_height),
+            Err(ScreenMapError::UnevenRows {
+                row: 2,
+                expected: 3,
+                found: 0
+            })
+        );
+        assert_eq!(
+            Map::try_new("XOX\nOOO\nXXX", screen_width, screen_height),
+            Err(ScreenMapError::MissingStartScreen)
+        );
+        assert_eq!(
+            Map::try_new("XOS\nSOO\nXXX", screen_width, screen_height),
+            Err(ScreenMapError::MultipleStartScreens {
+                first: (0, 2),
+                second: (1, 0)
+            })
+        );
+    }
+
+    #[test]
+    fn test_screen_map_error_display() {
+        assert_eq!(
+            ScreenMapError::InvalidCell {
+                cell: 'A',
+                row: 0,
+                column: 4
+            }
+            .to_string(),
+            "Unknown screen cell 'A' at row 0, column 4"
+        );
+        assert_eq!(
+            ScreenMapError::MultipleStartScreens {
+                first: (0, 2),
+                second: (1, 0)
+            }
+            .to_string(),
+            "Screen map has several start screens ('S'): row 0, column 2 and row 1, column 0"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid screen map: Screen map has no start screen ('S')")]
+    fn test_new_panics_on_invalid_map() {
+        Map::new("XO", 1280, 720);
+    }
+
+    fn indices(screens: &[&Screen]) -> Vec<(usize, usize)> {
+        screens.iter().map(|screen| screen.get_indices()).collect()
+    }
+
+    #[test]
+    fn test_get_neighbour_screens() {
+        let screen_map = "XOX\nSOO\nXOF";
+        let screen_width = 1280;
+        let screen_height = 720;
+
+        let map = Map::new(screen_map, screen_width, screen_height);
+
+        assert_eq!(
+            indices(&map.get_neighbour_screens(1, 1)),
+            vec![(1, 0), (2, 1), (1, 2), (0, 1)]
+        );
+        assert_eq!(indices(&map.get_neighbour_screens(0, 1)), vec![(1, 1)]);
+        assert_eq!(
+            indices(&map.get_neighbour_screens(2, 2)),
+            vec![(2, 1), (1, 2)]
+        );
+        // Not allowed screens have neighbours too
+        assert_eq!(
+            indices(&map.get_neighbour_screens(0, 0)),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(indices(&map.get_neighbour_screens(5, 5)), vec![]);
+    }
+
+    #[test]
+    fn test_get_connected_screens() {
+        let screen_map = "SOXO\nXXXO\nOXOX";
+        let screen_width = 1280;
+        let screen_height = 720;
+
+        let map = Map::new(screen_map, screen_width, screen_height);
+
+        let components = map.get_connected_screens();
+        assert_eq!(components.len(), 4);
+        assert_eq!(indices(&components[0]), vec![(0, 0), (1, 0)]);
+        assert_eq!(indices(&components[1]), vec![(3, 0), (3, 1)]);
+        assert_eq!(indices(&components[2]), vec![(0, 2)]);
+        assert_eq!(indices(&components[3]), vec![(2, 2)]);
+    }
+
+    #[test]
+    fn test_is_reachable() {
+        let screen_map = "SOXO\nXOXO\nXOOO";
+        let screen_width = 1280;
+        let screen_height = 720;
+
+        let map = Map::new(screen_map, screen_width, screen_height);
+
+        assert!(map.is_reachable((0, 0), (3, 0)));
+        assert!(map.is_reachable((3, 0), (0, 0)));
+        assert!(map.is_reachable((0, 0), (0, 0)));
+        // 'X' screens
+        assert!(!map.is_reachable((0, 0), (2, 0)));
+        assert!(!map.is_reachable((2, 0), (0, 0)));
+        // Out of the map
+        assert!(!map.is_reachable((0, 0), (4, 0)));
+    }
+
+    #[test]
+    fn test_get_screen_path() {
+        let screen_map = "SOXO\nXOXO\nXOOO";
+        let screen_width = 1280;
+        let screen_height = 720;
+
+        let map = Map::new(screen_map, screen_width, screen_height);
+
+        assert_eq!(
+            indices(&map.get_screen_path((0, 0), (3, 0)).unwrap()),
+            vec![
+                (0, 0),
+                (1, 0),
+                (1, 1),
+                (1, 2),
+                (2, 2),
+                (3, 2),
+                (3, 1),
+                (3, 0)
+            ]
+        );
+        assert_eq!(
+            indices(&map.get_screen_path((1, 1), (1, 1)).unwrap()),
+            vec![(1, 1)]
+        );
+        assert_eq!(map.get_screen_path((0, 0), (2, 0)), None);
+        assert_eq!(map.get_screen_path((0, 0), (4, 0)), None);
+    }
+
+    #[test]
+    fn test_get_unreachable_screens() {
+        let screen_map = "SOXO\nXXXO\nOXOX";
+        let screen_width = 1280;
+        let screen_height = 720;
+
+        let map = Map::new(screen_map, screen_width, screen_height);
+
+        assert_eq!(
+            indices(&map.get_unreachable_screens()),
+            vec![(3, 0), (3, 1), (0, 2), (2, 2)]
+        );
+
+        let map = Map::new("SHFXF\nXOFOO", screen_width, screen_height);
+        assert_eq!(indices(&map.get_unreachable_screens()), vec![]);
+    }
+
+    fn move_camera_with_policy(
+        policy: CameraPolicy,
+        old_pos: Vec2,
+        target: CameraTarget,
+        delta_secs: f32,
+    ) -> Vec2 {
+        let mut map = Map::new("OOO\nOSO\nOOO", 1280, 720);
+        map.set_camera_policy(policy);
+
+        let mut world = World::default();
+        let mut time: Time = Time::default();
+
+        time.advance_by(std::time::Duration::from_secs_f32(delta_secs));
+        world.insert_resource(time);
+        let time = world.resource_ref::<Time>();
+
+        // TODO: Remove this unsafe
+        // https://github.com/bevyengine/bevy/issues/16831
+        let time = unsafe { std::mem::transmute::<Ref<'_, Time>, Res<'_, Time>>(time) };
+
+        map.move_camera_to_target(&time, old_pos, &target)
+    }
+
+    #[test]
+    fn test_camera_policy_smooth_follow() {
+        let policy = CameraPolicy::SmoothFollow;
+
+        // Same behaviour as move_camera
+        assert_eq!(
+            move_camera_with_policy(
+                policy,
+                Vec2::new(-1280.0, 0.0),
+                Vec2::new(-1240.0, 0.0).into(),
+                1.0
+            ),
+            Vec2::new(-1240.0, 0.0)
+        );
+        // Half of the way on x axis, no smoothing on y axis
+        assert_eq!(
+            move_camera_with_policy(
+                policy,
+                Vec2::new(-1280.0, 0.0),
+                Vec2::new(-1240.0, -250.0).into(),
+                0.5
+            ),
+            Vec2::new(-1260.0, -250.0)
+        );
+    }
+
+    #[test]
+    fn test_camera_policy_dead_zone() {
+        let policy = CameraPolicy::DeadZone {
+            half_width: 100.0,
+            half_height: 50.0,
+        };
+
+        // In the dead zone
+        assert_eq!(
+            move_camera_with_policy(
+                policy,
+                Vec2::new(0.0, 0.0),
+                Vec2::new(80.0, -40.0).into(),
+                1.0
+            ),
+            Vec2::new(0.0, 0.0)
+        );
+        // Out of the dead zone on the right, the target stays on the zone edge
+        assert_eq!(
+            move_camera_with_policy(
+                policy,
+                Vec2::new(0.0, 0.0),
+                Vec2::new(130.0, 0.0).into(),
+                1.0
+            ),
+            Vec2::new(30.0, 0.0)
+        );
+        // Out of the dead zone on the left and below
+        assert_eq!(
+            move_camera_with_policy(
+                policy,
+                Vec2::new(0.0, 0.0),
+                Vec2::new(-130.0, -80.0).into(),
+                1.0
+            ),
+            Vec2::new(-30.0, -30.0)
+        );
+        // Screen boundaries still apply
+        assert_eq!(
+            move_camera_with_policy(
+                policy,
+                Vec2::new(-1280.0, 0.0),
+                Vec2::new(-1400.0, 0.0).into(),
+                1.0
+            ),
+            Vec2::new(-1280.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_camera_policy_look_ahead() {
+        let policy = CameraPolicy::LookAhead { distance: 200.0 };
+
+        // Moving right
+        assert_eq!(
+            move_camera_with_policy(
+                policy,
+                Vec2::new(0.0, 0.0),
+                CameraTarget {
+                    position: Vec2::new(50.0, 0.0),
+                    velocity: Vec2::new(300.0, 0.0),
+                    grounded: true,
+                },
+                1.0
+            ),
+            Vec2::new(250.0, 0.0)
+        );
+        // Moving left
+        assert_eq!(
+            move_camera_with_policy(
+                policy,
+                Vec2::new(0.0, 0.0),
+                CameraTarget {
+                    position: Vec2::new(-50.0, 0.0),
+                    velocity: Vec2::new(-300.0, 0.0),
+                    grounded: true,
+                },
+                1.0
+            ),
+            Vec2::new(-250.0, 0.0)
+        );
+        // Not moving
+        assert_eq!(
+            move_camera_with_policy(
+                policy,
+                Vec2::new(0.0, 0.0),
+                Vec2::new(50.0, 0.0).into(),
+                1.0
+            ),
+            Vec2::new(50.0, 0.0)
+        );
+        // Looking ahead out of the allowed screens sticks to the screen center
+        assert_eq!(
+            move_camera_with_policy(
+                policy,
+                Vec2::new(-1280.0, 0.0),
+                CameraTarget {
+                    position: Vec2::new(-1290.0, 0.0),
+                    velocity: Vec2::new(-300.0, 0.0),
+                    grounded: true,
+                },
+                1.0
+            ),
+            Vec2::new(-1280.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_camera_policy_platform_snap() {
+        let policy = CameraPolicy::PlatformSnap;
+
+        // Jumping, the camera only follows on x axis
+        assert_eq!(
+            move_camera_with_policy(
+                policy,
+                Vec2::new(0.0, 0.0),
+                CameraTarget {
+                    position: Vec2::new(40.0, 120.0),
+                    velocity: Vec2::new(300.0, 400.0),
+                    grounded: false,
+                },
+                1.0
+            ),
+            Vec2::new(40.0, 0.0)
+        );
+        // Landed on a platform
+        assert_eq!(
+            move_camera_with_policy(
+                policy,
+                Vec2::new(0.0, 0.0),
+                CameraTarget {
+                    position: Vec2::new(40.0, 120.0),
+                    velocity: Vec2::ZERO,
+                    grounded: true,
+                },
+                1.0
+            ),
+            Vec2::new(40.0, 120.0)
+        );
+        // Smoothing on both axes
+        assert_eq!(
+            move_camera_with_policy(
+                policy,
+                Vec2::new(0.0, 0.0),
+                Vec2::new(40.0, 120.0).into(),
+                0.5
+            ),
+            Vec2::new(20.0, 60.0)
+        );
+    }
+
+    #[test]
+    fn test_get_camera_policy() {
+        let mut map = Map::new("XXO\nSOO\nOXX", 1280, 720);
+
+        assert_eq!(
+            map.get_camera_policy(Vec2::new(0.0, 0.0)),
+            CameraPolicy::SmoothFollow
+        );
+
+        map.set_camera_policy(CameraPolicy::PlatformSnap);
+        assert!(map.set_screen_camera_policy(
+            1,
+            1,
+            Some(CameraPolicy::LookAhead { distance: 10.0 })
+        ));
+        assert!(!map.set_screen_camera_policy(3, 1, Some(CameraPolicy::SmoothFollow)));
+
+        assert_eq!(
+            map.get_camera_policy(Vec2::new(0.0, 0.0)),
+            CameraPolicy::LookAhead { distance: 10.0 }
+        );
+        assert_eq!(
+            map.get_camera_policy(Vec2::new(-1280.0, 0.0)),
+            CameraPolicy::PlatformSnap
+        );
+        // Out of the map
+        assert_eq!(
+            map.get_camera_policy(Vec2::new(5000.0, 0.0)),
+            CameraPolicy::PlatformSnap
+        );
+
+        assert!(map.set_screen_camera_policy(1, 1, None));
+        assert_eq!(
+            map.get_camera_policy(Vec2::new(0.0, 0.0)),
+            CameraPolicy::PlatformSnap
+        );
+    }
+
+    #[test]
+    fn test_camera_policy_from_str() {
+        assert_eq!(
+            "dead_zone".parse(),
+            Ok(CameraPolicy::DeadZone {
+                half_width: DEAD_ZONE_HALF_WIDTH,
+                half_height: DEAD_ZONE_HALF_HEIGHT
+            })
+        );
+        assert_eq!(
+            "look_ahead".parse(),
+            Ok(CameraPolicy::LookAhead {
+                distance: LOOK_AHEAD_DISTANCE
+            })
+        );
+        assert_eq!(
+            "Smooth".parse::<CameraPolicy>(),
+            Err(ScreenMapError::UnknownCameraPolicy("Smooth".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_new_screen_cells() {
+        let map = Map::new("SL-|\n><^v", 1280, 720);
+
+        let screen = |index_x, index_y| map.get_screen_from_index(index_x, index_y).unwrap();
+
+        assert!(screen(1, 0).is_locked_screen());
+        assert!(screen(1, 0).is_allowed_screen());
+        assert!(!screen(0, 0).is_locked_screen());
+
+        assert_eq!(screen(1, 0).get_scroll_axis(), ScrollAxis::Both);
+        assert_eq!(screen(2, 0).get_scroll_axis(), ScrollAxis::Horizontal);
+        assert_eq!(screen(3, 0).get_scroll_axis(), ScrollAxis::Vertical);
+
+        assert_eq!(screen(2, 0).get_auto_scroll(), None);
+        assert_eq!(
+            screen(0, 1).get_auto_scroll(),
+            Some(Vec2::new(AUTO_SCROLL_SPEED, 0.0))
+        );
+        assert_eq!(
+            screen(1, 1).get_auto_scroll(),
+            Some(Vec2::new(-AUTO_SCROLL_SPEED, 0.0))
+        );
+        assert_eq!(
+            screen(2, 1).get_auto_scroll(),
+            Some(Vec2::new(0.0, AUTO_SCROLL_SPEED))
+        );
+        assert_eq!(
+            screen(3, 1).get_auto_scroll(),
+            Some(Vec2::new(0.0, -AUTO_SCROLL_SPEED))
+        );
+    }
+
+    #[test]
+    fn test_move_camera_on_constrained_screens() {
+        let screen_width = 1280;
+        let screen_height = 720;
+
+        let mut world = World::default();
+        let mut time: Time = Time::default();
+
+        time.advance_by(std::time::Duration::from_secs(1));
+        world.insert_resource(time);
+        let time = world.resource_ref::<Time>();
+
+        // TODO: Remove this unsafe
+        // https://github.com/bevyengine/bevy/issues/16831
+        let time = unsafe { std::mem::transmute::<Ref<'_, Time>, Res<'_, Time>>(time) };
+
+        // Locked screen in the middle, the camera is held at its center until unlocked
+        let mut map = Map::new("OOO\nSLO\nOOO", screen_width, screen_height);
+        assert_eq!(
+            map.move_camera(&time, Vec2::new(-100.0, 0.0), Vec2::new(300.0, 200.0)),
+            Vec2::new(0.0, 0.0)
+        );
+        map.unlock_screen(1, 1);
+        assert_eq!(
+            map.move_camera(&time, Vec2::new(-100.0, 0.0), Vec2::new(300.0, 200.0)),
+            Vec2::new(300.0, 200.0)
+        );
+
+        // Horizontal only screen in the middle
+        let map = Map::new("OOO\nS-O\nOOO", screen_width, screen_height);
+        assert_eq!(
+            map.move_camera(&time, Vec2::new(0.0, 0.0), Vec2::new(300.0, 200.0)),
+            Vec2::new(300.0, 0.0)
+        );
+
+        // Vertical only screen in the middle
+        let map = Map::new("OOO\nS|O\nOOO", screen_width, screen_height);
+        assert_eq!(
+            map.move_camera(&time, Vec2::new(0.0, 0.0), Vec2::new(300.0, 200.0)),
+            Vec2::new(0.0, 200.0)
+        );
+
+        // Auto-scroll screen in the middle, the target is ignored
+        let map = Map::new("OOO\nS>O\nOOO", screen_width, screen_height);
+        assert_eq!(
+            map.move_camera(&time, Vec2::new(0.0, 0.0), Vec2::new(-300.0, 200.0)),
+            Vec2::new(AUTO_SCROLL_SPEED, 0.0)
+        );
+
+        // Auto-scroll screen on the right edge, the camera stops at the screen center
+        let map = Map::new("S>", screen_width, screen_height);
+        assert_eq!(
+            map.move_camera(&time, Vec2::new(640.0, 0.0), Vec2::new(300.0, 0.0)),
+            Vec2::new(640.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_animated_transitions() {
+        let mut map = Map::new("SBWI", 1280, 720);
+
+        let transition = |map: &Map, index_x| {
+            map.get_screen_from_index(index_x, 0)
+                .unwrap()
+                .get_transition()
+        };
+
+        assert!(!transition(&map, 0).is_animated());
+        assert_eq!(transition(&map, 0).get_duration(), None);
+        for (index_x, expected) in [
+            (1, Transition::Fade(FADE_DURATION)),
+            (2, Transition::Slide(SLIDE_DURATION)),
+            (3, Transition::Iris(IRIS_DURATION)),
+        ] {
+            assert!(
+                map.get_screen_from_index(index_x, 0)
+                    .unwrap()
+                    .is_fixed_screen()
+            );
+            assert_eq!(transition(&map, index_x), expected);
+            assert!(expected.is_animated());
+        }
+
+        let slide = Transition::Slide(Duration::from_millis(250));
+        assert!(map.set_screen_transition(0, 0, slide));
+        assert_eq!(transition(&map, 0), slide);
+        assert_eq!(slide.get_duration(), Some(Duration::from_millis(250)));
+        assert!(!map.set_screen_transition(4, 0, slide));
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let mut map = Map::new("XLB\nSO>", 1280, 720);
+        map.set_camera_policy(CameraPolicy::LookAhead { distance: 150.0 });
+        map.set_screen_metadata(
+            1,
+            1,
+            Some(ScreenMetadata::from([
+                ("music".to_string(), "music_level01".to_string()),
+                ("display_name".to_string(), "Valley".to_string()),
+            ])),
+        );
+        let json = serde_json::to_string(&map).unwrap();
+        let output: Map = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(output, map);
+        assert_eq!(
+            output
+                .get_screen_from_index(1, 1)
+                .unwrap()
+                .get_metadata("display_name"),
+            Some("Valley")
+        );
+
+        // Unlocked screens are game state, they are not serialized
+        map.unlock_screen(1, 0);
+        let json = serde_json::to_string(&map).unwrap();
+        let output: Map = serde_json::from_str(&json).unwrap();
+        assert!(output.is_screen_locked(1, 0));
+    }
+
+    #[test]
+    fn test_deserialize_invalid_map() {
+        let map = Map::new("SO\nOO", 1280, 720);
+        let mut json = serde_json::to_value(&map).unwrap();
+
+        // Remove the start screen
+        json["data"][0][0]["start_screen"] = serde_json::Value::Bool(false);
+        let error = serde_json::from_value::<Map>(json.clone()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            ScreenMapError::MissingStartScreen.to_string()
+        );
+
+        // Remove a screen of the second row
+        json["data"][0][0]["start_screen"] = serde_json::Value::Bool(true);
+        json["data"][1].as_array_mut().unwrap().pop();
+        let error = serde_json::from_value::<Map>(json).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            ScreenMapError::UnevenRows {
+                row: 1,
+                expected: 2,
+                found: 1
+            }
+            .to_string()
+        );
+
+        // Change the size of the map
+        let mut json = serde_json::to_value(&map).unwrap();
+        json["width"] = serde_json::Value::from(1280);
+        let error = serde_json::from_value::<Map>(json).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            ScreenMapError::SizeMismatch {
+                expected: (2560, 1440),
+                found: (1280, 1440)
+            }
+            .to_string()
+        );
+
+        // Swap the indices of a screen
+        let mut json = serde_json::to_value(&map).unwrap();
+        json["data"][0][1]["x_index"] = serde_json::Value::from(0);
+        json["data"][0][1]["y_index"] = serde_json::Value::from(1);
+        let error = serde_json::from_value::<Map>(json).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            ScreenMapError::MisplacedScreen { row: 0, column: 1 }.to_string()
+        );
+
+        // Move the range of a screen
+        let mut json = serde_json::to_value(&map).unwrap();
+        json["data"][1][1]["x_range"]["start"] = serde_json::Value::from(10.0);
+        let error = serde_json::from_value::<Map>(json).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            ScreenMapError::MisplacedScreen { row: 1, column: 1 }.to_string()
+        );
+    }
 }
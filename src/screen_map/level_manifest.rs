@@ -0,0 +1,48 @@
+// Description of the `levels.ron` manifest, read by the game and by `rockrun-lint`.
+//
+// The game loads the assets of the levels from it, see `helpers::level_manifest` of the game for
+// the format.
+
+use serde::Deserialize;
+
+/// Shaders that can be applied on a level
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum LevelShaderKind {
+    MysteriousFog,
+}
+
+/// Area of a level in Tiled coordinates (origin at the top left, in pixels)
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub struct LevelArea {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// A shader applied on an area of a level
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct LevelShader {
+    pub kind: LevelShaderKind,
+    /// Hexadecimal sRGB color, e.g. "FFD700"
+    pub color: String,
+    pub area: LevelArea,
+}
+
+/// A level of the manifest, with the paths of its assets
+#[derive(Debug, Deserialize)]
+pub struct LevelDescription {
+    pub map: String,
+    #[serde(default)]
+    pub screen_layout: Option<String>,
+    pub music: String,
+    #[serde(default)]
+    pub shaders: Vec<LevelShader>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LevelManifestDescription {
+    pub levels: Vec<LevelDescription>,
+}
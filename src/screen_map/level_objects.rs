@@ -0,0 +1,358 @@
+// Objects of the level maps the game gives a meaning to by their name.
+//
+// The kind of trigger of a position sensor is parsed from its name (and its `SensorValues`):
+// `exitNN`, `checkpointNN`, `batNN`, `rockNN`, `pterodactylNN`, `pterodactyl_attackNN`,
+// `pterodactyl_wave_<count>_NN`, `volcanoNN` and `volcano_rumbleNN`.
+//
+// Platforms and water zones are read from the class and custom properties of their objects.
+//
+// Both the game and `rockrun-lint` use these parsers, the levels are checked as the game reads
+// them.
+
+use bevy::prelude::{Component, Vec2};
+use thiserror::Error;
+
+const PLATFORM_PROPERTY: &str = "platform";
+const BREATH_PROPERTY: &str = "breath";
+
+#[derive(Debug, Error, PartialEq)]
+pub enum TriggerError {
+    #[error("unknown trigger")]
+    Unknown,
+    #[error("invalid number of pterodactyls in the wave")]
+    InvalidWaveCount,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ObjectError {
+    #[error("unknown platform kind {0}")]
+    UnknownPlatformKind(String),
+    #[error("breath must be a positive number of seconds")]
+    InvalidBreath,
+}
+
+/// Variant of a platform of the Platforms layer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlatformKind {
+    /// Can be jumped through from below
+    OneWay,
+    /// Falls after the player stood on it
+    Crumbling,
+    /// Throws the player up
+    Bounce,
+}
+
+impl PlatformKind {
+    /// Parses the kind of a platform from the class of its object, or from its `platform`
+    /// property for objects without class
+    ///
+    /// Plain platforms have no kind.
+    pub fn from_object(
+        class: &str,
+        properties: &tiled::Properties,
+    ) -> Result<Option<Self>, ObjectError> {
+        let kind = match (class, properties.get(PLATFORM_PROPERTY)) {
+            ("", Some(tiled::PropertyValue::StringValue(kind))) => kind.as_str(),
+            (class, _) => class,
+        };
+
+        match kind {
+            "" => Ok(None),
+            "one_way" => Ok(Some(Self::OneWay)),
+            "crumbling" => Ok(Some(Self::Crumbling)),
+            "bounce" => Ok(Some(Self::Bounce)),
+            kind => Err(ObjectError::UnknownPlatformKind(kind.to_string())),
+        }
+    }
+}
+
+/// Parses the `breath` property of a water zone, the seconds the player can swim in it
+///
+/// The breath is unlimited without the property.
+pub fn breath_limit(properties: &tiled::Properties) -> Result<Option<f32>, ObjectError> {
+    let breath = match properties.get(BREATH_PROPERTY) {
+        None => return Ok(None),
+        Some(tiled::PropertyValue::FloatValue(breath)) => *breath,
+        Some(tiled::PropertyValue::IntValue(breath)) => *breath as f32,
+        Some(_) => return Err(ObjectError::InvalidBreath),
+    };
+
+    if breath > 0.0 {
+        Ok(Some(breath))
+    } else {
+        Err(ObjectError::InvalidBreath)
+    }
+}
+
+/// Positions carried by a position sensor, read from the custom properties of its Tiled object:
+///
+/// - `start_x`, `start_y`: position where the triggered element spawns
+/// - `end_x`, `end_y`: position where the triggered element exits
+/// - `disable_next_collision`: the sensor triggers only once (until restart)
+///
+/// Positions are given in Tiled coordinates and stored in Bevy coordinates. A missing position
+/// is `Vec2::ZERO`.
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub struct SensorValues {
+    pub start_pos: Vec2,
+    pub end_pos: Vec2,
+    pub disable_next_collision: bool,
+}
+
+/// What happens when the player reaches a position sensor
+///
+/// Positions are in Bevy coordinates.
+#[derive(Component, Clone, Debug, PartialEq)]
+pub enum SensorTrigger {
+    /// The player can leave the level
+    Exit,
+    /// Respawn point, the player position when it is `None`
+    Checkpoint {
+        position: Option<Vec2>,
+    },
+    Bat {
+        spawn_pos: Vec2,
+        exit_pos: Vec2,
+    },
+    Rock {
+        spawn_pos: Vec2,
+    },
+    Pterodactyl {
+        spawn_pos: Vec2,
+        exit_pos: Vec2,
+    },
+    /// A pterodactyl throwing a rock at the player
+    PterodactylAttack {
+        spawn_pos: Vec2,
+        exit_pos: Vec2,
+    },
+    /// Attacking pterodactyls spawned at the height of the player
+    PterodactylWave {
+        count: usize,
+        exit_pos: Vec2,
+    },
+    /// The volcano throws fireballs from `spawn_pos`
+    VolcanoEruption {
+        spawn_pos: Vec2,
+    },
+    /// The volcano only shakes the camera
+    VolcanoRumble,
+}
+
+/// Kind of a [`SensorTrigger`], handlers are registered by kind
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TriggerKind {
+    Exit,
+    Checkpoint,
+    Bat,
+    Rock,
+    Pterodactyl,
+    PterodactylAttack,
+    PterodactylWave,
+    VolcanoEruption,
+    VolcanoRumble,
+}
+
+impl SensorTrigger {
+    /// Parses the trigger of a position sensor from its name and values
+    ///
+    /// Longer prefixes are checked first: `pterodactyl_attack` and `pterodactyl_wave_` before
+    /// `pterodactyl`, `volcano_rumble` before `volcano`.
+    pub fn from_sensor(name: &str, values: &SensorValues) -> Result<Self, TriggerError> {
+        let SensorValues {
+            start_pos: spawn_pos,
+            end_pos: exit_pos,
+            ..
+        } = *values;
+
+        if name.starts_with("exit") {
+            Ok(Self::Exit)
+        } else if name.starts_with("checkpoint") {
+            Ok(Self::Checkpoint {
+                position: (spawn_pos != Vec2::ZERO).then_some(spawn_pos),
+            })
+        } else if name.starts_with("bat") {
+            Ok(Self::Bat {
+                spawn_pos,
+                exit_pos,
+            })
+        } else if name.starts_with("rock") {
+            Ok(Self::Rock { spawn_pos })
+        } else if name.starts_with("pterodactyl_attack") {
+            Ok(Self::PterodactylAttack {
+                spawn_pos,
+                exit_pos,
+            })
+        } else if let Some(wave) = name.strip_prefix("pterodactyl_wave_") {
+            let count = wave
+                .split('_')
+                .next()
+                .and_then(|count| count.parse().ok())
+                .ok_or(TriggerError::InvalidWaveCount)?;
+            Ok(Self::PterodactylWave { count, exit_pos })
+        } else if name.starts_with("pterodactyl") {
+            Ok(Self::Pterodactyl {
+                spawn_pos,
+                exit_pos,
+            })
+        } else if name.starts_with("volcano_rumble") {
+            Ok(Self::VolcanoRumble)
+        } else if name.starts_with("volcano") {
+            Ok(Self::VolcanoEruption { spawn_pos })
+        } else {
+            Err(TriggerError::Unknown)
+        }
+    }
+
+    pub fn kind(&self) -> TriggerKind {
+        match self {
+            Self::Exit => TriggerKind::Exit,
+            Self::Checkpoint { .. } => TriggerKind::Checkpoint,
+            Self::Bat { .. } => TriggerKind::Bat,
+            Self::Rock { .. } => TriggerKind::Rock,
+            Self::Pterodactyl { .. } => TriggerKind::Pterodactyl,
+            Self::PterodactylAttack { .. } => TriggerKind::PterodactylAttack,
+            Self::PterodactylWave { .. } => TriggerKind::PterodactylWave,
+            Self::VolcanoEruption { .. } => TriggerKind::VolcanoEruption,
+            Self::VolcanoRumble => TriggerKind::VolcanoRumble,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn values() -> SensorValues {
+        SensorValues {
+            start_pos: Vec2::new(10.0, 20.0),
+            end_pos: Vec2::new(30.0, 40.0),
+            disable_next_collision: false,
+        }
+    }
+
+    fn kind(name: &str) -> Result<TriggerKind, TriggerError> {
+        SensorTrigger::from_sensor(name, &values()).map(|trigger| trigger.kind())
+    }
+
+    #[test]
+    fn test_trigger_kinds() {
+        assert_eq!(kind("exit01"), Ok(TriggerKind::Exit));
+        assert_eq!(kind("checkpoint01"), Ok(TriggerKind::Checkpoint));
+        assert_eq!(kind("bat01"), Ok(TriggerKind::Bat));
+        assert_eq!(kind("rock01"), Ok(TriggerKind::Rock));
+        assert_eq!(kind("unknown01"), Err(TriggerError::Unknown));
+    }
+
+    #[test]
+    fn test_longer_prefixes_first() {
+        assert_eq!(kind("pterodactyl01"), Ok(TriggerKind::Pterodactyl));
+        assert_eq!(
+            kind("pterodactyl_attack01"),
+            Ok(TriggerKind::PterodactylAttack)
+        );
+        assert_eq!(
+            SensorTrigger::from_sensor("pterodactyl_wave_3_01", &values()),
+            Ok(SensorTrigger::PterodactylWave {
+                count: 3,
+                exit_pos: Vec2::new(30.0, 40.0),
+            })
+        );
+        assert_eq!(kind("volcano01"), Ok(TriggerKind::VolcanoEruption));
+        assert_eq!(kind("volcano_rumble01"), Ok(TriggerKind::VolcanoRumble));
+    }
+
+    #[test]
+    fn test_malformed_wave_count() {
+        for name in [
+            "pterodactyl_wave_",
+            "pterodactyl_wave_x_01",
+            "pterodactyl_wave__01",
+            "pterodactyl_wave_-1_01",
+        ] {
+            assert_eq!(
+                SensorTrigger::from_sensor(name, &values()),
+                Err(TriggerError::InvalidWaveCount),
+                "{name}"
+            );
+        }
+    }
+
+    fn properties(name: &str, value: tiled::PropertyValue) -> tiled::Properties {
+        tiled::Properties::from([(name.to_string(), value)])
+    }
+
+    #[test]
+    fn test_platform_kinds() {
+        let no_properties = tiled::Properties::new();
+        assert_eq!(PlatformKind::from_object("", &no_properties), Ok(None));
+        assert_eq!(
+            PlatformKind::from_object("one_way", &no_properties),
+            Ok(Some(PlatformKind::OneWay))
+        );
+        assert_eq!(
+            PlatformKind::from_object(
+                "",
+                &properties(
+                    "platform",
+                    tiled::PropertyValue::StringValue("crumbling".to_string())
+                )
+            ),
+            Ok(Some(PlatformKind::Crumbling))
+        );
+        // The class wins over the property.
+        assert_eq!(
+            PlatformKind::from_object(
+                "bounce",
+                &properties(
+                    "platform",
+                    tiled::PropertyValue::StringValue("crumbling".to_string())
+                )
+            ),
+            Ok(Some(PlatformKind::Bounce))
+        );
+        assert_eq!(
+            PlatformKind::from_object("sticky", &no_properties),
+            Err(ObjectError::UnknownPlatformKind("sticky".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_breath_limit() {
+        assert_eq!(breath_limit(&tiled::Properties::new()), Ok(None));
+        assert_eq!(
+            breath_limit(&properties("breath", tiled::PropertyValue::FloatValue(5.5))),
+            Ok(Some(5.5))
+        );
+        assert_eq!(
+            breath_limit(&properties("breath", tiled::PropertyValue::IntValue(3))),
+            Ok(Some(3.0))
+        );
+        assert_eq!(
+            breath_limit(&properties("breath", tiled::PropertyValue::FloatValue(0.0))),
+            Err(ObjectError::InvalidBreath)
+        );
+        assert_eq!(
+            breath_limit(&properties(
+                "breath",
+                tiled::PropertyValue::StringValue("long".to_string())
+            )),
+            Err(ObjectError::InvalidBreath)
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_position() {
+        assert_eq!(
+            SensorTrigger::from_sensor("checkpoint01", &values()),
+            Ok(SensorTrigger::Checkpoint {
+                position: Some(Vec2::new(10.0, 20.0)),
+            })
+        );
+        assert_eq!(
+            SensorTrigger::from_sensor("checkpoint01", &SensorValues::default()),
+            Ok(SensorTrigger::Checkpoint { position: None })
+        );
+    }
+}
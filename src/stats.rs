@@ -0,0 +1,198 @@
+// Statistics of the current level, displayed in the results screen when the level is finished.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use bevy_fluent::{BundleAsset, Locale};
+
+use crate::{
+    assets::RockRunAssets,
+    coregame::{
+        level::CurrentLevel,
+        localization::{convert_to_fluent_args, get_translation},
+        menu::DrawBlinkTimer,
+        state::{AppState, ForState},
+    },
+    events::{EnigmaResult, KeyCollision, LifeEvent, NutCollision},
+};
+
+#[derive(Resource, Default, Debug)]
+pub struct LevelStats {
+    pub time: f32,
+    pub lives_lost: u32,
+    pub extra_lives: u32,
+    pub nuts: u32,
+    pub keys: u32,
+    pub enigmas_first_try: u32,
+    pub enigmas_retried: u32,
+    /// Wrong answers given to each enigma not solved yet
+    enigma_failures: HashMap<String, u32>,
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::GameCreate), reset_stats)
+            .add_systems(OnEnter(AppState::NextLevel), reset_stats)
+            .add_systems(OnEnter(AppState::FinishLevel), results_screen)
+            .add_systems(
+                Update,
+                update_time
+                    .run_if(in_state(AppState::GameRunning).or(in_state(AppState::GameMessage))),
+            )
+            .add_systems(Update, update_stats)
+            .insert_resource(LevelStats::default());
+    }
+}
+
+fn reset_stats(mut stats: ResMut<LevelStats>) {
+    *stats = LevelStats::default();
+}
+
+/// Pauses are not counted
+fn update_time(time: Res<Time>, mut stats: ResMut<LevelStats>) {
+    stats.time += time.delta_secs();
+}
+
+fn update_stats(
+    mut stats: ResMut<LevelStats>,
+    mut life_events: EventReader<LifeEvent>,
+    mut nut_collisions: EventReader<NutCollision>,
+    mut key_collisions: EventReader<KeyCollision>,
+    mut enigma_results: EventReader<EnigmaResult>,
+) {
+    for ev in life_events.read() {
+        match ev {
+            LifeEvent::Win => stats.extra_lives += 1,
+            LifeEvent::Lost => stats.lives_lost += 1,
+        }
+    }
+
+    stats.nuts += nut_collisions.read().count() as u32;
+    stats.keys += key_collisions.read().count() as u32;
+
+    for ev in enigma_results.read() {
+        match ev {
+            EnigmaResult::Correct(enigma) => match stats.enigma_failures.remove(enigma) {
+                Some(_) => stats.enigmas_retried += 1,
+                None => stats.enigmas_first_try += 1,
+            },
+            EnigmaResult::Incorrect(enigma) => {
+                *stats.enigma_failures.entry(enigma.clone()).or_default() += 1;
+            }
+        }
+    }
+}
+
+fn format_time(seconds: f32) -> String {
+    let seconds = seconds as u32;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn results_screen(
+    mut commands: Commands,
+    rock_run_assets: Res<RockRunAssets>,
+    locale: Res<Locale>,
+    assets: Res<Assets<BundleAsset>>,
+    current_level: Res<CurrentLevel>,
+    stats: Res<LevelStats>,
+) {
+    info!("results_screen {:?}", stats);
+
+    let lines = [
+        ("results_time", vec![("time", format_time(stats.time))]),
+        (
+            "results_lives_lost",
+            vec![("count", stats.lives_lost.to_string())],
+        ),
+        (
+            "results_extra_lives",
+            vec![("count", stats.extra_lives.to_string())],
+        ),
+        ("results_nuts", vec![("count", stats.nuts.to_string())]),
+        ("results_keys", vec![("count", stats.keys.to_string())]),
+        (
+            "results_enigmas",
+            vec![
+                ("first_try", stats.enigmas_first_try.to_string()),
+                ("retried", stats.enigmas_retried.to_string()),
+            ],
+        ),
+    ];
+
+    let translate = |message: &str, args: Vec<(&str, String)>| {
+        get_translation(
+            &locale,
+            &assets,
+            &rock_run_assets,
+            message,
+            convert_to_fluent_args(Some(
+                args.into_iter()
+                    .map(|(name, value)| (name.to_string(), value))
+                    .collect(),
+            ))
+            .as_ref(),
+        )
+    };
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(15.0),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK),
+            ForState {
+                states: vec![AppState::FinishLevel],
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(translate(
+                    "results_title",
+                    vec![("current_level", current_level.id.to_string())],
+                )),
+                TextFont {
+                    font: rock_run_assets.cute_dino_font.clone(),
+                    font_size: 60.0,
+                    ..default()
+                },
+                TextColor(Color::srgb_u8(0xF4, 0x78, 0x04)),
+                Node {
+                    margin: UiRect::bottom(Val::Px(30.0)),
+                    ..default()
+                },
+            ));
+
+            for (message, args) in lines {
+                parent.spawn((
+                    Text::new(translate(message, args)),
+                    TextFont {
+                        font: rock_run_assets.cute_dino_font.clone(),
+                        font_size: 35.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb_u8(0xF8, 0xE4, 0x73)),
+                ));
+            }
+
+            parent.spawn((
+                Text::new(translate("results_continue", vec![])),
+                TextFont {
+                    font: rock_run_assets.cute_dino_font.clone(),
+                    font_size: 30.0,
+                    ..default()
+                },
+                TextColor(Color::srgb_u8(0xD3, 0xCD, 0x39)),
+                Node {
+                    margin: UiRect::top(Val::Px(30.0)),
+                    ..default()
+                },
+                DrawBlinkTimer(Timer::from_seconds(0.5, TimerMode::Repeating)),
+            ));
+        });
+}